@@ -23,6 +23,10 @@ use crate::loaders::{FontLoader, FontSize, Scaling};
 use crate::renderer::InterfaceRenderer;
 
 impl korangar_interface::application::ColorTrait for Color {
+    fn rgba_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Color::rgba_u8(red, green, blue, alpha)
+    }
+
     fn is_transparent(&self) -> bool {
         const TRANSPARENCY_THRESHOLD: f32 = 0.999;
         self.alpha < TRANSPARENCY_THRESHOLD
@@ -68,8 +68,8 @@ impl Hotbar {
                     })
                     .unwrap_or(HotkeyData::UNBOUND);
 
-                let _ = networking_system.set_hotkey_data(HotbarTab(0), destination_slot, first_data);
-                let _ = networking_system.set_hotkey_data(HotbarTab(0), source_slot, second_data);
+                let _ =
+                    networking_system.set_multiple_hotkey_data(HotbarTab(0), [(destination_slot, first_data), (source_slot, second_data)]);
 
                 skills[source_slot.0 as usize] = second;
                 skills[destination_slot.0 as usize] = first;
@@ -5,7 +5,7 @@ use std::cell::Ref;
 
 use korangar_interface::state::{PlainRemote, PlainTrackedState, TrackedState, TrackedStateExt, ValueState};
 use korangar_networking::{InventoryItem, InventoryItemDetails, NoMetadata};
-use ragnarok_packets::{EquipPosition, InventoryIndex};
+use ragnarok_packets::{EquipPosition, EquippableSwitchItemInformation, InventoryIndex};
 
 pub use self::hotbar::Hotbar;
 pub use self::skills::{Skill, SkillTree};
@@ -14,6 +14,7 @@ use crate::loaders::{ResourceMetadata, ScriptLoader, TextureLoader};
 #[derive(Default)]
 pub struct Inventory {
     items: PlainTrackedState<Vec<InventoryItem<ResourceMetadata>>>,
+    equip_switch_items: PlainTrackedState<Vec<EquippableSwitchItemInformation>>,
 }
 
 impl Inventory {
@@ -76,6 +77,21 @@ impl Inventory {
         });
     }
 
+    pub fn set_equip_switch_items(&mut self, items: Vec<EquippableSwitchItemInformation>) {
+        self.equip_switch_items.set(items);
+    }
+
+    pub fn add_equip_switch_item(&mut self, index: InventoryIndex, position: EquipPosition) {
+        self.equip_switch_items.mutate(|items| {
+            items.retain(|item| item.index != index);
+            items.push(EquippableSwitchItemInformation { index, position });
+        });
+    }
+
+    pub fn remove_equip_switch_item(&mut self, index: InventoryIndex) {
+        self.equip_switch_items.mutate(|items| items.retain(|item| item.index != index));
+    }
+
     pub fn get_items(&self) -> Ref<'_, Vec<InventoryItem<ResourceMetadata>>> {
         self.items.get()
     }
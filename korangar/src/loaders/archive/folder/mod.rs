@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 
 use walkdir::WalkDir;
 
-use super::{Archive, Writable};
+use super::{Archive, ArchiveError, Writable};
 
 pub struct FolderArchive {
     folder_path: PathBuf,
@@ -51,11 +51,11 @@ impl FolderArchive {
 }
 
 impl Archive for FolderArchive {
-    fn from_path(path: &Path) -> Self {
+    fn from_path(path: &Path) -> Result<Self, ArchiveError> {
         let folder_path = PathBuf::from(path);
         let file_mapping = Self::load_mapping(&folder_path);
 
-        Self { folder_path, file_mapping }
+        Ok(Self { folder_path, file_mapping })
     }
 
     fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>> {
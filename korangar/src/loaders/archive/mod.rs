@@ -1,10 +1,42 @@
 pub mod folder;
 pub mod native;
 
+use std::fmt::{Display, Formatter};
 use std::path::Path;
 
+/// The reason why an archive could not be opened.
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// The archive could not be read from disk.
+    Io(std::io::Error),
+    /// The archive doesn't start with the GRF signature, so it's either
+    /// corrupt or not a GRF file at all.
+    BadMagic,
+    /// The archive uses a GRF version that is not supported.
+    UnsupportedVersion(u32),
+    /// The file table of the archive can't be read.
+    CorruptFileTable,
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Io(error) => write!(formatter, "can't read archive: {error}"),
+            ArchiveError::BadMagic => write!(formatter, "archive is not a valid GRF file"),
+            ArchiveError::UnsupportedVersion(version) => write!(formatter, "unsupported GRF version 0x{version:X}"),
+            ArchiveError::CorruptFileTable => write!(formatter, "file table of the archive is corrupt"),
+        }
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(error: std::io::Error) -> Self {
+        ArchiveError::Io(error)
+    }
+}
+
 pub trait Archive: Send + Sync {
-    fn from_path(path: &Path) -> Self
+    fn from_path(path: &Path) -> Result<Self, ArchiveError>
     where
         Self: Sized;
 
@@ -59,7 +59,7 @@ impl Writable for NativeArchiveBuilder {
         let file_table_offset = self.data.len() as u32;
         let reserved_files = 0;
         let raw_file_count = self.file_table.len() as u32 + 7;
-        let version = 0x200;
+        let version = Header::SUPPORTED_VERSION;
         let file_header = Header::new(file_table_offset, reserved_files, raw_file_count, version);
 
         let mut bytes = file_header.to_bytes().unwrap();
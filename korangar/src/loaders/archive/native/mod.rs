@@ -16,7 +16,7 @@ use ragnarok_formats::archive::{AssetTable, FileTableRow, Header};
 
 pub use self::builder::NativeArchiveBuilder;
 use crate::loaders::archive::native::mixcrypt::decrypt_file;
-use crate::loaders::archive::Archive;
+use crate::loaders::archive::{Archive, ArchiveError};
 
 /// Represents a GRF file. GRF Files are an archive to store game assets.
 /// Each GRF contains a [`Header`] with metadata (number of files, size,
@@ -28,30 +28,48 @@ pub struct NativeArchive {
     file_handle: Mutex<File>,
 }
 
+impl NativeArchive {
+    /// Reads the header of the archive and makes sure that it is a GRF file
+    /// with a supported version, so invalid archives are rejected before any
+    /// of their entries are read.
+    fn parse_header(bytes: &[u8]) -> Result<Header, ArchiveError> {
+        let file_header = Header::from_bytes(&mut ByteReader::without_metadata(bytes)).map_err(|_| ArchiveError::BadMagic)?;
+
+        if file_header.version != Header::SUPPORTED_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(file_header.version));
+        }
+
+        if (file_header.file_count as u64) < file_header.reserved_files as u64 + Header::FILE_OFFSET as u64 {
+            return Err(ArchiveError::CorruptFileTable);
+        }
+
+        Ok(file_header)
+    }
+}
+
 impl Archive for NativeArchive {
-    fn from_path(path: &Path) -> Self {
+    fn from_path(path: &Path) -> Result<Self, ArchiveError> {
         #[cfg(feature = "debug")]
         let timer = Timer::new_dynamic(format!("load game data from {}", path.display().magenta()));
-        let mut file = File::open(path).unwrap();
+        let mut file = File::open(path)?;
 
         let mut file_header_buffer = vec![0u8; Header::size_in_bytes()];
-        file.read_exact(&mut file_header_buffer).unwrap();
-        let file_header = Header::from_bytes(&mut ByteReader::without_metadata(&file_header_buffer)).unwrap();
-
-        assert_eq!(file_header.version, 0x200, "invalid grf version");
+        file.read_exact(&mut file_header_buffer)?;
+        let file_header = Self::parse_header(&file_header_buffer)?;
 
-        let _ = file.seek(SeekFrom::Current(file_header.file_table_offset as i64)).unwrap();
+        let _ = file.seek(SeekFrom::Current(file_header.file_table_offset as i64))?;
         let mut file_table_buffer = vec![0; AssetTable::size_in_bytes()];
 
-        file.read_exact(&mut file_table_buffer).unwrap();
-        let file_table = AssetTable::from_bytes(&mut ByteReader::without_metadata(&file_table_buffer)).expect("can't read file table");
+        file.read_exact(&mut file_table_buffer)?;
+        let file_table =
+            AssetTable::from_bytes(&mut ByteReader::without_metadata(&file_table_buffer)).map_err(|_| ArchiveError::CorruptFileTable)?;
 
         let mut compressed_file_table_buffer = vec![0u8; file_table.compressed_size as usize];
-        file.read_exact(&mut compressed_file_table_buffer).unwrap();
+        file.read_exact(&mut compressed_file_table_buffer)?;
 
         let mut decoder = ZlibDecoder::new(compressed_file_table_buffer.as_slice());
         let mut decompressed = Vec::with_capacity(file_table.uncompressed_size as usize);
-        decoder.read_to_end(&mut decompressed).expect("can't decompress file table");
+        decoder.read_to_end(&mut decompressed).map_err(|_| ArchiveError::CorruptFileTable)?;
 
         let file_count = file_header.get_file_count();
 
@@ -59,7 +77,7 @@ impl Archive for NativeArchive {
         let mut assets = HashMap::with_capacity(file_count);
 
         for _index in 0..file_count {
-            let file_information = FileTableRow::from_bytes(&mut file_table_byte_reader).unwrap();
+            let file_information = FileTableRow::from_bytes(&mut file_table_byte_reader).map_err(|_| ArchiveError::CorruptFileTable)?;
             let file_name = file_information.file_name.to_lowercase();
 
             assets.insert(file_name, file_information);
@@ -70,10 +88,10 @@ impl Archive for NativeArchive {
 
         // TODO: only take 64..? bytes so that loaded game archives can be extended
         //       as well.
-        Self {
+        Ok(Self {
             file_table: assets,
             file_handle: Mutex::new(file),
-        }
+        })
     }
 
     fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>> {
@@ -112,3 +130,48 @@ impl Archive for NativeArchive {
         files.extend(found_files);
     }
 }
+
+#[cfg(test)]
+mod header {
+    use ragnarok_bytes::ToBytes;
+    use ragnarok_formats::archive::Header;
+
+    use super::NativeArchive;
+    use crate::loaders::archive::ArchiveError;
+
+    #[test]
+    fn valid_header() {
+        let bytes = Header::new(0, 0, 10, Header::SUPPORTED_VERSION).to_bytes().unwrap();
+        let file_header = NativeArchive::parse_header(&bytes).unwrap();
+
+        assert_eq!(file_header.get_file_count(), 3);
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut bytes = Header::new(0, 0, 10, Header::SUPPORTED_VERSION).to_bytes().unwrap();
+        bytes[0] = b'X';
+
+        assert!(matches!(NativeArchive::parse_header(&bytes), Err(ArchiveError::BadMagic)));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let bytes = Header::new(0, 0, 10, 0x103).to_bytes().unwrap();
+
+        assert!(matches!(
+            NativeArchive::parse_header(&bytes),
+            Err(ArchiveError::UnsupportedVersion(0x103))
+        ));
+    }
+
+    #[test]
+    fn corrupt_file_count() {
+        let bytes = Header::new(0, 5, 10, Header::SUPPORTED_VERSION).to_bytes().unwrap();
+
+        assert!(matches!(
+            NativeArchive::parse_header(&bytes),
+            Err(ArchiveError::CorruptFileTable)
+        ));
+    }
+}
@@ -14,7 +14,7 @@ use korangar_util::{FileLoader, FileNotFoundError};
 use self::list::GameArchiveList;
 use super::archive::folder::FolderArchive;
 use super::archive::native::{NativeArchive, NativeArchiveBuilder};
-use super::archive::{Archive, ArchiveType, Writable};
+use super::archive::{Archive, ArchiveError, ArchiveType, Writable};
 
 #[cfg(feature = "patched_as_folder")]
 const LUA_GRF_FILE_NAME: &str = "lua_files/";
@@ -27,9 +27,19 @@ const LUA_GRF_FILE_NAME: &str = "lua_files.grf";
 /// [`Archive`]:
 /// - [`NativeArchive`] - Retrieve assets from GRF files.
 /// - [`FolderArchive`] - Retrieve assets from an OS folder.
+///
+/// Archives that are loaded later take precedence over archives that were
+/// loaded before, so a custom GRF can override files of the base `data.grf`
+/// by being listed after it.
 #[derive(Default)]
 pub struct GameFileLoader {
-    archives: RwLock<Vec<Box<dyn Archive>>>,
+    /// Sorted from the highest to the lowest precedence.
+    archives: RwLock<Vec<LoadedArchive>>,
+}
+
+struct LoadedArchive {
+    path: String,
+    archive: Box<dyn Archive>,
 }
 
 impl FileLoader for GameFileLoader {
@@ -39,14 +49,30 @@ impl FileLoader for GameFileLoader {
             .read()
             .unwrap()
             .iter()
-            .find_map(|archive| archive.get_file_by_path(&lowercase_path))
+            .find_map(|loaded| loaded.archive.get_file_by_path(&lowercase_path))
             .ok_or_else(|| FileNotFoundError::new(path.to_owned()))
     }
 }
 
 impl GameFileLoader {
-    fn add_archive(&self, game_archive: Box<dyn Archive>) {
-        self.archives.write().unwrap().insert(0, game_archive);
+    fn add_archive(&self, path: &str, game_archive: Box<dyn Archive>) {
+        self.archives.write().unwrap().insert(0, LoadedArchive {
+            path: path.to_owned(),
+            archive: game_archive,
+        });
+    }
+
+    /// Returns the path of the archive that a file is loaded from, or `None`
+    /// if none of the archives contain the file. Useful for finding out which
+    /// archive overrides a file.
+    pub fn resolve_archive(&self, path: &str) -> Option<String> {
+        let lowercase_path = path.to_lowercase();
+        self.archives
+            .read()
+            .unwrap()
+            .iter()
+            .find(|loaded| loaded.archive.get_file_by_path(&lowercase_path).is_some())
+            .map(|loaded| loaded.path.clone())
     }
 
     fn get_archive_type_by_path(path: &Path) -> ArchiveType {
@@ -64,10 +90,12 @@ impl GameFileLoader {
     fn load_archive_from_path(path: &str) -> Box<dyn Archive> {
         let path = Path::new(path);
 
-        match GameFileLoader::get_archive_type_by_path(path) {
-            ArchiveType::Folder => Box::new(FolderArchive::from_path(path)),
-            ArchiveType::Native => Box::new(NativeArchive::from_path(path)),
-        }
+        let archive: Result<Box<dyn Archive>, ArchiveError> = match GameFileLoader::get_archive_type_by_path(path) {
+            ArchiveType::Folder => FolderArchive::from_path(path).map(|archive| Box::new(archive) as Box<dyn Archive>),
+            ArchiveType::Native => NativeArchive::from_path(path).map(|archive| Box::new(archive) as Box<dyn Archive>),
+        };
+
+        archive.unwrap_or_else(|error| panic!("failed to load game archive {}: {}", path.display(), error))
     }
 
     pub fn load_archives_from_settings(&self) {
@@ -78,7 +106,7 @@ impl GameFileLoader {
 
         game_archive_list.archives.iter().for_each(|path| {
             let game_archive = Self::load_archive_from_path(path);
-            self.add_archive(game_archive);
+            self.add_archive(path, game_archive);
         });
 
         #[cfg(feature = "debug")]
@@ -101,7 +129,7 @@ impl GameFileLoader {
         }
 
         let lua_archive = Self::load_archive_from_path(LUA_GRF_FILE_NAME);
-        self.add_archive(lua_archive);
+        self.add_archive(LUA_GRF_FILE_NAME, lua_archive);
     }
 
     fn patch_lua_files(&self) {
@@ -114,11 +142,11 @@ impl GameFileLoader {
             .read()
             .unwrap()
             .iter()
-            .for_each(|archive| archive.get_files_with_extension(&mut lua_files, LUA_BYTECODE_EXTENSION));
+            .for_each(|loaded| loaded.archive.get_files_with_extension(&mut lua_files, LUA_BYTECODE_EXTENSION));
 
         let path = Path::new(LUA_GRF_FILE_NAME);
         let mut lua_archive: Box<dyn Writable> = match GameFileLoader::get_archive_type_by_path(path) {
-            ArchiveType::Folder => Box::new(FolderArchive::from_path(path)),
+            ArchiveType::Folder => Box::new(FolderArchive::from_path(path).expect("can't create lua archive")),
             ArchiveType::Native => Box::new(NativeArchiveBuilder::from_path(path)),
         };
 
@@ -176,3 +204,60 @@ impl GameFileLoader {
         lua_archive.save();
     }
 }
+
+#[cfg(test)]
+mod precedence {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use korangar_util::FileLoader;
+
+    use super::GameFileLoader;
+    use crate::loaders::archive::{Archive, ArchiveError};
+
+    struct MemoryArchive(HashMap<String, Vec<u8>>);
+
+    impl MemoryArchive {
+        fn new(files: &[(&str, &str)]) -> Box<Self> {
+            Box::new(Self(
+                files
+                    .iter()
+                    .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+                    .collect(),
+            ))
+        }
+    }
+
+    impl Archive for MemoryArchive {
+        fn from_path(_path: &Path) -> Result<Self, ArchiveError> {
+            Err(ArchiveError::Io(std::io::ErrorKind::Unsupported.into()))
+        }
+
+        fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>> {
+            self.0.get(asset_path).cloned()
+        }
+
+        fn get_files_with_extension(&self, files: &mut Vec<String>, extension: &str) {
+            files.extend(self.0.keys().filter(|path| path.ends_with(extension)).cloned());
+        }
+    }
+
+    #[test]
+    fn later_archive_overrides() {
+        let game_file_loader = GameFileLoader::default();
+
+        game_file_loader.add_archive(
+            "data.grf",
+            MemoryArchive::new(&[("data\\a.txt", "base"), ("data\\b.txt", "base")]),
+        );
+        game_file_loader.add_archive("custom.grf", MemoryArchive::new(&[("data\\a.txt", "custom")]));
+
+        assert_eq!(game_file_loader.get("data\\a.txt").unwrap(), b"custom");
+        assert_eq!(game_file_loader.get("DATA\\B.TXT").unwrap(), b"base");
+        assert!(game_file_loader.get("data\\c.txt").is_err());
+
+        assert_eq!(game_file_loader.resolve_archive("data\\a.txt").as_deref(), Some("custom.grf"));
+        assert_eq!(game_file_loader.resolve_archive("data\\b.txt").as_deref(), Some("data.grf"));
+        assert_eq!(game_file_loader.resolve_archive("data\\c.txt"), None);
+    }
+}
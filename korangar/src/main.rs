@@ -41,12 +41,13 @@ use std::net::{SocketAddr, ToSocketAddrs};
 use std::rc::Rc;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::time::Instant;
 
 use cgmath::{Vector2, Vector3};
 #[cfg(feature = "debug")]
 use graphics::RenderSettings;
 use image::{EncodableLayout, ImageFormat, ImageReader};
-use korangar_audio::{AudioEngine, SoundEffectKey};
+use korangar_audio::{AudioEngine, SoundCategory, SoundEffectKey};
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{print_debug, Colorize};
 #[cfg(feature = "debug")]
@@ -59,8 +60,8 @@ use korangar_interface::state::{
 };
 use korangar_interface::Interface;
 use korangar_networking::{
-    DisconnectReason, HotkeyState, LoginServerLoginData, MessageColor, NetworkEvent, NetworkEventBuffer, NetworkingSystem, SellItem,
-    ShopItem,
+    DialogButtonKind, DisconnectReason, HotkeyState, LoginServerLoginData, MapSize, MessageColor, NetworkEvent, NetworkEventBuffer,
+    NetworkingSystem, SellItem, ShopItem, UnifiedBuyItemsResult,
 };
 use korangar_util::pathing::PathFinder;
 #[cfg(feature = "debug")]
@@ -68,8 +69,8 @@ use korangar_util::texture_atlas::AtlasAllocation;
 #[cfg(not(feature = "debug"))]
 use ragnarok_packets::handler::NoPacketCallback;
 use ragnarok_packets::{
-    BuyShopItemsResult, CharacterId, CharacterInformation, CharacterServerInformation, Direction, DisappearanceReason, Friend, HotbarSlot,
-    SellItemsResult, SkillId, SkillType, TilePosition, UnitId, WorldPosition,
+    CharacterId, CharacterInformation, CharacterServerInformation, CombatResult, DisappearanceReason, ExperienceSource, ExperienceType,
+    Friend, HotbarSlot, SellItemsResult, SkillFailureCause, SkillId, SkillType, TilePosition, UnitId,
 };
 use renderer::InterfaceRenderer;
 use settings::AudioSettings;
@@ -100,7 +101,7 @@ use crate::loaders::*;
 use crate::renderer::DebugMarkerRenderer;
 use crate::renderer::{AlignHorizontal, EffectRenderer, GameInterfaceRenderer};
 use crate::settings::{GraphicsSettings, LightingMode};
-use crate::system::GameTimer;
+use crate::system::{ExperienceSummary, GameTimer};
 use crate::world::*;
 
 const CLIENT_NAME: &str = "Korangar";
@@ -220,6 +221,7 @@ struct Client {
     dialog_system: DialogSystem,
     show_interface: bool,
     game_timer: GameTimer,
+    experience_summary: ExperienceSummary,
 
     #[cfg(feature = "debug")]
     debug_camera: DebugCamera,
@@ -369,6 +371,7 @@ impl Client {
 
             let audio_engine = Arc::new(AudioEngine::new(game_file_loader.clone()));
             audio_engine.set_background_music_volume(0.1);
+            audio_engine.warm_up(SPATIAL_SOUND_RANGE);
         });
 
         time_phase!("create resource managers", {
@@ -476,6 +479,7 @@ impl Client {
 
         time_phase!("initialize timer", {
             let game_timer = GameTimer::new();
+            let experience_summary = ExperienceSummary::default();
         });
 
         time_phase!("initialize camera", {
@@ -564,7 +568,7 @@ impl Client {
                 color: MessageColor::Server,
             }]);
 
-            let main_menu_click_sound_effect = audio_engine.load(MAIN_MENU_CLICK_SOUND_EFFECT);
+            let main_menu_click_sound_effect = audio_engine.register_ui_sound(MAIN_MENU_CLICK_SOUND_EFFECT);
         });
 
         time_phase!("load default map", {
@@ -649,6 +653,7 @@ impl Client {
             dialog_system,
             show_interface,
             game_timer,
+            experience_summary,
             #[cfg(feature = "debug")]
             debug_camera,
             start_camera,
@@ -799,11 +804,16 @@ impl Client {
 
         for event in self.network_event_buffer.drain() {
             match event {
+                NetworkEvent::Connecting { address: _address } => {
+                    #[cfg(feature = "debug")]
+                    print_debug!("connecting to {}", _address.to_string().magenta());
+                }
                 NetworkEvent::LoginServerConnected {
                     character_servers,
                     login_data,
                 } => {
-                    self.audio_engine.play_sound_effect(self.main_menu_click_sound_effect);
+                    self.audio_engine
+                        .play_sound_effect(self.main_menu_click_sound_effect, Some(SoundCategory::Interface), false);
 
                     self.saved_login_data = Some(login_data);
 
@@ -820,6 +830,18 @@ impl Client {
                     self.interface
                         .open_window(&self.application, &mut self.focus_state, &ErrorWindow::new(message.to_owned()));
                 }
+                NetworkEvent::LoginServerDisconnected {
+                    reason: DisconnectReason::ConnectTimedOut { address },
+                }
+                | NetworkEvent::CharacterServerDisconnected {
+                    reason: DisconnectReason::ConnectTimedOut { address },
+                } => {
+                    self.interface.open_window(
+                        &self.application,
+                        &mut self.focus_state,
+                        &ErrorWindow::new(format!("Connection to {address} timed out")),
+                    );
+                }
                 NetworkEvent::LoginServerDisconnected { reason } => {
                     if reason != DisconnectReason::ClosedByClient {
                         // TODO: Make this an on-screen popup.
@@ -852,7 +874,13 @@ impl Client {
                     }
                 }
                 NetworkEvent::MapServerDisconnected { reason } => {
-                    if reason != DisconnectReason::ClosedByClient {
+                    if let DisconnectReason::ConnectTimedOut { address } = reason {
+                        self.interface.open_window(
+                            &self.application,
+                            &mut self.focus_state,
+                            &ErrorWindow::new(format!("Connection to {address} timed out")),
+                        );
+                    } else if reason != DisconnectReason::ClosedByClient {
                         // TODO: Make this an on-screen popup.
                         #[cfg(feature = "debug")]
                         print_debug!("Disconnection from the map server with error");
@@ -898,11 +926,20 @@ impl Client {
                     self.directional_shadow_camera
                         .set_focus_point(cgmath::Point3::new(600.0, 0.0, 240.0));
                 }
-                NetworkEvent::ResurrectPlayer { entity_id } => {
-                    // If the resurrected player is us, close the resurrect window.
+                NetworkEvent::EntityResurrected { entity_id } => {
+                    if let Some(entity) = self.entities.iter_mut().find(|entity| entity.get_entity_id() == entity_id) {
+                        entity.set_idle(client_tick);
+                    }
+
+                    // If the resurrected player is us, close the resurrect window and resume
+                    // the background music of the current map.
                     if self.entities[0].get_entity_id() == entity_id {
                         self.interface
                             .close_window_with_class(&mut self.focus_state, RespawnWindow::WINDOW_CLASS);
+
+                        if let Some(map) = self.map.as_ref() {
+                            self.audio_engine.play_background_music_track(map.background_music_track_name());
+                        }
                     }
                 }
                 NetworkEvent::PlayerStandUp { entity_id } => {
@@ -912,7 +949,8 @@ impl Client {
                 }
                 NetworkEvent::AccountId(..) => {}
                 NetworkEvent::CharacterList { characters } => {
-                    self.audio_engine.play_sound_effect(self.main_menu_click_sound_effect);
+                    self.audio_engine
+                        .play_sound_effect(self.main_menu_click_sound_effect, Some(SoundCategory::Interface), false);
 
                     self.saved_characters.set(characters);
                     let character_selection_window = CharacterSelectionWindow::new(
@@ -941,7 +979,8 @@ impl Client {
                         .open_window(&self.application, &mut self.focus_state, &ErrorWindow::new(message.to_owned()))
                 }
                 NetworkEvent::CharacterSelected { login_data, .. } => {
-                    self.audio_engine.play_sound_effect(self.main_menu_click_sound_effect);
+                    self.audio_engine
+                        .play_sound_effect(self.main_menu_click_sound_effect, Some(SoundCategory::Interface), false);
 
                     let saved_login_data = self.saved_login_data.as_ref().unwrap();
                     self.networking_system.disconnect_from_character_server();
@@ -1010,6 +1049,33 @@ impl Client {
                         .open_window(&self.application, &mut self.focus_state, &ErrorWindow::new(message.to_owned()));
                 }
                 NetworkEvent::CharacterSlotSwitched => {}
+                NetworkEvent::QuestList { .. } => {}
+                // TODO: Draw the navigation path on the minimap.
+                NetworkEvent::Navigate { .. } => {}
+                // TODO: Decode and cache the emblem once guild emblems are rendered.
+                NetworkEvent::GuildEmblem { .. } => {}
+                NetworkEvent::SpecialEffect { .. } => {}
+                NetworkEvent::MapType { .. } => {}
+                NetworkEvent::SkillUseResult { result: Ok(()), .. } => {}
+                NetworkEvent::SkillUseResult { result: Err(cause), .. } => {
+                    let text = match cause {
+                        SkillFailureCause::InsufficientSpellPoints => "Not enough SP".to_string(),
+                        SkillFailureCause::InsufficientHealthPoints => "Not enough HP".to_string(),
+                        SkillFailureCause::InsufficientZeny => "Not enough Zeny".to_string(),
+                        SkillFailureCause::InsufficientItems => "Required items are missing".to_string(),
+                        cause => format!("Skill failed: {cause:?}"),
+                    };
+
+                    self.chat_messages.push(ChatMessage {
+                        text,
+                        color: MessageColor::Error,
+                    });
+                }
+                NetworkEvent::Pincode { .. } => {}
+                NetworkEvent::MapLoginSuccess { .. } => {}
+                NetworkEvent::EnteredGame { .. } => {}
+                NetworkEvent::EnterGameFailed { .. } => {}
+                NetworkEvent::SkillCooldown { .. } => {}
                 NetworkEvent::CharacterSlotSwitchFailed => {
                     self.interface.open_window(
                         &self.application,
@@ -1106,7 +1172,7 @@ impl Client {
                 NetworkEvent::UpdateClientTick { client_tick, received_at } => {
                     self.game_timer.set_client_tick(client_tick, received_at);
                 }
-                NetworkEvent::ChatMessage { text, color } => {
+                NetworkEvent::ChatMessage { text, color, .. } => {
                     self.chat_messages.push(ChatMessage { text, color });
                 }
                 NetworkEvent::UpdateEntityDetails(entity_id, name) => {
@@ -1116,15 +1182,25 @@ impl Client {
                         entity.set_details(name);
                     }
                 }
-                NetworkEvent::DamageEffect { entity_id, damage_amount } => {
+                NetworkEvent::DamageEffect {
+                    entity_id,
+                    damage_amount,
+                    combat_result,
+                } => {
                     let entity = self
                         .entities
                         .iter()
                         .find(|entity| entity.get_entity_id() == entity_id)
                         .unwrap_or(&self.entities[0]);
 
+                    let text = match combat_result {
+                        CombatResult::Miss => "Miss".to_owned(),
+                        CombatResult::LuckyDodge => "Lucky".to_owned(),
+                        _ => damage_amount.to_string(),
+                    };
+
                     self.particle_holder
-                        .spawn_particle(Box::new(DamageNumber::new(entity.get_position(), damage_amount.to_string())));
+                        .spawn_particle(Box::new(DamageNumber::new(entity.get_position(), text)));
                 }
                 NetworkEvent::HealEffect(entity_id, damage_amount) => {
                     let entity = self
@@ -1136,7 +1212,18 @@ impl Client {
                     self.particle_holder
                         .spawn_particle(Box::new(HealNumber::new(entity.get_position(), damage_amount.to_string())));
                 }
-                NetworkEvent::UpdateEntityHealth(entity_id, health_points, maximum_health_points) => {
+                NetworkEvent::ExperienceGained {
+                    amount,
+                    experience_type,
+                    source,
+                } => {
+                    self.experience_summary.add(amount, experience_type, source, Instant::now());
+                }
+                NetworkEvent::EntityHealth {
+                    entity_id,
+                    health_points,
+                    maximum_health_points,
+                } => {
                     let entity = self.entities.iter_mut().find(|entity| entity.get_entity_id() == entity_id);
 
                     if let Some(entity) = entity {
@@ -1155,9 +1242,25 @@ impl Client {
                         self.interface.open_window(&self.application, &mut self.focus_state, &dialog_window);
                     }
                 }
-                NetworkEvent::AddNextButton => self.dialog_system.add_next_button(),
-                NetworkEvent::AddCloseButton => self.dialog_system.add_close_button(),
+                NetworkEvent::NpcDialogButton { kind, .. } => match kind {
+                    DialogButtonKind::Next => self.dialog_system.add_next_button(),
+                    DialogButtonKind::Close => self.dialog_system.add_close_button(),
+                },
                 NetworkEvent::AddChoiceButtons(choices) => self.dialog_system.add_choice_buttons(choices),
+                NetworkEvent::DisplayImage { .. } => {
+                    // TODO: Show the illustration once the interface can
+                    // display images from the game files.
+                }
+                NetworkEvent::MinimapMarker { .. } => {
+                    // TODO: Add and remove the marker once there is a minimap.
+                }
+                NetworkEvent::ConfigurationChanged { .. } => {
+                    // TODO: Show the options in the equipment window.
+                }
+                NetworkEvent::InitialStatus(..) => {
+                    // The values the player entity keeps track of are also
+                    // sent individually as status updates.
+                }
                 NetworkEvent::AddQuestEffect(quest_effect) => {
                     if let Some(map) = self.map.as_ref() {
                         self.particle_holder.add_quest_icon(&self.texture_loader, map, quest_effect)
@@ -1189,6 +1292,25 @@ impl Client {
                 NetworkEvent::UpdateEquippedPosition { index, equipped_position } => {
                     self.player_inventory.update_equipped_position(index, equipped_position);
                 }
+                NetworkEvent::EquipSwitchUpdated(items) => {
+                    self.player_inventory.set_equip_switch_items(items);
+                }
+                NetworkEvent::EquipSwitchItemAdded { index, position } => {
+                    self.player_inventory.add_equip_switch_item(index, position);
+                }
+                NetworkEvent::EquipSwitchItemRemoved { index } => {
+                    self.player_inventory.remove_equip_switch_item(index);
+                }
+                NetworkEvent::FullEquipSwitchCompleted => {
+                    // NOTE: The map server sends the new equipped positions and
+                    // equip switch set separately.
+                }
+                NetworkEvent::FullEquipSwitchFailed => {
+                    self.chat_messages.push(ChatMessage {
+                        text: "Failed to switch equipment".to_owned(),
+                        color: MessageColor::Error,
+                    });
+                }
                 NetworkEvent::ChangeJob { account_id, job_id } => {
                     let entity = self
                         .entities
@@ -1223,9 +1345,33 @@ impl Client {
                         entity.get_entity_part_files(&self.script_loader),
                     );
                 }
+                NetworkEvent::SpriteChanged { .. } => {
+                    // TODO: Entities are only rendered with their body and head
+                    // sprites, so there is nothing to update for equipment yet.
+                }
                 NetworkEvent::LoggedOut => {
                     self.networking_system.disconnect_from_map_server();
                 }
+                NetworkEvent::LogoutDelayed { delay } => {
+                    self.chat_messages.push(ChatMessage {
+                        text: format!("Logging out in {} seconds.", delay.as_secs()),
+                        color: MessageColor::Information,
+                    });
+                }
+                NetworkEvent::LogoutFailed => {
+                    self.chat_messages.push(ChatMessage {
+                        text: "Failed to log out.".to_owned(),
+                        color: MessageColor::Error,
+                    });
+                }
+                NetworkEvent::LogoutCountdown { remaining_seconds } => {
+                    if remaining_seconds > 0 {
+                        self.chat_messages.push(ChatMessage {
+                            text: format!("{remaining_seconds}..."),
+                            color: MessageColor::Information,
+                        });
+                    }
+                }
                 NetworkEvent::FriendRequest { requestee } => {
                     self.interface
                         .open_window(&self.application, &mut self.focus_state, &FriendRequestWindow::new(requestee))
@@ -1237,6 +1383,27 @@ impl Client {
                 NetworkEvent::FriendAdded { friend } => {
                     self.friend_list.push((friend, LinkedElement::new()));
                 }
+                NetworkEvent::StorageOpened { .. }
+                | NetworkEvent::StorageItemCount { .. }
+                | NetworkEvent::StorageItemAdded { .. }
+                | NetworkEvent::StorageItemRemoved { .. }
+                | NetworkEvent::StorageClosed => {
+                    // TODO: Add a storage window.
+                }
+                NetworkEvent::VendingShopOpened { .. }
+                | NetworkEvent::VendingShopClosed { .. }
+                | NetworkEvent::VendingOpened { .. }
+                | NetworkEvent::VendingPurchaseCompleted { .. } => {
+                    // TODO: Show vending shop signs and add a window to buy
+                    // from vending shops.
+                }
+                NetworkEvent::MailInbox { .. } | NetworkEvent::MailOpened { .. } | NetworkEvent::MailSent { .. } => {
+                    // TODO: Add a mail window.
+                }
+                NetworkEvent::PartyUpdate { .. } => {
+                    // TODO: Show the party members in the interface and on the
+                    // minimap.
+                }
                 NetworkEvent::VisualEffect(path, entity_id) => {
                     let effect = self.effect_loader.get_or_load(path, &self.texture_loader).unwrap();
                     let frame_timer = effect.new_frame_timer();
@@ -1360,7 +1527,7 @@ impl Client {
                         .open_window(&self.application, &mut self.focus_state, &BuyOrSellWindow::new(shop_id));
                 }
                 NetworkEvent::BuyingCompleted { result } => match result {
-                    BuyShopItemsResult::Success => {
+                    UnifiedBuyItemsResult::Success => {
                         let _ = self.networking_system.close_shop();
 
                         self.interface
@@ -1368,9 +1535,17 @@ impl Client {
                         self.interface
                             .close_window_with_class(&mut self.focus_state, BuyCartWindow::WINDOW_CLASS);
                     }
-                    BuyShopItemsResult::Error => {
+                    result => {
+                        let text = match result {
+                            UnifiedBuyItemsResult::NotEnoughZeny => "Not enough zeny to buy items",
+                            UnifiedBuyItemsResult::WeightLimitExceeded => "Can't carry the weight of the items",
+                            UnifiedBuyItemsResult::TooManyItems => "Can't carry that many items",
+                            UnifiedBuyItemsResult::SoldOut => "Items are sold out",
+                            _ => "Failed to buy items",
+                        };
+
                         self.chat_messages.push(ChatMessage {
-                            text: "Failed to buy items".to_owned(),
+                            text: text.to_owned(),
                             color: MessageColor::Error,
                         });
                     }
@@ -1431,6 +1606,22 @@ impl Client {
             }
         }
 
+        for (experience_type, source, amount) in self.experience_summary.take_finished(Instant::now()) {
+            let experience_name = match experience_type {
+                ExperienceType::BaseExperience => "base",
+                ExperienceType::JobExperience => "job",
+            };
+            let source_suffix = match source {
+                ExperienceSource::Regular => "",
+                ExperienceSource::Quest => " from a quest",
+            };
+
+            self.chat_messages.push(ChatMessage {
+                text: format!("Gained {amount} {experience_name} experience{source_suffix}"),
+                color: MessageColor::Information,
+            });
+        }
+
         #[cfg(feature = "debug")]
         network_event_measurement.stop();
 
@@ -1480,6 +1671,9 @@ impl Client {
                         .close_window_with_class(&mut self.focus_state, RespawnWindow::WINDOW_CLASS);
                 }
                 UserEvent::LogOut => {
+                    // Logging out again restarts a delayed logout instead of queueing
+                    // another one.
+                    self.networking_system.cancel_log_out();
                     let _ = self.networking_system.log_out();
                 }
                 UserEvent::Exit => event_loop.exit(),
@@ -1579,10 +1773,9 @@ impl Client {
                 }
                 UserEvent::RequestPlayerMove(destination) => {
                     if !self.entities.is_empty() {
-                        let _ = self.networking_system.player_move(WorldPosition {
-                            x: destination.x,
-                            y: destination.y,
-                            direction: Direction::N,
+                        let _ = self.networking_system.player_move(TilePosition {
+                            x: destination.x as u16,
+                            y: destination.y as u16,
                         });
                     }
                 }
@@ -1595,10 +1788,9 @@ impl Client {
                             EntityType::Monster => self.networking_system.player_attack(entity_id),
                             EntityType::Warp => self.networking_system.player_move({
                                 let position = entity.get_grid_position();
-                                WorldPosition {
-                                    x: position.x,
-                                    y: position.y,
-                                    direction: Direction::N,
+                                TilePosition {
+                                    x: position.x as u16,
+                                    y: position.y as u16,
                                 }
                             }),
                             _ => Ok(()),
@@ -1666,10 +1858,19 @@ impl Client {
                                 }
                             }
                             SkillType::Ground | SkillType::Trap => {
-                                if let Some(PickerTarget::Tile { x, y }) = mouse_target {
-                                    let _ = self
-                                        .networking_system
-                                        .cast_ground_skill(skill.skill_id, skill.skill_level, TilePosition { x, y });
+                                if let Some(PickerTarget::Tile { x, y }) = mouse_target
+                                    && let Some(map) = self.map.as_ref()
+                                {
+                                    let map_size = MapSize {
+                                        width: map.get_width() as u16,
+                                        height: map.get_height() as u16,
+                                    };
+                                    let _ = self.networking_system.cast_ground_skill(
+                                        skill.skill_id,
+                                        skill.skill_level,
+                                        TilePosition { x, y },
+                                        map_size,
+                                    );
                                 }
                             }
                             SkillType::SelfCast => match skill.skill_id == ROLLING_CUTTER_ID {
@@ -225,10 +225,10 @@ impl GameInterfaceRenderer {
         self.render_text(text, position, color, font_size, AlignHorizontal::Mid);
     }
 
-    pub fn render_bar(&self, position: ScreenPosition, size: ScreenSize, color: Color, maximum: f32, current: f32) {
+    pub fn render_bar(&self, position: ScreenPosition, size: ScreenSize, color: Color, fraction: f32) {
         let bar_offset = ScreenSize::only_width(size.width / 2.0);
         let bar_size = ScreenSize {
-            width: (size.width / maximum) * current,
+            width: size.width * fraction,
             height: size.height,
         };
 
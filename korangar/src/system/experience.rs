@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use ragnarok_packets::{ExperienceSource, ExperienceType};
+
+/// How long gained experience is collected before it is reported.
+const SUMMARY_WINDOW: Duration = Duration::from_secs(1);
+
+/// Sums up experience gained in quick succession, so that defeating a
+/// monster is reported once per experience type instead of once per packet.
+#[derive(Default)]
+pub struct ExperienceSummary {
+    started_at: Option<Instant>,
+    gains: Vec<(ExperienceType, ExperienceSource, u64)>,
+}
+
+impl ExperienceSummary {
+    pub fn add(&mut self, amount: u64, experience_type: ExperienceType, source: ExperienceSource, now: Instant) {
+        self.started_at.get_or_insert(now);
+
+        match self
+            .gains
+            .iter_mut()
+            .find(|(gain_type, gain_source, _)| *gain_type == experience_type && *gain_source == source)
+        {
+            Some((.., total)) => *total = total.saturating_add(amount),
+            None => self.gains.push((experience_type, source, amount)),
+        }
+    }
+
+    /// Returns the summed up gains once the window has passed and starts a
+    /// new window with the next gain.
+    pub fn take_finished(&mut self, now: Instant) -> Vec<(ExperienceType, ExperienceSource, u64)> {
+        match self.started_at {
+            Some(started_at) if now.duration_since(started_at) >= SUMMARY_WINDOW => {
+                self.started_at = None;
+                std::mem::take(&mut self.gains)
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod summary {
+    use std::time::{Duration, Instant};
+
+    use ragnarok_packets::{ExperienceSource, ExperienceType};
+
+    use super::{ExperienceSummary, SUMMARY_WINDOW};
+
+    #[test]
+    fn gains_are_summed_over_the_window() {
+        let start = Instant::now();
+        let mut summary = ExperienceSummary::default();
+
+        summary.add(10, ExperienceType::BaseExperience, ExperienceSource::Regular, start);
+        summary.add(5, ExperienceType::JobExperience, ExperienceSource::Regular, start);
+        summary.add(
+            20,
+            ExperienceType::BaseExperience,
+            ExperienceSource::Regular,
+            start + Duration::from_millis(100),
+        );
+
+        assert!(summary.take_finished(start + Duration::from_millis(500)).is_empty());
+        assert_eq!(summary.take_finished(start + SUMMARY_WINDOW), vec![
+            (ExperienceType::BaseExperience, ExperienceSource::Regular, 30),
+            (ExperienceType::JobExperience, ExperienceSource::Regular, 5),
+        ]);
+        assert!(summary.take_finished(start + SUMMARY_WINDOW * 2).is_empty());
+    }
+
+    #[test]
+    fn sources_are_reported_separately() {
+        let start = Instant::now();
+        let mut summary = ExperienceSummary::default();
+
+        summary.add(10, ExperienceType::BaseExperience, ExperienceSource::Regular, start);
+        summary.add(50, ExperienceType::BaseExperience, ExperienceSource::Quest, start);
+
+        assert_eq!(summary.take_finished(start + SUMMARY_WINDOW), vec![
+            (ExperienceType::BaseExperience, ExperienceSource::Regular, 10),
+            (ExperienceType::BaseExperience, ExperienceSource::Quest, 50),
+        ]);
+    }
+}
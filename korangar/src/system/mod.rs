@@ -1,3 +1,5 @@
+mod experience;
 mod timer;
 
+pub use self::experience::ExperienceSummary;
 pub use self::timer::GameTimer;
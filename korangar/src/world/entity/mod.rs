@@ -7,7 +7,7 @@ use derive_new::new;
 use korangar_audio::{AudioEngine, SoundEffectKey};
 use korangar_interface::elements::PrototypeElement;
 use korangar_interface::windows::{PrototypeWindow, Window};
-use korangar_networking::EntityData;
+use korangar_networking::{EntityData, HealthState};
 use korangar_util::pathing::{PathFinder, MAX_WALK_PATH_SIZE};
 #[cfg(feature = "debug")]
 use korangar_util::texture_atlas::AtlasAllocation;
@@ -35,7 +35,7 @@ use crate::{Buffer, Color, ModelVertex};
 const MALE_HAIR_LOOKUP: &[usize] = &[2, 2, 1, 7, 5, 4, 3, 6, 8, 9, 10, 12, 11];
 const FEMALE_HAIR_LOOKUP: &[usize] = &[2, 2, 4, 7, 1, 5, 3, 6, 12, 10, 9, 11, 8];
 const SOUND_COOLDOWN_DURATION: u32 = 200;
-const SPATIAL_SOUND_RANGE: f32 = 250.0;
+pub const SPATIAL_SOUND_RANGE: f32 = 250.0;
 
 pub enum ResourceState<T> {
     Available(T),
@@ -114,7 +114,7 @@ impl SoundState {
         };
 
         if should_play {
-            audio_engine.play_spatial_sound_effect(sound_effect_key, position, SPATIAL_SOUND_RANGE);
+            audio_engine.play_spatial_sound_effect(sound_effect_key, position, SPATIAL_SOUND_RANGE, None, false);
             self.last_played_at = Some(client_tick);
             self.previous_key = Some(sound_effect_key);
         }
@@ -850,8 +850,7 @@ impl Player {
                 height: theme.status_bar.health_height.get(),
             },
             theme.status_bar.player_health_color.get(),
-            self.common.maximum_health_points as f32,
-            self.common.health_points as f32,
+            HealthState::new(self.common.health_points, self.common.maximum_health_points).fraction(),
         );
 
         offset += gap + theme.status_bar.health_height.get();
@@ -863,8 +862,7 @@ impl Player {
                 height: theme.status_bar.spell_point_height.get(),
             },
             theme.status_bar.spell_point_color.get(),
-            self.maximum_spell_points as f32,
-            self.spell_points as f32,
+            (self.spell_points as f32 / self.maximum_spell_points.max(1) as f32).clamp(0.0, 1.0),
         );
 
         offset += gap + theme.status_bar.spell_point_height.get();
@@ -876,8 +874,7 @@ impl Player {
                 height: theme.status_bar.activity_point_height.get(),
             },
             theme.status_bar.activity_point_color.get(),
-            self.maximum_activity_points as f32,
-            self.activity_points as f32,
+            (self.activity_points as f32 / self.maximum_activity_points.max(1) as f32).clamp(0.0, 1.0),
         );
     }
 
@@ -947,8 +944,7 @@ impl Npc {
                 height: theme.status_bar.enemy_health_height.get(),
             },
             theme.status_bar.enemy_health_color.get(),
-            self.common.maximum_health_points as f32,
-            self.common.health_points as f32,
+            HealthState::new(self.common.health_points, self.common.maximum_health_points).fraction(),
         );
     }
 }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use cgmath::{Array, Deg, Matrix3, Matrix4, Point3, SquareMatrix, Vector2, Vector3};
 use derive_new::new;
-use korangar_audio::AudioEngine;
+use korangar_audio::{AmbientKey, AudioEngine};
 #[cfg(feature = "debug")]
 use korangar_interface::windows::PrototypeWindow;
 use korangar_util::collision::{Frustum, KDTree, Sphere, AABB};
@@ -143,6 +143,14 @@ pub struct Map {
 }
 
 impl Map {
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
     pub fn water_bounds(&self) -> Rectangle<f32> {
         self.water_bounds
     }
@@ -181,19 +189,24 @@ impl Map {
         // This is the only correct place to clear the ambient sound.
         audio_engine.clear_ambient_sound();
 
-        for sound in self.sound_sources.iter() {
-            let sound_effect_key = audio_engine.load(&sound.sound_file);
-
-            audio_engine.add_ambient_sound(
-                sound_effect_key,
-                sound.position,
-                sound.range * AMBIENT_SOUND_MULTIPLIER,
-                sound.volume,
-                sound.cycle,
-            );
-        }
+        let ambient_keys: Vec<AmbientKey> = self
+            .sound_sources
+            .iter()
+            .map(|sound| {
+                let sound_effect_key = audio_engine.load(&sound.sound_file);
+
+                audio_engine.add_ambient_sound(
+                    sound_effect_key,
+                    sound.position,
+                    sound.range * AMBIENT_SOUND_MULTIPLIER,
+                    sound.volume,
+                    sound.cycle,
+                )
+            })
+            .collect();
 
         audio_engine.prepare_ambient_sound_world();
+        audio_engine.prefetch_ambient_sounds(&ambient_keys);
     }
 
     // We want to make sure that the object set also captures the lifetime of the
@@ -3,54 +3,361 @@
 #![forbid(missing_docs)]
 
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Cursor;
 use std::mem::swap;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering as AtomicOrdering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, TryLockError};
 use std::time::{Duration, Instant};
 
-use cgmath::{InnerSpace, Matrix3, Point3, Quaternion, Vector3};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix3, MetricSpace, One, Point3, Quaternion, Vector3};
 use cpal::BufferSize;
+use kira::effect::filter::{FilterBuilder, FilterHandle, FilterMode};
+use kira::effect::{Effect, EffectBuilder};
+use kira::info::Info;
 use kira::manager::backend::cpal::{CpalBackend, CpalBackendSettings};
 use kira::manager::{AudioManager, AudioManagerSettings, Capacities};
-use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings};
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
 use kira::sound::{FromFileError, PlaybackState};
 use kira::spatial::emitter::{EmitterDistances, EmitterHandle, EmitterSettings};
 use kira::spatial::listener::{ListenerHandle, ListenerSettings};
 use kira::spatial::scene::{SpatialSceneHandle, SpatialSceneSettings};
-use kira::track::{TrackBuilder, TrackHandle};
-use kira::tween::{Easing, Tween, Value};
+use kira::track::{TrackBuilder, TrackHandle, TrackRoutes};
+pub use kira::tween::Easing;
+use kira::tween::{Tween, Value};
 use kira::{Frame, Volume};
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{print_debug, Colorize};
-use korangar_util::collision::{KDTree, Sphere};
+use korangar_util::collision::{Capsule, Insertable, KDTree, Query, Sphere, AABB};
 use korangar_util::container::{Cacheable, GenerationalSlab, SimpleCache, SimpleSlab};
-use korangar_util::{create_generational_key, create_simple_key, FileLoader};
-use rayon::spawn;
+use korangar_util::{create_generational_key, create_simple_key, FileLoader, FileNotFoundError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::{spawn, ThreadPool, ThreadPoolBuilder};
 
 create_generational_key!(SoundEffectKey, "The key for a cached sound effect");
 create_simple_key!(AmbientKey, "The key for a ambient sound");
 
 const MAX_QUEUE_TIME_SECONDS: f32 = 1.0;
+const MAX_QUEUED_SOUND_EFFECTS: usize = 64;
+const DEFAULT_MAX_EFFECT_VOICES: usize = 64;
+const VOICE_STEAL_FADE: Duration = Duration::from_millis(20);
+const LOAD_RETRY_ATTEMPTS: u32 = 2;
+/// How much a single prefetched sound effect changes the eviction pressure.
+const EVICTION_PRESSURE_SMOOTHING: f32 = 0.2;
+const MAX_PREFETCH_LOADS: usize = 4;
+const MIN_EMITTER_DISTANCE: f32 = 5.0;
+/// Ambient sounds with bounds that differ by less than this are considered
+/// duplicates.
+const DUPLICATE_AMBIENT_EPSILON: f32 = 0.1;
+/// The time it takes the output levels to settle after a change, similar to
+/// the integration time of a VU meter.
+const LEVEL_METER_TIME_CONSTANT: f64 = 0.3;
 const MAX_CACHE_COUNT: u32 = 400;
 const MAX_CACHE_SIZE: usize = 50 * 104 * 1024; // 50 MiB
+const MAX_COMPRESSED_CACHE_COUNT: u32 = 100;
+const MAX_COMPRESSED_CACHE_SIZE: usize = 16 * 1024 * 1024; // 16 MiB
 const SOUND_EFFECT_BASE_PATH: &str = "data\\wav";
+const NORMALIZED_SAMPLE_RATE: u32 = 48000;
 const BACKGROUND_MUSIC_MAPPING_FILE: &str = "data\\mp3NameTable.txt";
+const MEMORY_SOUND_EFFECT_PREFIX: &str = "memory:";
+const REDUCED_MUSIC_SAMPLE_RATE: u32 = 22050;
+
+/// The music layer that is used by
+/// [`AudioEngine::play_background_music_track`].
+pub const DEFAULT_MUSIC_LAYER: &str = "default";
+
+/// The curve that should be used for volume changes unless a specific curve
+/// is needed. A linear change in amplitude sounds abrupt at the start of a
+/// fade in and at the end of a fade out, so this eases in and out instead.
+/// Use [`Easing::Linear`] for a linear change.
+pub const DEFAULT_VOLUME_EASING: Easing = Easing::InOutPowi(2);
+
+/// How the audio engine outputs spatial sounds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Spatial sounds are played through a spatial scene, which attenuates and
+    /// pans them relative to the listener.
+    #[default]
+    Spatial,
+    /// No spatial scene is created. Spatial sounds are played on the sound
+    /// effect track with a volume that is only derived from their distance to
+    /// the listener. Saves CPU time for headless or mono output.
+    Mono,
+}
+
+/// A group of sound effects that shares a volume, so players can adjust for
+/// example combat sounds independently of voices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundCategory {
+    /// Attacks, hits and skills.
+    Combat,
+    /// Footsteps of the player and other entities.
+    Footsteps,
+    /// Clicks and other feedback of the user interface.
+    Interface,
+    /// Voice lines of characters and NPCs.
+    Voice,
+}
+
+impl SoundCategory {
+    /// All sound categories.
+    pub const ALL: [SoundCategory; 4] = [
+        SoundCategory::Combat,
+        SoundCategory::Footsteps,
+        SoundCategory::Interface,
+        SoundCategory::Voice,
+    ];
+}
+
+/// The algorithm used to resample sound effects to a common sample rate when
+/// they are loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// Nearest neighbor sampling. Cheapest to compute, but introduces audible
+    /// artifacts.
+    Fast,
+    /// Linear interpolation between samples.
+    #[default]
+    Balanced,
+    /// Cubic interpolation between samples. Highest fidelity, but also the
+    /// most expensive to compute.
+    High,
+}
+
+/// How background music is decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MusicDecodeQuality {
+    /// The music is streamed from disk and decoded at its original sample rate
+    /// while it plays.
+    #[default]
+    Full,
+    /// The music is decoded completely on the loading threads and resampled to
+    /// a lower sample rate, so that no decoding happens during playback. Trades
+    /// fidelity, memory and a longer delay when switching tracks for less CPU
+    /// usage while the music plays.
+    Reduced,
+}
+
+/// Settings that are needed by the async loading tasks.
+#[derive(Clone)]
+struct LoadSettings {
+    sound_effect_base_path: String,
+    /// Resample all sound effects to [`NORMALIZED_SAMPLE_RATE`] when they are
+    /// loaded.
+    normalize_sample_rate: bool,
+    resample_quality: ResampleQuality,
+    compressed_threshold: Option<usize>,
+    /// Dedicated thread pool for loading sound effects. Uses the global
+    /// thread pool if `None`.
+    thread_pool: Option<Arc<ThreadPool>>,
+}
+
+/// A music track that is decoded on the loading threads and starts playing
+/// once it is done, see [`MusicDecodeQuality::Reduced`].
+struct LoadingMusicTrack {
+    track_name: String,
+    start_position: f64,
+    volume: Value<Volume>,
+}
 
 struct BackgroundMusicTrack {
     track_name: String,
-    handle: StreamingSoundHandle<FromFileError>,
+    handle: MusicHandle,
+    /// Length of the looped region in seconds.
+    loop_duration: f64,
+    /// Playback position in seconds at `playing_since`, or the position the
+    /// track was paused at.
+    start_position: f64,
+    /// The time the track was last started or resumed. `None` while paused.
+    playing_since: Option<Instant>,
+    /// Playback rate since `playing_since`.
+    playback_rate: f64,
+}
+
+impl BackgroundMusicTrack {
+    /// Returns the playback position in seconds. It is derived from the wall
+    /// clock instead of the handle, so it stays valid even if the handle had
+    /// to be recreated.
+    fn elapsed(&self, now: Instant) -> f64 {
+        let position = match self.playing_since {
+            Some(playing_since) => self.start_position + now.saturating_duration_since(playing_since).as_secs_f64() * self.playback_rate,
+            None => self.start_position,
+        };
+
+        match self.loop_duration > 0.0 {
+            true => position % self.loop_duration,
+            false => position,
+        }
+    }
+
+    /// Restarts the elapsed time tracking at the given position.
+    fn rebase(&mut self, now: Instant, position: f64, playback_rate: f64) {
+        self.start_position = position;
+        self.playback_rate = playback_rate;
+
+        if self.playing_since.is_some() {
+            self.playing_since = Some(now);
+        }
+    }
+}
+
+/// An effect on the main track that collapses the final mix to mono.
+struct MonoDownmix {
+    enabled: Arc<AtomicBool>,
+}
+
+impl EffectBuilder for MonoDownmix {
+    type Handle = Arc<AtomicBool>;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        let handle = self.enabled.clone();
+        (Box::new(self), handle)
+    }
+}
+
+impl Effect for MonoDownmix {
+    fn process(&mut self, input: Frame, _dt: f64, _info: &Info) -> Frame {
+        match self.enabled.load(AtomicOrdering::Relaxed) {
+            true => Frame::from_mono((input.left + input.right) * 0.5),
+            false => input,
+        }
+    }
+}
+
+/// The output levels measured by the [`LevelMeter`], shared with the audio
+/// thread. The levels are stored as the bits of an `f32`.
+#[derive(Default)]
+struct OutputLevels {
+    enabled: AtomicBool,
+    left: AtomicU32,
+    right: AtomicU32,
+}
+
+/// An effect on the main track that measures the RMS level of each channel
+/// without changing the audio.
+struct LevelMeter {
+    levels: Arc<OutputLevels>,
+    left_mean_square: f32,
+    right_mean_square: f32,
+}
+
+impl EffectBuilder for LevelMeter {
+    type Handle = Arc<OutputLevels>;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        let handle = self.levels.clone();
+        (Box::new(self), handle)
+    }
+}
+
+impl Effect for LevelMeter {
+    fn process(&mut self, input: Frame, dt: f64, _info: &Info) -> Frame {
+        if !self.levels.enabled.load(AtomicOrdering::Relaxed) {
+            self.left_mean_square = 0.0;
+            self.right_mean_square = 0.0;
+            return input;
+        }
+
+        let smoothing = (1.0 - (-dt / LEVEL_METER_TIME_CONSTANT).exp()) as f32;
+        self.left_mean_square += (input.left * input.left - self.left_mean_square) * smoothing;
+        self.right_mean_square += (input.right * input.right - self.right_mean_square) * smoothing;
+
+        self.levels
+            .left
+            .store(self.left_mean_square.sqrt().to_bits(), AtomicOrdering::Relaxed);
+        self.levels
+            .right
+            .store(self.right_mean_square.sqrt().to_bits(), AtomicOrdering::Relaxed);
+
+        input
+    }
+}
+
+/// The handle of a music track, depending on the [`MusicDecodeQuality`] it
+/// was started with.
+enum MusicHandle {
+    Streaming(StreamingSoundHandle<FromFileError>),
+    Static(StaticSoundHandle),
+}
+
+impl MusicHandle {
+    fn state(&self) -> PlaybackState {
+        match self {
+            MusicHandle::Streaming(handle) => handle.state(),
+            MusicHandle::Static(handle) => handle.state(),
+        }
+    }
+
+    fn position(&self) -> f64 {
+        match self {
+            MusicHandle::Streaming(handle) => handle.position(),
+            MusicHandle::Static(handle) => handle.position(),
+        }
+    }
+
+    fn set_volume(&mut self, volume: impl Into<Value<Volume>>, tween: Tween) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.set_volume(volume, tween),
+            MusicHandle::Static(handle) => handle.set_volume(volume, tween),
+        }
+    }
+
+    fn set_playback_rate(&mut self, playback_rate: f64, tween: Tween) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.set_playback_rate(playback_rate, tween),
+            MusicHandle::Static(handle) => handle.set_playback_rate(playback_rate, tween),
+        }
+    }
+
+    fn stop(&mut self, tween: Tween) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.stop(tween),
+            MusicHandle::Static(handle) => handle.stop(tween),
+        }
+    }
+
+    fn pause(&mut self, tween: Tween) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.pause(tween),
+            MusicHandle::Static(handle) => handle.pause(tween),
+        }
+    }
+
+    fn resume(&mut self, tween: Tween) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.resume(tween),
+            MusicHandle::Static(handle) => handle.resume(tween),
+        }
+    }
+
+    fn seek_to(&mut self, position: f64) {
+        match self {
+            MusicHandle::Streaming(handle) => handle.seek_to(position),
+            MusicHandle::Static(handle) => handle.seek_to(position),
+        }
+    }
 }
 
 enum QueuedSoundEffectType {
-    Sound,
-    SpatialSound { position: Vector3<f32>, range: f32 },
-    AmbientSound { ambient_key: AmbientKey },
+    Sound {
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    },
+    SpatialSound {
+        position: Point3<f32>,
+        range: f32,
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    },
+    AmbientSound {
+        ambient_key: AmbientKey,
+    },
 }
 
 struct QueuedSoundEffect {
@@ -62,17 +369,268 @@ struct QueuedSoundEffect {
     queued_time: Instant,
 }
 
+/// The region in which an ambient sound can be heard.
+#[derive(Debug, Clone, Copy)]
+pub enum AmbientBounds {
+    /// The sound is emitted from the center and fades out towards the edge of
+    /// the sphere.
+    Sphere(Sphere),
+    /// The sound is emitted from the core of the box and fades out towards its
+    /// edges. The fade out distance is half of the smallest horizontal extent
+    /// of the box.
+    Aabb(AABB),
+    /// The sound is emitted from the segment of the capsule and fades out
+    /// towards its surface. Useful for linear sources, like rivers and roads.
+    Capsule(Capsule),
+}
+
+impl AmbientBounds {
+    /// Checks if the point is inside the bounds.
+    pub fn contains(&self, point: Point3<f32>) -> bool {
+        match self {
+            AmbientBounds::Sphere(sphere) => sphere.contains_point(point),
+            AmbientBounds::Aabb(aabb) => aabb.contains_point(point),
+            AmbientBounds::Capsule(capsule) => capsule.contains_point(point),
+        }
+    }
+
+    /// Returns the distance from the point to the bounds. Points inside the
+    /// bounds have a distance of 0.
+    pub fn distance(&self, point: Point3<f32>) -> f32 {
+        match self {
+            AmbientBounds::Sphere(sphere) => (sphere.center().distance(point) - sphere.radius()).max(0.0),
+            AmbientBounds::Aabb(aabb) => clamp_point(point, aabb.min(), aabb.max()).distance(point),
+            AmbientBounds::Capsule(capsule) => capsule.distance_to_point(point),
+        }
+    }
+
+    /// The distance from the emitting position at which the sound can't be
+    /// heard anymore.
+    fn range(&self) -> f32 {
+        match self {
+            AmbientBounds::Sphere(sphere) => sphere.radius(),
+            AmbientBounds::Aabb(aabb) => aabb.size().x.min(aabb.size().z) * 0.5,
+            AmbientBounds::Capsule(capsule) => capsule.radius(),
+        }
+    }
+
+    /// The position the sound is emitted from, as heard by a listener at the
+    /// given position.
+    fn emitter_position(&self, listener_position: Point3<f32>) -> Point3<f32> {
+        match self {
+            AmbientBounds::Sphere(sphere) => sphere.center(),
+            AmbientBounds::Aabb(aabb) => {
+                let range = self.range();
+                let center = aabb.center();
+                let shrink = |min: f32, max: f32, center: f32| match max - min > range * 2.0 {
+                    true => (min + range, max - range),
+                    false => (center, center),
+                };
+                let (min_x, max_x) = shrink(aabb.min().x, aabb.max().x, center.x);
+                let (min_y, max_y) = shrink(aabb.min().y, aabb.max().y, center.y);
+                let (min_z, max_z) = shrink(aabb.min().z, aabb.max().z, center.z);
+
+                clamp_point(
+                    listener_position,
+                    Point3::new(min_x, min_y, min_z),
+                    Point3::new(max_x, max_y, max_z),
+                )
+            }
+            AmbientBounds::Capsule(capsule) => capsule.closest_point_on_segment(listener_position),
+        }
+    }
+
+    fn is_same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AmbientBounds::Sphere(sphere), AmbientBounds::Sphere(other)) => {
+                sphere.center() == other.center() && sphere.radius() == other.radius()
+            }
+            (AmbientBounds::Aabb(aabb), AmbientBounds::Aabb(other)) => aabb.min() == other.min() && aabb.max() == other.max(),
+            (AmbientBounds::Capsule(capsule), AmbientBounds::Capsule(other)) => capsule == other,
+            _ => false,
+        }
+    }
+
+    /// Like [`is_same_as`](Self::is_same_as), but allows the bounds to differ
+    /// by up to `epsilon` to catch duplicates with rounding errors.
+    fn is_near(&self, other: &Self, epsilon: f32) -> bool {
+        let near = |point: Point3<f32>, other: Point3<f32>| point.distance(other) <= epsilon;
+
+        match (self, other) {
+            (AmbientBounds::Sphere(sphere), AmbientBounds::Sphere(other)) => {
+                near(sphere.center(), other.center()) && (sphere.radius() - other.radius()).abs() <= epsilon
+            }
+            (AmbientBounds::Aabb(aabb), AmbientBounds::Aabb(other)) => near(aabb.min(), other.min()) && near(aabb.max(), other.max()),
+            (AmbientBounds::Capsule(capsule), AmbientBounds::Capsule(other)) => {
+                near(capsule.start(), other.start())
+                    && near(capsule.end(), other.end())
+                    && (capsule.radius() - other.radius()).abs() <= epsilon
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Insertable for AmbientBounds {
+    fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        match self {
+            AmbientBounds::Sphere(sphere) => sphere.intersects_aabb(aabb),
+            AmbientBounds::Aabb(bounds) => bounds.intersects_aabb(aabb),
+            AmbientBounds::Capsule(capsule) => capsule.intersects_aabb(aabb),
+        }
+    }
+
+    fn bounding_box(&self) -> AABB {
+        match self {
+            AmbientBounds::Sphere(sphere) => Insertable::bounding_box(sphere),
+            AmbientBounds::Aabb(aabb) => *aabb,
+            AmbientBounds::Capsule(capsule) => capsule.bounding_box(),
+        }
+    }
+}
+
+impl Query<AmbientBounds> for Sphere {
+    fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.intersects_aabb(aabb)
+    }
+
+    fn intersects_object(&self, object: &AmbientBounds) -> bool {
+        match object {
+            AmbientBounds::Sphere(sphere) => self.intersects_sphere(sphere),
+            AmbientBounds::Aabb(aabb) => self.intersects_aabb(aabb),
+            AmbientBounds::Capsule(capsule) => capsule.intersects_sphere(self),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 struct AmbientSoundConfig {
     sound_effect_key: SoundEffectKey,
-    bounds: Sphere,
+    bounds: AmbientBounds,
     volume: f32,
     cycle: Option<f32>,
+    priority: u8,
+}
+
+impl AmbientSoundConfig {
+    fn is_same_as(&self, other: &Self) -> bool {
+        self.sound_effect_key == other.sound_effect_key
+            && self.bounds.is_same_as(&other.bounds)
+            && self.volume == other.volume
+            && self.cycle == other.cycle
+            && self.priority == other.priority
+    }
+}
+
+/// A snapshot of the playing background music and the registered ambient
+/// sounds. Can be restored with [`AudioEngine::restore`].
+pub struct AudioSnapshot {
+    background_music_track: Option<(String, f64)>,
+    ambient_sounds: Vec<AmbientSoundConfig>,
+}
+
+/// The spatial scene and its listener. Only present in
+/// [`OutputMode::Spatial`].
+struct SpatialOutput {
+    scene: SpatialSceneHandle,
+    listener: ListenerHandle,
+    /// Set once the scene ran out of emitters, so the fallback is only
+    /// reported once.
+    emitter_limit_reached: bool,
+}
+
+/// A smooth movement of the listener, see
+/// [`AudioEngine::move_listener_smooth`].
+struct ListenerTransition {
+    start_position: Point3<f32>,
+    start_rotation: Quaternion<f32>,
+    target_position: Point3<f32>,
+    target_rotation: Quaternion<f32>,
+    started: Instant,
+    duration: Duration,
+}
+
+impl ListenerTransition {
+    /// Returns the position and rotation of the listener at the given time and
+    /// whether the transition is complete.
+    fn transform_at(&self, now: Instant) -> (Point3<f32>, Quaternion<f32>, bool) {
+        let progress = match self.duration.is_zero() {
+            true => 1.0,
+            false => (now.saturating_duration_since(self.started).as_secs_f32() / self.duration.as_secs_f32()).min(1.0),
+        };
+
+        let position = self.start_position + (self.target_position - self.start_position) * progress;
+        let rotation = self.start_rotation.slerp(self.target_rotation, progress);
+
+        (position, rotation, progress >= 1.0)
+    }
+}
+
+/// The destination of sounds that are emitted from a position.
+enum SoundEmitter {
+    /// An emitter of the spatial scene, which takes care of attenuation and
+    /// panning.
+    Spatial {
+        emitter_handle: EmitterHandle,
+        handle: Option<StaticSoundHandle>,
+    },
+    /// The sound effect track. The position and range are used to derive the
+    /// volume from the distance to the listener.
+    Mono {
+        position: Point3<f32>,
+        range: f32,
+        handle: Option<StaticSoundHandle>,
+    },
+}
+
+impl SoundEmitter {
+    /// The handle of the non-cycling sound played by this emitter.
+    fn handle_mut(&mut self) -> &mut Option<StaticSoundHandle> {
+        match self {
+            SoundEmitter::Spatial { handle, .. } | SoundEmitter::Mono { handle, .. } => handle,
+        }
+    }
+}
+
+/// A temporary volume boost of an ambient sound.
+struct AmbientEmphasis {
+    gain: f32,
+    until: Instant,
+}
+
+/// A sound effect that is kept outside of the sound effect cache.
+#[derive(Default)]
+struct PinnedSoundEffect {
+    /// `None` while the sound effect is still loading.
+    data: Option<StaticSoundData>,
+    /// How often the sound effect was pinned with
+    /// [`AudioEngine::pin_sound_effects`] and not unpinned yet.
+    pin_count: usize,
+    /// UI sound effects stay pinned, regardless of the pin count.
+    ui_sound: bool,
+}
+
+impl PinnedSoundEffect {
+    /// Releases one pin and returns `true` if the sound effect isn't pinned
+    /// anymore.
+    fn unpin(&mut self) -> bool {
+        self.pin_count = self.pin_count.saturating_sub(1);
+        self.pin_count == 0 && !self.ui_sound
+    }
+}
+
+struct PlayingSoundEffect {
+    handle: StaticSoundHandle,
+    /// Non-interruptible sound effects are never stopped to make room for new
+    /// sound effects.
+    non_interruptible: bool,
 }
 
 struct PlayingAmbient {
     data: StaticSoundData,
     handle: StaticSoundHandle,
     cycle: f32,
+    restart_delay: f32,
     last_start: Instant,
 }
 
@@ -85,17 +643,198 @@ impl Cacheable for CachedSoundEffect {
     }
 }
 
+/// The undecoded bytes of a sound effect, that is decoded every time it is
+/// played.
+struct CompressedSoundEffect(Arc<[u8]>);
+
+impl Cacheable for CompressedSoundEffect {
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+
 enum AsyncLoadResult {
     Loaded {
         path: String,
         key: SoundEffectKey,
         sound_effect: Box<StaticSoundData>,
     },
+    /// The sound effect is bigger than the compressed threshold, so only its
+    /// undecoded bytes should be cached.
+    LoadedCompressed {
+        path: String,
+        key: SoundEffectKey,
+        data: Arc<[u8]>,
+        sound_effect: Box<StaticSoundData>,
+    },
     Error {
         path: String,
         key: SoundEffectKey,
-        message: String,
+        error: SoundEffectLoadError,
+    },
+    /// Loading the file failed in a way that might not happen again, so it
+    /// should be loaded again on a later frame.
+    Retry { path: String, key: SoundEffectKey, attempt: u32 },
+    MusicLoaded {
+        layer_name: String,
+        track_name: String,
+        music: Box<StaticSoundData>,
     },
+    MusicError {
+        layer_name: String,
+        track_name: String,
+        error: FromFileError,
+    },
+}
+
+/// The reason why a sound effect could not be loaded.
+#[derive(Debug)]
+pub enum SoundEffectLoadError {
+    /// The file loader could not find the audio file.
+    NotFound(FileNotFoundError),
+    /// The audio file was found but could not be decoded.
+    Decode(FromFileError),
+}
+
+/// The outcome of a request to play a sound effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEffectPlayback {
+    /// The sound effect was loaded and started playing right away.
+    Played,
+    /// The sound effect is still loading and will start playing once it
+    /// finished loading.
+    Queued,
+    /// The request was dropped, because the playback queue is full, the
+    /// engine is busy or the sound effect is unknown.
+    Dropped,
+}
+
+impl SoundEffectPlayback {
+    /// Returns `true` if the sound effect was played or queued.
+    pub fn is_accepted(self) -> bool {
+        self != Self::Dropped
+    }
+}
+
+type LoadErrorCallback = Box<dyn FnMut(&str, &SoundEffectLoadError) + Send>;
+
+/// How well prefetching sound effects works out, see
+/// [`AudioEngine::prefetch_statistics`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrefetchStatistics {
+    /// Number of prefetched sound effects that were played while they were
+    /// still cached.
+    pub used: u32,
+    /// Number of prefetched sound effects that were evicted from the cache
+    /// before they were played.
+    pub evicted: u32,
+    /// Number of prefetched sound effects that could not be loaded.
+    pub failed: u32,
+    /// Moving average of the share of recently prefetched sound effects that
+    /// were evicted before they were played, between 0 and 1. A high value
+    /// means the cache is too small for everything that is prefetched.
+    pub eviction_pressure: f32,
+}
+
+/// Keeps track of prefetched sound effects until they are either played,
+/// evicted from the cache or failed to load.
+#[derive(Default)]
+struct PrefetchTracker {
+    /// Prefetched sound effects that weren't played yet.
+    pending: HashSet<SoundEffectKey>,
+    used: u32,
+    evicted: u32,
+    failed: u32,
+    eviction_pressure: f32,
+    /// Reduce prefetching while the eviction pressure is high.
+    adaptive: bool,
+}
+
+impl PrefetchTracker {
+    fn mark_used(&mut self, sound_effect_key: SoundEffectKey) {
+        if self.pending.remove(&sound_effect_key) {
+            self.used += 1;
+            self.eviction_pressure = update_eviction_pressure(self.eviction_pressure, false);
+        }
+    }
+
+    fn mark_evicted(&mut self, sound_effect_key: SoundEffectKey) {
+        if self.pending.remove(&sound_effect_key) {
+            self.evicted += 1;
+            self.eviction_pressure = update_eviction_pressure(self.eviction_pressure, true);
+        }
+    }
+
+    /// Failed loads say nothing about the size of the cache, so they don't
+    /// change the eviction pressure.
+    fn mark_failed(&mut self, sound_effect_key: SoundEffectKey) {
+        if self.pending.remove(&sound_effect_key) {
+            self.failed += 1;
+        }
+    }
+
+    fn statistics(&self) -> PrefetchStatistics {
+        PrefetchStatistics {
+            used: self.used,
+            evicted: self.evicted,
+            failed: self.failed,
+            eviction_pressure: self.eviction_pressure,
+        }
+    }
+}
+
+/// Sound effects that wait to be loaded by
+/// [`AudioEngine::prefetch_ambient_sounds`]. Only a few of them are loaded at
+/// the same time, so the sizes of the loaded sound effects can be checked
+/// against the remaining budget before more are loaded.
+#[derive(Default)]
+struct PrefetchQueue {
+    queued: VecDeque<SoundEffectKey>,
+    loading: HashSet<SoundEffectKey>,
+    remaining_count: usize,
+    remaining_bytes: usize,
+}
+
+impl PrefetchQueue {
+    /// Replaces the queued sound effects and the budget.
+    fn reset(&mut self, sound_effect_keys: impl IntoIterator<Item = SoundEffectKey>, remaining_count: usize, remaining_bytes: usize) {
+        self.queued = sound_effect_keys.into_iter().collect();
+        self.remaining_count = remaining_count;
+        self.remaining_bytes = remaining_bytes;
+    }
+
+    /// Returns the next sound effect to load, if there is budget left and not
+    /// too many sound effects are loading already.
+    fn next(&mut self) -> Option<SoundEffectKey> {
+        if self.loading.len() >= MAX_PREFETCH_LOADS || self.remaining_count == 0 || self.remaining_bytes == 0 {
+            return None;
+        }
+
+        let sound_effect_key = self.queued.pop_front()?;
+
+        self.remaining_count -= 1;
+        self.loading.insert(sound_effect_key);
+        Some(sound_effect_key)
+    }
+
+    /// Takes a loaded sound effect out of the budget. Returns `false` if it
+    /// doesn't fit the remaining budget anymore, in which case it shouldn't be
+    /// cached and nothing else is prefetched.
+    fn loaded(&mut self, sound_effect_key: SoundEffectKey, size: usize) -> bool {
+        self.loading.remove(&sound_effect_key);
+
+        match self.remaining_bytes.checked_sub(size) {
+            Some(remaining_bytes) => {
+                self.remaining_bytes = remaining_bytes;
+                true
+            }
+            None => {
+                self.queued.clear();
+                self.remaining_bytes = 0;
+                false
+            }
+        }
+    }
 }
 
 /// The audio engine of Korangar. Provides a simple interface to play background
@@ -106,8 +845,8 @@ pub struct AudioEngine<F> {
 }
 
 struct EngineContext<F> {
-    active_emitters: HashMap<AmbientKey, EmitterHandle>,
-    spatial_listener: ListenerHandle,
+    active_emitters: HashMap<AmbientKey, SoundEmitter>,
+    ambient_cycle_jitter: f32,
     ambient_sound: SimpleSlab<AmbientKey, AmbientSoundConfig>,
     spatial_sound_effect_track: TrackHandle,
     async_response_receiver: Receiver<AsyncLoadResult>,
@@ -115,30 +854,73 @@ struct EngineContext<F> {
     background_music_track: TrackHandle,
     background_music_track_mapping: HashMap<String, String>,
     cache: SimpleCache<SoundEffectKey, CachedSoundEffect>,
-    current_background_music_track: Option<BackgroundMusicTrack>,
+    compressed_cache: SimpleCache<SoundEffectKey, CompressedSoundEffect>,
     cycling_ambient: HashMap<AmbientKey, PlayingAmbient>,
+    decoded_compressed: HashMap<SoundEffectKey, StaticSoundData>,
+    emphasized_ambient: HashMap<AmbientKey, AmbientEmphasis>,
     game_file_loader: Arc<F>,
+    global_lowpass: FilterHandle,
     last_listener_update: Instant,
+    listener_position: Point3<f32>,
+    listener_rotation: Quaternion<f32>,
+    listener_transition: Option<ListenerTransition>,
+    load_error_callback: Option<LoadErrorCallback>,
+    load_settings: LoadSettings,
+    loading_music_layers: HashMap<String, LoadingMusicTrack>,
     loading_sound_effect: HashSet<SoundEffectKey>,
     lookup: HashMap<String, SoundEffectKey>,
     manager: AudioManager,
-    object_kdtree: KDTree<AmbientKey, Sphere>,
+    max_active_ambient_sounds: Option<usize>,
+    max_effect_voices: usize,
+    memory_sound_effects: HashMap<SoundEffectKey, Arc<[u8]>>,
+    mono_output: Arc<AtomicBool>,
+    output_levels: Arc<OutputLevels>,
+    music_decode_quality: MusicDecodeQuality,
+    music_layers: HashMap<String, BackgroundMusicTrack>,
+    object_kdtree: KDTree<AmbientKey, AmbientBounds>,
+    pinned_sound_effect: HashMap<SoundEffectKey, PinnedSoundEffect>,
+    playing_sound_effects: Vec<PlayingSoundEffect>,
+    prefetch_queue: PrefetchQueue,
+    prefetch_tracker: PrefetchTracker,
     previous_query_result: Vec<AmbientKey>,
     query_result: Vec<AmbientKey>,
     queued_background_music_track: Option<String>,
     queued_sound_effect: Vec<QueuedSoundEffect>,
-    scene: SpatialSceneHandle,
+    rng: StdRng,
     scratchpad: Vec<AmbientKey>,
+    sound_category_tracks: HashMap<SoundCategory, TrackHandle>,
     sound_effect_paths: GenerationalSlab<SoundEffectKey, String>,
     sound_effect_track: TrackHandle,
+    spatial_output: Option<SpatialOutput>,
+    time_scale: f64,
 }
 
 impl<F: FileLoader> AudioEngine<F> {
     /// Crates a new audio engine.
     pub fn new(game_file_loader: Arc<F>) -> AudioEngine<F> {
+        Self::with_output_mode(game_file_loader, OutputMode::default())
+    }
+
+    /// Crates a new audio engine with the given output mode.
+    pub fn with_output_mode(game_file_loader: Arc<F>, output_mode: OutputMode) -> AudioEngine<F> {
+        // Kira can't add effects to a track after it was created, so the global
+        // low-pass filter is always present and disabled by setting its mix to 0.
+        // The same goes for the mono downmix and the level meter, which are
+        // toggled with a flag. The level meter comes last so it measures the
+        // audio as it is output, but before the main volume is applied.
+        let mut main_track_builder = TrackBuilder::default();
+        let global_lowpass = main_track_builder.add_effect(FilterBuilder::new().mode(FilterMode::LowPass).mix(0.0));
+        let mono_output = main_track_builder.add_effect(MonoDownmix {
+            enabled: Arc::new(AtomicBool::new(false)),
+        });
+        let output_levels = main_track_builder.add_effect(LevelMeter {
+            levels: Arc::new(OutputLevels::default()),
+            left_mean_square: 0.0,
+            right_mean_square: 0.0,
+        });
         let mut manager = AudioManager::<CpalBackend>::new(AudioManagerSettings {
             capacities: Capacities::default(),
-            main_track_builder: TrackBuilder::default(),
+            main_track_builder,
             backend_settings: CpalBackendSettings {
                 device: None,
                 // At sampling rate of 48 kHz 1200 frames take 25 ms.
@@ -146,28 +928,52 @@ impl<F: FileLoader> AudioEngine<F> {
             },
         })
         .expect("Can't initialize audio backend");
-        let mut scene = manager
-            .add_spatial_scene(SpatialSceneSettings::default())
-            .expect("Can't create spatial scene");
         let background_music_track = manager
             .add_sub_track(TrackBuilder::new())
             .expect("Can't create background music track");
         let sound_effect_track = manager.add_sub_track(TrackBuilder::new()).expect("Can't create sound effect track");
+        let sound_category_tracks = SoundCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let track = manager
+                    .add_sub_track(TrackBuilder::new().routes(TrackRoutes::parent(sound_effect_track.id())))
+                    .expect("Can't create sound category track");
+                (category, track)
+            })
+            .collect();
         let spatial_sound_effect_track = manager
             .add_sub_track(TrackBuilder::new())
             .expect("Can't create spatial sound effect track");
-        let position = Vector3::new(0.0, 0.0, 0.0);
-        let orientation = Quaternion::new(0.0, 0.0, 0.0, 0.0);
-        let spatial_listener = scene
-            .add_listener(position, orientation, ListenerSettings {
-                track: spatial_sound_effect_track.id(),
-            })
-            .expect("Can't create ambient listener");
+        let spatial_output = match output_mode {
+            OutputMode::Spatial => {
+                let mut scene = manager
+                    .add_spatial_scene(SpatialSceneSettings::default())
+                    .expect("Can't create spatial scene");
+                let position = Vector3::new(0.0, 0.0, 0.0);
+                let orientation = Quaternion::new(0.0, 0.0, 0.0, 0.0);
+                let listener = scene
+                    .add_listener(position, orientation, ListenerSettings {
+                        track: spatial_sound_effect_track.id(),
+                    })
+                    .expect("Can't create ambient listener");
+
+                Some(SpatialOutput {
+                    scene,
+                    listener,
+                    emitter_limit_reached: false,
+                })
+            }
+            OutputMode::Mono => None,
+        };
         let loading_sound_effect = HashSet::new();
         let cache = SimpleCache::new(
             NonZeroU32::new(MAX_CACHE_COUNT).unwrap(),
             NonZeroUsize::new(MAX_CACHE_SIZE).unwrap(),
         );
+        let compressed_cache = SimpleCache::new(
+            NonZeroU32::new(MAX_COMPRESSED_CACHE_COUNT).unwrap(),
+            NonZeroUsize::new(MAX_COMPRESSED_CACHE_SIZE).unwrap(),
+        );
         let (async_response_sender, async_response_receiver) = channel();
 
         let background_music_track_mapping = parse_background_music_track_mapping(game_file_loader.deref());
@@ -176,7 +982,7 @@ impl<F: FileLoader> AudioEngine<F> {
 
         let engine_context = Mutex::new(EngineContext {
             active_emitters: HashMap::default(),
-            spatial_listener,
+            ambient_cycle_jitter: 0.0,
             ambient_sound: SimpleSlab::default(),
             spatial_sound_effect_track,
             async_response_receiver,
@@ -184,22 +990,51 @@ impl<F: FileLoader> AudioEngine<F> {
             background_music_track,
             background_music_track_mapping,
             cache,
-            current_background_music_track: None,
+            compressed_cache,
             cycling_ambient: HashMap::default(),
+            decoded_compressed: HashMap::default(),
+            emphasized_ambient: HashMap::default(),
             game_file_loader,
+            global_lowpass,
             last_listener_update: Instant::now(),
+            listener_position: Point3::new(0.0, 0.0, 0.0),
+            listener_rotation: Quaternion::one(),
+            listener_transition: None,
+            load_error_callback: None,
+            load_settings: LoadSettings {
+                sound_effect_base_path: SOUND_EFFECT_BASE_PATH.to_string(),
+                normalize_sample_rate: false,
+                resample_quality: ResampleQuality::default(),
+                compressed_threshold: None,
+                thread_pool: None,
+            },
+            loading_music_layers: HashMap::default(),
             loading_sound_effect,
             lookup: HashMap::default(),
             manager,
+            max_active_ambient_sounds: None,
+            max_effect_voices: DEFAULT_MAX_EFFECT_VOICES,
+            memory_sound_effects: HashMap::default(),
+            mono_output,
+            output_levels,
+            music_decode_quality: MusicDecodeQuality::default(),
+            music_layers: HashMap::default(),
             object_kdtree,
+            pinned_sound_effect: HashMap::default(),
+            playing_sound_effects: Vec::default(),
+            prefetch_queue: PrefetchQueue::default(),
+            prefetch_tracker: PrefetchTracker::default(),
             previous_query_result: Vec::default(),
             query_result: Vec::default(),
             queued_background_music_track: None,
             queued_sound_effect: Vec::default(),
-            scene,
+            rng: StdRng::from_entropy(),
             scratchpad: Vec::default(),
+            sound_category_tracks,
             sound_effect_paths: GenerationalSlab::default(),
             sound_effect_track,
+            spatial_output,
+            time_scale: 1.0,
         });
         AudioEngine { engine_context }
     }
@@ -210,7 +1045,7 @@ impl<F: FileLoader> AudioEngine<F> {
         if enable {
             volume = Volume::Amplitude(0.0);
         }
-        self.set_main_volume(volume);
+        self.set_main_volume_with_easing(volume, DEFAULT_VOLUME_EASING);
     }
 
     /// This function needs the full file path with the file extension.
@@ -230,79 +1065,405 @@ impl<F: FileLoader> AudioEngine<F> {
     /// key. If the audio file path was already registers, it will simply return
     /// its key.
     pub fn load(&self, path: &str) -> SoundEffectKey {
-        let mut context = self.engine_context.lock().unwrap();
+        self.engine_context.lock().unwrap().load(path)
+    }
 
-        if let Some(sound_effect_key) = context.lookup.get(path) {
-            return *sound_effect_key;
-        }
+    /// Registers the given audio file path as a UI sound effect. UI sound
+    /// effects are kept outside of the sound effect cache and are never
+    /// evicted, so they are always available for instant playback.
+    pub fn register_ui_sound(&self, path: &str) -> SoundEffectKey {
+        self.engine_context.lock().unwrap().register_ui_sound(path)
+    }
 
-        let sound_effect_key = context.sound_effect_paths.insert(path.to_string()).expect("Mapping slab is full");
-        context.lookup.insert(path.to_string(), sound_effect_key);
+    /// Registers the given audio file paths, queues their loading and pins
+    /// them, so they are kept outside of the sound effect cache and never
+    /// evicted. Unlike prefetched sound effects, pinned sound effects are
+    /// always available for instant playback, which is useful for the sounds
+    /// of the skills on a hotbar. Pins are counted, so a sound effect that is
+    /// pinned twice has to be unpinned twice. Returns the keys in the order of
+    /// the paths.
+    pub fn pin_sound_effects(&self, paths: &[&str]) -> Vec<SoundEffectKey> {
+        let mut context = self.engine_context.lock().unwrap();
+        paths.iter().map(|path| context.pin_sound_effect(path)).collect()
+    }
 
-        spawn_async_load(
-            context.game_file_loader.clone(),
-            context.async_response_sender.clone(),
-            path.to_string(),
-            sound_effect_key,
-        );
+    /// Releases one pin of each of the given sound effects. Sound effects
+    /// without any remaining pins are moved back into the sound effect cache,
+    /// so they can be evicted again. UI sound effects always stay pinned.
+    pub fn unpin_sound_effects(&self, sound_effect_keys: &[SoundEffectKey]) {
+        let mut context = self.engine_context.lock().unwrap();
+        sound_effect_keys
+            .iter()
+            .for_each(|sound_effect_key| context.unpin_sound_effect(*sound_effect_key));
+    }
 
-        sound_effect_key
+    /// Sets the base path that all registered sound effect paths are relative
+    /// to. Defaults to `data\wav`. Only affects sound effects that are loaded
+    /// after the change.
+    pub fn set_sound_effect_base_path(&self, base_path: impl Into<String>) {
+        self.engine_context.lock().unwrap().load_settings.sound_effect_base_path = base_path.into();
     }
 
-    /// Unloads und unregisters the registered audio file.
-    pub fn unload(&self, sound_effect_key: SoundEffectKey) {
-        let mut context = self.engine_context.lock().unwrap();
+    /// Resamples all sound effects to a common sample rate when they are
+    /// loaded, which trades a longer load for cheaper playback. Disabled by
+    /// default. Only affects sound effects that are loaded after the change.
+    pub fn set_normalize_sample_rate(&self, normalize_sample_rate: bool) {
+        self.engine_context.lock().unwrap().load_settings.normalize_sample_rate = normalize_sample_rate;
+    }
 
-        if let Some(path) = context.sound_effect_paths.remove(sound_effect_key) {
-            let _ = context.lookup.remove(&path);
-        }
-        context.loading_sound_effect.remove(&sound_effect_key);
-        let _ = context.cache.remove(&sound_effect_key);
+    /// Sets the algorithm used to resample sound effects to a common sample
+    /// rate, see [`set_normalize_sample_rate`](Self::set_normalize_sample_rate).
+    /// Only affects sound effects that are loaded after the change.
+    pub fn set_resample_quality(&self, resample_quality: ResampleQuality) {
+        self.engine_context.lock().unwrap().load_settings.resample_quality = resample_quality;
     }
 
-    /// Sets the global volume.
-    pub fn set_main_volume(&self, volume: impl Into<Value<Volume>>) {
-        self.engine_context.lock().unwrap().set_main_volume(volume)
+    /// Sets how background music is decoded. Only affects music tracks that
+    /// are started after the change.
+    pub fn set_music_decode_quality(&self, music_decode_quality: MusicDecodeQuality) {
+        self.engine_context.lock().unwrap().music_decode_quality = music_decode_quality;
     }
 
-    /// Sets the volume of the background music.
-    pub fn set_background_music_volume(&self, volume: impl Into<Value<Volume>>) {
-        self.engine_context.lock().unwrap().set_background_music_volume(volume)
+    /// Sound effect files of at least the given size in bytes are kept in
+    /// memory undecoded and decoded every time they are played, instead of
+    /// caching the decoded samples. This saves memory for medium sized sound
+    /// effects that are played occasionally, while still avoiding to load them
+    /// from disk again. The undecoded files share a separate budget of 16 MiB.
+    /// `None` disables this, which is the default. Only affects sound effects
+    /// that are loaded after the change.
+    pub fn set_compressed_threshold(&self, threshold: Option<usize>) {
+        self.engine_context.lock().unwrap().load_settings.compressed_threshold = threshold;
     }
 
-    /// Sets the volume of sound effect.
-    pub fn set_sound_effect_volume(&self, volume: impl Into<Value<Volume>>) {
-        self.engine_context.lock().unwrap().set_sound_effect_volume(volume)
+    /// Sets the number of threads used to load and decode sound effects. With
+    /// `Some`, the audio engine uses a dedicated thread pool, so loading sound
+    /// effects can't starve other work on the global thread pool. `None` uses
+    /// the global thread pool, which is the default. Tasks that are already
+    /// running finish on the pool they were started on.
+    pub fn set_loading_thread_count(&self, thread_count: Option<usize>) {
+        self.engine_context.lock().unwrap().set_loading_thread_count(thread_count)
     }
 
-    /// Sets the volume of spatial sound effects.
-    pub fn set_spatial_sound_effect_volume(&self, volume: impl Into<Value<Volume>>) {
-        self.engine_context.lock().unwrap().set_spatial_sound_effect_volume(volume)
+    /// Returns the number of threads used to load and decode sound effects.
+    pub fn loading_thread_count(&self) -> usize {
+        match &self.engine_context.lock().unwrap().load_settings.thread_pool {
+            Some(thread_pool) => thread_pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
     }
 
-    /// Plays the background music track. Fades out the currently playing
-    /// background music track and then start the new background music
-    /// track.
-    pub fn play_background_music_track(&self, track_name: Option<&str>) {
-        self.engine_context.lock().unwrap().play_background_music_track(track_name)
+    /// Sets a callback that is called whenever a sound effect fails to load.
+    /// While a callback is set, load errors are no longer logged by the audio
+    /// engine.
+    pub fn set_load_error_callback(&self, callback: impl FnMut(&str, &SoundEffectLoadError) + Send + 'static) {
+        self.engine_context.lock().unwrap().load_error_callback = Some(Box::new(callback));
     }
 
-    /// Plays a sound effect.
-    pub fn play_sound_effect(&self, sound_effect_key: SoundEffectKey) {
-        self.engine_context.lock().unwrap().play_sound_effect(sound_effect_key)
+    /// Seeds the random number generator used for all randomized audio
+    /// behavior, making it reproducible. By default the generator is seeded
+    /// from system entropy.
+    pub fn set_rng_seed(&self, seed: u64) {
+        self.engine_context.lock().unwrap().rng = StdRng::seed_from_u64(seed);
     }
 
-    /// Plays a spatial sound effect, which will get removed automatically once
-    /// it finishes playing.
-    pub fn play_spatial_sound_effect(&self, sound_effect_key: SoundEffectKey, position: Point3<f32>, range: f32) {
-        self.engine_context
-            .lock()
-            .unwrap()
-            .play_spatial_sound_effect(sound_effect_key, position, range);
+    /// Sets how much the restart delay of cycling ambient sounds may randomly
+    /// deviate from their cycle, as a fraction of the cycle. Defaults to 0,
+    /// meaning ambient sounds restart exactly after their cycle.
+    pub fn set_ambient_cycle_jitter(&self, jitter: f32) {
+        self.engine_context.lock().unwrap().ambient_cycle_jitter = jitter.clamp(0.0, 1.0);
     }
 
-    /// Sets the listener of the spatial sound. This is normally the camera's
-    /// position and orientation. This should update each frame.
+    /// Limits how many ambient sounds can be active at the same time. Ambient
+    /// sounds with a higher priority are always preferred, ambient sounds with
+    /// the same priority are chosen by their distance to the listener. `None`
+    /// disables the limit, which is the default.
+    pub fn set_max_active_ambient_sounds(&self, max_count: Option<usize>) {
+        self.engine_context.lock().unwrap().max_active_ambient_sounds = max_count;
+    }
+
+    /// Limits how many sound effects can play at the same time. Once the limit
+    /// is reached, playing another sound effect stops the oldest interruptible
+    /// one, see [`AudioEngine::play_sound_effect`]. Lowering the limit
+    /// reduces the CPU time spent on audio in crowded scenes. The limit is
+    /// at least 1 and defaults to 64. Ambient sounds and music are not
+    /// affected.
+    pub fn set_max_effect_voices(&self, max_voices: usize) {
+        self.engine_context.lock().unwrap().set_max_effect_voices(max_voices)
+    }
+
+    /// Returns how many sound effects can play at the same time. See
+    /// [`AudioEngine::set_max_effect_voices`].
+    pub fn max_effect_voices(&self) -> usize {
+        self.engine_context.lock().unwrap().max_effect_voices
+    }
+
+    /// Sets the priority of an ambient sound, which decides which ambient
+    /// sounds stay active once [`set_max_active_ambient_sounds()`] limits the
+    /// number of active ambient sounds. Ambient sounds have a priority of 0 by
+    /// default.
+    ///
+    /// [`set_max_active_ambient_sounds()`]: Self::set_max_active_ambient_sounds
+    pub fn set_ambient_priority(&self, ambient_key: AmbientKey, priority: u8) {
+        if let Some(sound_config) = self.engine_context.lock().unwrap().ambient_sound.get_mut(ambient_key) {
+            sound_config.priority = priority;
+        }
+    }
+
+    /// Unloads und unregisters the registered audio file.
+    pub fn unload(&self, sound_effect_key: SoundEffectKey) {
+        let mut context = self.engine_context.lock().unwrap();
+
+        if let Some(path) = context.sound_effect_paths.remove(sound_effect_key) {
+            let _ = context.lookup.remove(&path);
+        }
+        context.loading_sound_effect.remove(&sound_effect_key);
+        context.memory_sound_effects.remove(&sound_effect_key);
+        context.pinned_sound_effect.remove(&sound_effect_key);
+        context.prefetch_tracker.pending.remove(&sound_effect_key);
+        let _ = context.cache.remove(&sound_effect_key);
+        let _ = context.compressed_cache.remove(&sound_effect_key);
+    }
+
+    /// Returns the size in bytes of all decoded sound effects inside the sound
+    /// effect cache. This is the size the cache budget is checked against, so
+    /// pinned sound effects and compressed sound effects are not included.
+    pub fn cache_memory_bytes(&self) -> usize {
+        self.engine_context.lock().unwrap().cache.size()
+    }
+
+    /// Evicts all cached sound effects that are not used by any of the
+    /// registered ambient sounds. Useful to free memory without having to
+    /// reload the ambient sounds of the current map.
+    pub fn trim_effect_cache_except_ambients(&self) {
+        self.engine_context.lock().unwrap().trim_effect_cache_except_ambients();
+    }
+
+    /// Sets the global volume. The volume changes over half a second,
+    /// following [`DEFAULT_VOLUME_EASING`].
+    pub fn set_main_volume(&self, volume: impl Into<Value<Volume>>) {
+        self.set_main_volume_with_easing(volume, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Sets the global volume. The volume changes over half a second,
+    /// following the given curve.
+    pub fn set_main_volume_with_easing(&self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.engine_context.lock().unwrap().set_main_volume(volume, easing)
+    }
+
+    /// Sets the volume of the background music. The volume changes over half
+    /// a second, following [`DEFAULT_VOLUME_EASING`].
+    pub fn set_background_music_volume(&self, volume: impl Into<Value<Volume>>) {
+        self.set_background_music_volume_with_easing(volume, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Sets the volume of the background music. The volume changes over half
+    /// a second, following the given curve.
+    pub fn set_background_music_volume_with_easing(&self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.engine_context.lock().unwrap().set_background_music_volume(volume, easing)
+    }
+
+    /// Sets the volume of sound effect. The volume changes over half a second,
+    /// following [`DEFAULT_VOLUME_EASING`].
+    pub fn set_sound_effect_volume(&self, volume: impl Into<Value<Volume>>) {
+        self.set_sound_effect_volume_with_easing(volume, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Sets the volume of sound effect. The volume changes over half a second,
+    /// following the given curve.
+    pub fn set_sound_effect_volume_with_easing(&self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.engine_context.lock().unwrap().set_sound_effect_volume(volume, easing)
+    }
+
+    /// Sets the volume of spatial sound effects. The volume changes over half
+    /// a second, following [`DEFAULT_VOLUME_EASING`].
+    pub fn set_spatial_sound_effect_volume(&self, volume: impl Into<Value<Volume>>) {
+        self.set_spatial_sound_effect_volume_with_easing(volume, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Sets the volume of spatial sound effects. The volume changes over half
+    /// a second, following the given curve.
+    pub fn set_spatial_sound_effect_volume_with_easing(&self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.engine_context.lock().unwrap().set_spatial_sound_effect_volume(volume, easing)
+    }
+
+    /// Sets the volume of a sound category. It is applied on top of the sound
+    /// effect volume. The volume changes over half a second, following
+    /// [`DEFAULT_VOLUME_EASING`].
+    pub fn set_sound_category_volume(&self, category: SoundCategory, volume: impl Into<Value<Volume>>) {
+        self.set_sound_category_volume_with_easing(category, volume, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Sets the volume of a sound category. It is applied on top of the sound
+    /// effect volume. The volume changes over half a second, following the
+    /// given curve.
+    pub fn set_sound_category_volume_with_easing(&self, category: SoundCategory, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .set_sound_category_volume(category, volume, easing)
+    }
+
+    /// Applies a low-pass filter with the given cutoff frequency to all audio,
+    /// for example to muffle sounds while the player is deafened or
+    /// underwater. Passing `None` removes the filter.
+    pub fn set_global_lowpass(&self, cutoff_hz: Option<f32>) {
+        self.engine_context.lock().unwrap().set_global_lowpass(cutoff_hz)
+    }
+
+    /// Collapses the final mix to mono by averaging the left and right
+    /// channels. Spatial sounds keep their distance attenuation but lose their
+    /// panning. Intended for players that can only hear on one ear.
+    pub fn set_mono_output(&self, enable: bool) {
+        self.engine_context.lock().unwrap().set_mono_output(enable)
+    }
+
+    /// Enables measuring the output levels, which can then be queried with
+    /// [`AudioEngine::output_levels`]. Disabled by default to avoid the
+    /// overhead. Measuring doesn't change the audio in any way.
+    pub fn set_output_metering(&self, enable: bool) {
+        self.engine_context.lock().unwrap().set_output_metering(enable)
+    }
+
+    /// Returns the RMS levels of the left and right channel of the output
+    /// before the main volume is applied, as amplitudes between 0 and 1.
+    /// Intended for VU meters and visualizers. Both levels are 0 if output
+    /// metering is disabled.
+    pub fn output_levels(&self) -> (f32, f32) {
+        self.engine_context.lock().unwrap().output_levels()
+    }
+
+    /// Scales the playback rate of all playing and newly started sounds, for
+    /// example for slow motion effects. A time scale of 1 is the normal speed
+    /// and 0.5 is half the speed. Defaults to 1.
+    pub fn set_global_time_scale(&self, time_scale: f32) {
+        self.engine_context.lock().unwrap().set_global_time_scale(time_scale)
+    }
+
+    /// Plays the background music track. Fades out the currently playing
+    /// background music track and then start the new background music
+    /// track.
+    pub fn play_background_music_track(&self, track_name: Option<&str>) {
+        self.engine_context.lock().unwrap().play_background_music_track(track_name)
+    }
+
+    /// Pauses the background music over the given fade duration and
+    /// remembers the playback position, for example to briefly mute the
+    /// music during a loading transition.
+    pub fn pause_background_music(&self, fade: Duration) {
+        self.engine_context.lock().unwrap().pause_background_music(fade)
+    }
+
+    /// Resumes the background music from the position it was paused at. If
+    /// the underlying sound was dropped in the meantime, the track is started
+    /// again at that position.
+    pub fn resume_background_music(&self, fade: Duration) {
+        self.engine_context.lock().unwrap().resume_background_music(fade)
+    }
+
+    /// Moves the playback position of the background music to the given
+    /// position in seconds. A paused track stays paused.
+    pub fn seek_background_music(&self, position: f64) {
+        self.engine_context.lock().unwrap().seek_background_music(position)
+    }
+
+    /// Plays a background music track on a named layer, in addition to the
+    /// tracks playing on all other layers. This allows for dynamic music, for
+    /// example by fading in a combat layer over the regular background music
+    /// with [`AudioEngine::set_music_layer_volume`]. Passing `None` fades out
+    /// the layer.
+    pub fn play_music_layer(&self, layer_name: &str, track_name: Option<&str>, volume: impl Into<Value<Volume>>) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .play_music_layer(layer_name, track_name, volume.into())
+    }
+
+    /// Changes the volume of a music layer over the given fade duration,
+    /// following [`DEFAULT_VOLUME_EASING`].
+    pub fn set_music_layer_volume(&self, layer_name: &str, volume: impl Into<Value<Volume>>, fade: Duration) {
+        self.set_music_layer_volume_with_easing(layer_name, volume, fade, DEFAULT_VOLUME_EASING)
+    }
+
+    /// Changes the volume of a music layer over the given fade duration,
+    /// following the given curve.
+    pub fn set_music_layer_volume_with_easing(&self, layer_name: &str, volume: impl Into<Value<Volume>>, fade: Duration, easing: Easing) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .set_music_layer_volume(layer_name, volume, fade, easing)
+    }
+
+    /// Plays a sound effect. Sound effects with a category are mixed on the
+    /// track of that category, see
+    /// [`AudioEngine::set_sound_category_volume`].
+    ///
+    /// A non-interruptible playback always plays until the end, even if the
+    /// voice limit is reached. If only non-interruptible playbacks are active
+    /// once the limit is reached, new sound effects are dropped instead. The
+    /// flag only applies to this playback, other playbacks of the same sound
+    /// effect are not affected.
+    pub fn play_sound_effect(&self, sound_effect_key: SoundEffectKey, category: Option<SoundCategory>, non_interruptible: bool) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .play_sound_effect(sound_effect_key, category, non_interruptible);
+    }
+
+    /// Plays a sound effect that is decoded from the given bytes instead of
+    /// being loaded through the file loader, for example for content that is
+    /// not part of any archive. The decoded sound effect is cached under the
+    /// given key hint, so repeated calls with the same hint reuse it. Returns
+    /// the key of the sound effect, which can be used like any other sound
+    /// effect key.
+    pub fn play_sound_effect_from_memory(&self, bytes: Arc<[u8]>, key_hint: &str) -> SoundEffectKey {
+        self.engine_context.lock().unwrap().play_sound_effect_from_memory(bytes, key_hint)
+    }
+
+    /// Tries to play a sound effect without ever waiting. The request is
+    /// dropped if the engine is currently busy or too many sound effects are
+    /// already waiting to be loaded. See [`AudioEngine::play_sound_effect`].
+    pub fn try_play_sound_effect(
+        &self,
+        sound_effect_key: SoundEffectKey,
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    ) -> SoundEffectPlayback {
+        match self.engine_context.try_lock() {
+            Ok(mut engine_context) => engine_context.play_sound_effect(sound_effect_key, category, non_interruptible),
+            Err(TryLockError::WouldBlock) => SoundEffectPlayback::Dropped,
+            Err(TryLockError::Poisoned(error)) => panic!("{error}"),
+        }
+    }
+
+    /// Plays a UI sound effect without spatialization. The audio file path is
+    /// registered as a UI sound effect if it wasn't already.
+    pub fn play_ui_sound(&self, path: &str) {
+        self.engine_context.lock().unwrap().play_ui_sound(path)
+    }
+
+    /// Plays a spatial sound effect, which will get removed automatically once
+    /// it finishes playing. The category only applies if the sound isn't
+    /// played through the spatial scene, since the spatial scene mixes all of
+    /// its sounds on the spatial sound effect track. See
+    /// [`AudioEngine::play_sound_effect`] for non-interruptible playbacks.
+    pub fn play_spatial_sound_effect(
+        &self,
+        sound_effect_key: SoundEffectKey,
+        position: Point3<f32>,
+        range: f32,
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    ) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .play_spatial_sound_effect(sound_effect_key, position, range, category, non_interruptible);
+    }
+
+    /// Sets the listener of the spatial sound. This is normally the camera's
+    /// position and orientation. This should update each frame. While the
+    /// listener is moved with [`AudioEngine::move_listener_smooth`], this
+    /// changes the target of the movement instead.
     pub fn set_spatial_listener(&self, position: Point3<f32>, view_direction: Vector3<f32>, look_up: Vector3<f32>) {
         self.engine_context
             .lock()
@@ -310,6 +1471,18 @@ impl<F: FileLoader> AudioEngine<F> {
             .set_spatial_listener(position, view_direction, look_up)
     }
 
+    /// Moves the listener of the spatial sound to the given position and
+    /// rotation over the given duration, so that spatial sounds pan smoothly
+    /// during camera transitions instead of jumping. The rotation is
+    /// interpreted like in [`facing_vectors`]. The movement is advanced by
+    /// [`AudioEngine::update`].
+    pub fn move_listener_smooth(&self, target_position: Point3<f32>, target_orientation: Quaternion<f32>, duration: Duration) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .move_listener_smooth(target_position, target_orientation, duration)
+    }
+
     /// Adds a static, spatial sound, that is used for ambient sound inside the
     /// world.
     ///
@@ -318,6 +1491,10 @@ impl<F: FileLoader> AudioEngine<F> {
     ///
     /// [`clear_ambient_sound()`] must be called if the "map" or "level" is
     /// switched.
+    ///
+    /// Adding the same sound effect with (nearly) the same bounds again
+    /// returns the key of the existing ambient sound and keeps the higher of
+    /// both volumes, instead of playing the sound twice.
     pub fn add_ambient_sound(
         &self,
         sound_effect_key: SoundEffectKey,
@@ -325,11 +1502,47 @@ impl<F: FileLoader> AudioEngine<F> {
         range: f32,
         volume: f32,
         cycle: Option<f32>,
+    ) -> AmbientKey {
+        self.engine_context.lock().unwrap().add_ambient_sound(
+            sound_effect_key,
+            AmbientBounds::Sphere(Sphere::new(position, range)),
+            volume,
+            cycle,
+        )
+    }
+
+    /// Adds a static, spatial sound with arbitrary bounds, that is used for
+    /// ambient sound inside the world. Boxes and capsules are better suited
+    /// for extended sources like rivers or roads than a single sphere.
+    ///
+    /// [`prepare_ambient_sound_world()`] must be called once all ambient sound
+    /// have been added.
+    ///
+    /// [`clear_ambient_sound()`] must be called if the "map" or "level" is
+    /// switched.
+    ///
+    /// Adding the same sound effect with (nearly) the same bounds again
+    /// returns the key of the existing ambient sound and keeps the higher of
+    /// both volumes, instead of playing the sound twice.
+    pub fn add_ambient_sound_with_bounds(
+        &self,
+        sound_effect_key: SoundEffectKey,
+        bounds: AmbientBounds,
+        volume: f32,
+        cycle: Option<f32>,
     ) -> AmbientKey {
         self.engine_context
             .lock()
             .unwrap()
-            .add_ambient_sound(sound_effect_key, position, range, volume, cycle)
+            .add_ambient_sound(sound_effect_key, bounds, volume, cycle)
+    }
+
+    /// Fades out a single ambient sound over the given duration, without
+    /// affecting the other ambient sounds. The ambient sound stays registered
+    /// and starts playing again once the listener leaves and re-enters its
+    /// range.
+    pub fn stop_ambient(&self, ambient_key: AmbientKey, fade: Duration) {
+        self.engine_context.lock().unwrap().stop_ambient(ambient_key, fade)
     }
 
     /// Removes all ambient sound emitters from the spatial scene.
@@ -342,6 +1555,67 @@ impl<F: FileLoader> AudioEngine<F> {
         self.engine_context.lock().unwrap().prepare_ambient_sound_world()
     }
 
+    /// Loads the sound effects of the given ambient sounds into the cache
+    /// ahead of time, so that they don't start late once the listener comes
+    /// into range. Sound effects are only loaded once and only as many as fit
+    /// into the free space of the cache, in the given order. Replaces the
+    /// sound effects of the previous call that weren't loaded yet.
+    pub fn prefetch_ambient_sounds(&self, ambient_keys: &[AmbientKey]) {
+        self.engine_context.lock().unwrap().prefetch_ambient_sounds(ambient_keys)
+    }
+
+    /// Returns how many prefetched sound effects were played or evicted from
+    /// the cache before they could be played.
+    pub fn prefetch_statistics(&self) -> PrefetchStatistics {
+        self.engine_context.lock().unwrap().prefetch_tracker.statistics()
+    }
+
+    /// Prefetches fewer sound effects the more prefetched sound effects are
+    /// evicted from the cache before they are played, so prefetching doesn't
+    /// thrash the cache on systems with little memory. Disabled by default.
+    pub fn set_adaptive_prefetch(&self, enabled: bool) {
+        self.engine_context.lock().unwrap().prefetch_tracker.adaptive = enabled;
+    }
+
+    /// Temporarily multiplies the volume of an ambient sound with the given
+    /// gain, for example to draw attention to it. The volume fades back to the
+    /// configured volume once the duration has passed. Emphasizing an ambient
+    /// sound again replaces the previous gain and duration.
+    pub fn emphasize_ambient(&self, ambient_key: AmbientKey, gain: f32, duration: Duration) {
+        self.engine_context.lock().unwrap().emphasize_ambient(ambient_key, gain, duration)
+    }
+
+    /// Returns the volume of an ambient sound after the attenuation by its
+    /// distance to the listener and its emphasis, or `None` if the ambient
+    /// sound isn't active. The volumes of the tracks are not taken into
+    /// account.
+    pub fn ambient_effective_volume(&self, ambient_key: AmbientKey) -> Option<f32> {
+        self.engine_context.lock().unwrap().ambient_effective_volume(ambient_key)
+    }
+
+    /// Captures the currently playing background music track, including its
+    /// playback position, and all registered ambient sounds.
+    pub fn snapshot(&self) -> AudioSnapshot {
+        self.engine_context.lock().unwrap().snapshot()
+    }
+
+    /// Restores a snapshot taken with [`AudioEngine::snapshot`]. Music that is
+    /// already playing and ambient sounds that didn't change keep playing
+    /// without fading out, so this should be used instead of re-adding all
+    /// ambient sounds when the same map is loaded again.
+    pub fn restore(&self, snapshot: AudioSnapshot) {
+        self.engine_context.lock().unwrap().restore(snapshot)
+    }
+
+    /// Plays a short silent sound through every track and the spatial scene,
+    /// so the audio backend is ready before the first real sound is played.
+    /// The spatial sound is played from an emitter with the given range, which
+    /// should match the range of the spatial sound effects played later.
+    /// Should be called during a loading screen.
+    pub fn warm_up(&self, spatial_range: f32) {
+        self.engine_context.lock().unwrap().warm_up(spatial_range)
+    }
+
     /// Updates the internal state of the audio engine. Should be called once
     /// each frame.
     pub fn update(&self) {
@@ -350,48 +1624,247 @@ impl<F: FileLoader> AudioEngine<F> {
 }
 
 impl<F: FileLoader> EngineContext<F> {
-    fn set_main_volume(&mut self, volume: impl Into<Value<Volume>>) {
-        self.manager.main_track().set_volume(volume, Tween {
+    fn load(&mut self, path: &str) -> SoundEffectKey {
+        if let Some(sound_effect_key) = self.lookup.get(path) {
+            return *sound_effect_key;
+        }
+
+        let sound_effect_key = self.sound_effect_paths.insert(path.to_string()).expect("Mapping slab is full");
+        self.lookup.insert(path.to_string(), sound_effect_key);
+        self.loading_sound_effect.insert(sound_effect_key);
+
+        spawn_async_load(
+            self.game_file_loader.clone(),
+            self.async_response_sender.clone(),
+            self.load_settings.clone(),
+            path.to_string(),
+            None,
+            sound_effect_key,
+            0,
+        );
+
+        sound_effect_key
+    }
+
+    fn register_ui_sound(&mut self, path: &str) -> SoundEffectKey {
+        let sound_effect_key = self.load_pinned(path);
+
+        if let Some(pinned_sound_effect) = self.pinned_sound_effect.get_mut(&sound_effect_key) {
+            pinned_sound_effect.ui_sound = true;
+        }
+
+        sound_effect_key
+    }
+
+    fn pin_sound_effect(&mut self, path: &str) -> SoundEffectKey {
+        let sound_effect_key = self.load_pinned(path);
+
+        if let Some(pinned_sound_effect) = self.pinned_sound_effect.get_mut(&sound_effect_key) {
+            pinned_sound_effect.pin_count += 1;
+        }
+
+        sound_effect_key
+    }
+
+    /// Loads the sound effect outside of the sound effect cache, without
+    /// adding a pin.
+    fn load_pinned(&mut self, path: &str) -> SoundEffectKey {
+        let sound_effect_key = self.load(path);
+
+        if !self.pinned_sound_effect.contains_key(&sound_effect_key) {
+            // Move the sound effect out of the cache if it was already loaded.
+            let data = self
+                .cache
+                .remove(&sound_effect_key)
+                .map(|cached_sound_effect| cached_sound_effect.0);
+
+            // Sound effects that are only kept undecoded, or that failed to load
+            // before, need to be loaded again to be pinned.
+            if data.is_none() && self.loading_sound_effect.insert(sound_effect_key) {
+                spawn_async_load(
+                    self.game_file_loader.clone(),
+                    self.async_response_sender.clone(),
+                    self.load_settings.clone(),
+                    path.to_string(),
+                    self.memory_sound_effects.get(&sound_effect_key).cloned(),
+                    sound_effect_key,
+                    0,
+                );
+            }
+
+            self.pinned_sound_effect.insert(sound_effect_key, PinnedSoundEffect {
+                data,
+                ..Default::default()
+            });
+        }
+
+        sound_effect_key
+    }
+
+    fn unpin_sound_effect(&mut self, sound_effect_key: SoundEffectKey) {
+        let Some(pinned_sound_effect) = self.pinned_sound_effect.get_mut(&sound_effect_key) else {
+            return;
+        };
+
+        if !pinned_sound_effect.unpin() {
+            return;
+        }
+
+        // Sound effects that are still loading will be put into the cache once
+        // they are loaded.
+        if let Some(PinnedSoundEffect { data: Some(data), .. }) = self.pinned_sound_effect.remove(&sound_effect_key)
+            && let Err(_error) = self.cache.insert(sound_effect_key, CachedSoundEffect(data))
+        {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] pinned audio file is too big for cache: {:?}", "error".red(), _error);
+        }
+    }
+
+    fn play_ui_sound(&mut self, path: &str) {
+        let sound_effect_key = self.register_ui_sound(path);
+        self.play_sound_effect(sound_effect_key, Some(SoundCategory::Interface), false);
+    }
+
+    fn set_loading_thread_count(&mut self, thread_count: Option<usize>) {
+        let Some(thread_count) = thread_count else {
+            self.load_settings.thread_pool = None;
+            return;
+        };
+
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(thread_count)
+            .thread_name(|index| format!("audio-loader-{index}"))
+            .build();
+
+        match thread_pool {
+            Ok(thread_pool) => self.load_settings.thread_pool = Some(Arc::new(thread_pool)),
+            Err(_error) => {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] can't create audio loading thread pool: {:?}", "error".red(), _error);
+            }
+        }
+    }
+
+    fn set_main_volume(&mut self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.manager.main_track().set_volume(normalize_volume_value(volume), Tween {
             duration: Duration::from_millis(500),
+            easing,
             ..Default::default()
         });
     }
 
-    fn set_background_music_volume(&mut self, volume: impl Into<Value<Volume>>) {
-        self.background_music_track.set_volume(volume, Tween {
+    fn set_background_music_volume(&mut self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.background_music_track.set_volume(normalize_volume_value(volume), Tween {
             duration: Duration::from_millis(500),
+            easing,
             ..Default::default()
         });
     }
 
-    fn set_sound_effect_volume(&mut self, volume: impl Into<Value<Volume>>) {
-        self.sound_effect_track.set_volume(volume, Tween {
+    fn set_sound_effect_volume(&mut self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.sound_effect_track.set_volume(normalize_volume_value(volume), Tween {
             duration: Duration::from_millis(500),
+            easing,
             ..Default::default()
         });
     }
 
-    fn set_spatial_sound_effect_volume(&mut self, volume: impl Into<Value<Volume>>) {
-        self.spatial_sound_effect_track.set_volume(volume, Tween {
+    fn set_spatial_sound_effect_volume(&mut self, volume: impl Into<Value<Volume>>, easing: Easing) {
+        self.spatial_sound_effect_track.set_volume(normalize_volume_value(volume), Tween {
+            duration: Duration::from_millis(500),
+            easing,
+            ..Default::default()
+        });
+    }
+
+    fn set_sound_category_volume(&mut self, category: SoundCategory, volume: impl Into<Value<Volume>>, easing: Easing) {
+        let Some(track) = self.sound_category_tracks.get_mut(&category) else {
+            return;
+        };
+
+        track.set_volume(normalize_volume_value(volume), Tween {
             duration: Duration::from_millis(500),
+            easing,
             ..Default::default()
         });
     }
 
+    fn set_global_lowpass(&mut self, cutoff_hz: Option<f32>) {
+        let tween = Tween {
+            duration: Duration::from_millis(500),
+            ..Default::default()
+        };
+
+        match cutoff_hz {
+            Some(cutoff_hz) => {
+                self.global_lowpass.set_cutoff(cutoff_hz as f64, tween);
+                self.global_lowpass.set_mix(1.0, tween);
+            }
+            None => self.global_lowpass.set_mix(0.0, tween),
+        }
+    }
+
+    fn set_mono_output(&mut self, enable: bool) {
+        self.mono_output.store(enable, AtomicOrdering::Relaxed);
+    }
+
+    fn set_output_metering(&mut self, enable: bool) {
+        self.output_levels.enabled.store(enable, AtomicOrdering::Relaxed);
+
+        if !enable {
+            self.output_levels.left.store(0.0f32.to_bits(), AtomicOrdering::Relaxed);
+            self.output_levels.right.store(0.0f32.to_bits(), AtomicOrdering::Relaxed);
+        }
+    }
+
+    fn output_levels(&self) -> (f32, f32) {
+        let left = f32::from_bits(self.output_levels.left.load(AtomicOrdering::Relaxed));
+        let right = f32::from_bits(self.output_levels.right.load(AtomicOrdering::Relaxed));
+        (left, right)
+    }
+
+    fn set_global_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0) as f64;
+
+        let tween = Tween::default();
+        let now = Instant::now();
+
+        for playing in self.music_layers.values_mut() {
+            let position = playing.elapsed(now);
+            playing.rebase(now, position, self.time_scale);
+            playing.handle.set_playback_rate(self.time_scale, tween);
+        }
+
+        for playing in self.cycling_ambient.values_mut() {
+            playing.handle.set_playback_rate(self.time_scale, tween);
+        }
+
+        for emitter in self.active_emitters.values_mut() {
+            if let Some(handle) = emitter.handle_mut() {
+                handle.set_playback_rate(self.time_scale, tween);
+            }
+        }
+
+        for playing in self.playing_sound_effects.iter_mut() {
+            playing.handle.set_playback_rate(self.time_scale, tween);
+        }
+    }
+
     fn play_background_music_track(&mut self, track_name: Option<&str>) {
         let Some(track_name) = track_name else {
-            if let Some(playing) = self.current_background_music_track.as_mut() {
+            self.loading_music_layers.remove(DEFAULT_MUSIC_LAYER);
+
+            if let Some(mut playing) = self.music_layers.remove(DEFAULT_MUSIC_LAYER) {
                 playing.handle.stop(Tween {
                     duration: Duration::from_secs(1),
                     ..Default::default()
                 });
             }
 
-            self.current_background_music_track = None;
             return;
         };
 
-        if let Some(playing) = self.current_background_music_track.as_mut()
+        if let Some(playing) = self.music_layers.get_mut(DEFAULT_MUSIC_LAYER)
             && (playing.handle.state() == PlaybackState::Playing || playing.handle.state() == PlaybackState::Stopping)
         {
             if playing.track_name.as_str() == track_name {
@@ -409,234 +1882,820 @@ impl<F: FileLoader> EngineContext<F> {
             return;
         }
 
-        self.change_background_music_track(track_name);
+        self.change_background_music_track(track_name, 0.0);
     }
 
-    fn play_sound_effect(&mut self, sound_effect_key: SoundEffectKey) {
-        if let Some(data) = self
-            .cache
-            .get(&sound_effect_key)
-            .map(|cached_sound_effect| cached_sound_effect.0.clone())
-        {
-            let data = data.output_destination(&self.sound_effect_track);
-            if let Err(_error) = self.manager.play(data.clone()) {
-                #[cfg(feature = "debug")]
-                print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
+    fn pause_background_music(&mut self, fade: Duration) {
+        let Some(playing) = self.music_layers.get_mut(DEFAULT_MUSIC_LAYER) else {
+            return;
+        };
+
+        if playing.playing_since.is_none() || playing.handle.state() != PlaybackState::Playing {
+            return;
+        }
+
+        playing.start_position = playing.elapsed(Instant::now());
+        playing.playing_since = None;
+        playing.handle.pause(Tween {
+            duration: fade,
+            ..Default::default()
+        });
+    }
+
+    fn resume_background_music(&mut self, fade: Duration) {
+        let Some(playing) = self.music_layers.get_mut(DEFAULT_MUSIC_LAYER) else {
+            return;
+        };
+
+        if playing.playing_since.is_some() {
+            return;
+        }
+
+        let position = playing.start_position;
+
+        match playing.handle.state() {
+            PlaybackState::Pausing | PlaybackState::Paused => {
+                playing.handle.seek_to(position);
+                playing.handle.resume(Tween {
+                    duration: fade,
+                    ..Default::default()
+                });
+                playing.playing_since = Some(Instant::now());
+            }
+            _ => {
+                let track_name = playing.track_name.clone();
+
+                self.queued_background_music_track = None;
+                self.change_background_music_track(&track_name, position);
+            }
+        }
+    }
+
+    fn seek_background_music(&mut self, position: f64) {
+        let Some(playing) = self.music_layers.get_mut(DEFAULT_MUSIC_LAYER) else {
+            return;
+        };
+
+        let position = position.max(0.0);
+
+        match playing.handle.state() {
+            PlaybackState::Playing | PlaybackState::Pausing | PlaybackState::Paused => {
+                playing.handle.seek_to(position);
+                playing.rebase(Instant::now(), position, self.time_scale);
+            }
+            _ => {
+                let track_name = playing.track_name.clone();
+
+                self.queued_background_music_track = None;
+                self.change_background_music_track(&track_name, position);
+            }
+        }
+    }
+
+    fn play_music_layer(&mut self, layer_name: &str, track_name: Option<&str>, volume: Value<Volume>) {
+        let volume = normalize_volume_value(volume);
+
+        let tween = Tween {
+            duration: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        let Some(track_name) = track_name else {
+            self.loading_music_layers.remove(layer_name);
+
+            if let Some(mut playing) = self.music_layers.remove(layer_name) {
+                playing.handle.stop(tween);
             }
 
             return;
+        };
+
+        if let Some(playing) = self.music_layers.get_mut(layer_name) {
+            if playing.track_name == track_name && playing.handle.state() == PlaybackState::Playing {
+                playing.handle.set_volume(volume, tween);
+                return;
+            }
+
+            // Cross fade to the new track.
+            playing.handle.stop(tween);
+        }
+
+        self.change_music_layer_track(layer_name, track_name, 0.0, volume);
+    }
+
+    fn set_music_layer_volume(&mut self, layer_name: &str, volume: impl Into<Value<Volume>>, fade: Duration, easing: Easing) {
+        let Some(playing) = self.music_layers.get_mut(layer_name) else {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] music layer {:?} is not playing", "error".red(), layer_name);
+            return;
+        };
+
+        playing.handle.set_volume(normalize_volume_value(volume), Tween {
+            duration: fade,
+            easing,
+            ..Default::default()
+        });
+    }
+
+    fn set_max_effect_voices(&mut self, max_voices: usize) {
+        self.max_effect_voices = max_voices.max(1);
+        steal_voices(&mut self.playing_sound_effects, self.max_effect_voices);
+    }
+
+    fn play_sound_effect(
+        &mut self,
+        sound_effect_key: SoundEffectKey,
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    ) -> SoundEffectPlayback {
+        self.prefetch_tracker.mark_used(sound_effect_key);
+
+        if let Some(data) = self
+            .pinned_sound_effect
+            .get(&sound_effect_key)
+            .and_then(|pinned_sound_effect| pinned_sound_effect.data.clone())
+            .or_else(|| {
+                self.cache
+                    .get(&sound_effect_key)
+                    .map(|cached_sound_effect| cached_sound_effect.0.clone())
+            })
+        {
+            let track = category_track(&self.sound_effect_track, &self.sound_category_tracks, category);
+            let data = data.output_destination(track).playback_rate(self.time_scale);
+
+            return play_effect_voice(
+                &mut self.manager,
+                &mut self.playing_sound_effects,
+                self.max_effect_voices,
+                non_interruptible,
+                data,
+            );
         }
 
         queue_sound_effect_playback(
             self.game_file_loader.clone(),
             self.async_response_sender.clone(),
+            self.load_settings.clone(),
             &self.sound_effect_paths,
+            &self.memory_sound_effects,
+            &mut self.compressed_cache,
+            &mut self.loading_sound_effect,
             &mut self.queued_sound_effect,
             sound_effect_key,
-            QueuedSoundEffectType::Sound,
-        );
+            QueuedSoundEffectType::Sound {
+                category,
+                non_interruptible,
+            },
+        )
     }
 
-    fn play_spatial_sound_effect(&mut self, sound_effect_key: SoundEffectKey, position: Point3<f32>, range: f32) {
-        // Kira uses a RH coordinate system, so we need to convert our LH vectors.
-        let position = Vector3::new(position.x, position.y, -position.z);
+    fn play_sound_effect_from_memory(&mut self, bytes: Arc<[u8]>, key_hint: &str) -> SoundEffectKey {
+        let path = format!("{MEMORY_SOUND_EFFECT_PREFIX}{key_hint}");
+
+        let sound_effect_key = match self.lookup.get(&path) {
+            Some(sound_effect_key) => *sound_effect_key,
+            None => {
+                let sound_effect_key = self.sound_effect_paths.insert(path.clone()).expect("Mapping slab is full");
+                self.lookup.insert(path, sound_effect_key);
+                self.memory_sound_effects.insert(sound_effect_key, bytes);
+                sound_effect_key
+            }
+        };
+
+        self.play_sound_effect(sound_effect_key, None, false);
+
+        sound_effect_key
+    }
+
+    fn play_spatial_sound_effect(
+        &mut self,
+        sound_effect_key: SoundEffectKey,
+        position: Point3<f32>,
+        range: f32,
+        category: Option<SoundCategory>,
+        non_interruptible: bool,
+    ) {
+        self.prefetch_tracker.mark_used(sound_effect_key);
 
         if let Some(data) = self
             .cache
             .get(&sound_effect_key)
             .map(|cached_sound_effect| cached_sound_effect.0.clone())
         {
-            let settings = EmitterSettings {
-                distances: EmitterDistances {
-                    min_distance: 5.0,
-                    max_distance: range,
-                },
-                attenuation_function: Some(Easing::Linear),
-                enable_spatialization: true,
-                persist_until_sounds_finish: true,
-            };
-
-            match self.scene.add_emitter(position, settings) {
-                Ok(emitter_handle) => {
-                    let data = adjust_ambient_sound(data, &emitter_handle, 1.0);
+            let emitter = create_emitter(self.spatial_output.as_mut(), position, range);
+            let track = category_track(&self.sound_effect_track, &self.sound_category_tracks, category);
+            let data = route_to_emitter(data, &emitter, 1.0, self.listener_position, track);
+
+            play_effect_voice(
+                &mut self.manager,
+                &mut self.playing_sound_effects,
+                self.max_effect_voices,
+                non_interruptible,
+                data.playback_rate(self.time_scale),
+            );
 
-                    if let Err(_error) = self.manager.play(data) {
-                        #[cfg(feature = "debug")]
-                        print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
-                    }
-                }
-                Err(_error) => {
-                    #[cfg(feature = "debug")]
-                    print_debug!("[{}] can't add spatial sound emitter: {:?}", "error".red(), _error);
-                }
-            };
+            return;
         }
 
         queue_sound_effect_playback(
             self.game_file_loader.clone(),
             self.async_response_sender.clone(),
+            self.load_settings.clone(),
             &self.sound_effect_paths,
+            &self.memory_sound_effects,
+            &mut self.compressed_cache,
+            &mut self.loading_sound_effect,
             &mut self.queued_sound_effect,
             sound_effect_key,
-            QueuedSoundEffectType::SpatialSound { position, range },
+            QueuedSoundEffectType::SpatialSound {
+                position,
+                range,
+                category,
+                non_interruptible,
+            },
         );
     }
 
     fn set_spatial_listener(&mut self, position: Point3<f32>, view_direction: Vector3<f32>, look_up: Vector3<f32>) {
+        if let Some(transition) = self.listener_transition.as_mut() {
+            transition.target_position = position;
+            transition.target_rotation = facing_rotation(view_direction, look_up);
+            return;
+        }
+
+        self.apply_spatial_listener(position, view_direction, look_up);
+    }
+
+    fn move_listener_smooth(&mut self, target_position: Point3<f32>, target_orientation: Quaternion<f32>, duration: Duration) {
+        let now = Instant::now();
+
+        // Start from wherever the listener currently is, even if it is still
+        // moving, so that interrupting a transition doesn't cause a jump.
+        let (start_position, start_rotation) = match &self.listener_transition {
+            Some(transition) => {
+                let (position, rotation, _) = transition.transform_at(now);
+                (position, rotation)
+            }
+            None => (self.listener_position, self.listener_rotation),
+        };
+
+        self.listener_transition = Some(ListenerTransition {
+            start_position,
+            start_rotation,
+            target_position,
+            target_rotation: target_orientation.normalize(),
+            started: now,
+            duration,
+        });
+    }
+
+    fn update_listener_transition(&mut self) {
+        let Some(transition) = &self.listener_transition else {
+            return;
+        };
+
+        let (position, rotation, complete) = transition.transform_at(Instant::now());
+        let (view_direction, look_up) = facing_vectors(rotation);
+
+        // The transition is only done once the final transform reached the
+        // listener, which is only updated every 50 ms.
+        if self.apply_spatial_listener(position, view_direction, look_up) && complete {
+            self.listener_transition = None;
+        }
+    }
+
+    /// Moves the listener right away. Returns `false` if the spatial listener
+    /// was updated too recently to apply the new transform.
+    fn apply_spatial_listener(&mut self, position: Point3<f32>, view_direction: Vector3<f32>, look_up: Vector3<f32>) -> bool {
+        self.listener_position = position;
+        self.listener_rotation = facing_rotation(view_direction, look_up);
+
         let listener = Sphere::new(position, 10.0);
 
         self.query_result.clear();
         self.object_kdtree.query(&listener, &mut self.query_result);
+
+        if let Some(max_count) = self.max_active_ambient_sounds {
+            let ambient_sound = &self.ambient_sound;
+            limit_ambient_sounds(&mut self.query_result, max_count, |ambient_key| {
+                ambient_sound.get(ambient_key).map_or((0, f32::MAX), |sound_config| {
+                    let distance = sound_config.bounds.emitter_position(position).distance(position);
+                    (sound_config.priority, distance)
+                })
+            });
+        }
+
         self.query_result.sort_unstable();
 
-        // Add ambient sound that came into reach.
-        difference(&mut self.query_result, &mut self.previous_query_result, &mut self.scratchpad);
+        // Add ambient sound that came into reach.
+        difference(&mut self.query_result, &mut self.previous_query_result, &mut self.scratchpad);
+
+        for ambient_key in self.scratchpad.iter().copied() {
+            let Some(sound_config) = self.ambient_sound.get(ambient_key) else {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] can't find sound config for: {:?}", "error".red(), ambient_key);
+                continue;
+            };
+
+            let emitter_position = sound_config.bounds.emitter_position(position);
+            let mut emitter = create_emitter(self.spatial_output.as_mut(), emitter_position, sound_config.bounds.range());
+
+            let sound_effect_key = sound_config.sound_effect_key;
+            self.prefetch_tracker.mark_used(sound_effect_key);
+
+            if let Some(data) = self
+                .cache
+                .get(&sound_effect_key)
+                .map(|cached_sound_effect| cached_sound_effect.0.clone())
+            {
+                let volume = sound_config.volume * emphasis_gain(&self.emphasized_ambient, ambient_key);
+                let data = route_to_emitter(data, &emitter, volume, position, &self.sound_effect_track);
+                match self.manager.play(data.clone().playback_rate(self.time_scale)) {
+                    Ok(handle) => {
+                        if let Some(cycle) = sound_config.cycle {
+                            let restart_delay = jittered_cycle(&mut self.rng, cycle, self.ambient_cycle_jitter);
+                            self.cycling_ambient.insert(ambient_key, PlayingAmbient {
+                                data,
+                                handle,
+                                cycle,
+                                restart_delay,
+                                last_start: Instant::now(),
+                            });
+                        } else {
+                            *emitter.handle_mut() = Some(handle);
+                        }
+                    }
+                    Err(_error) => {
+                        #[cfg(feature = "debug")]
+                        print_debug!("[{}] can't ambient sound effect: {:?}", "error".red(), _error);
+                    }
+                }
+            } else {
+                queue_sound_effect_playback(
+                    self.game_file_loader.clone(),
+                    self.async_response_sender.clone(),
+                    self.load_settings.clone(),
+                    &self.sound_effect_paths,
+                    &self.memory_sound_effects,
+                    &mut self.compressed_cache,
+                    &mut self.loading_sound_effect,
+                    &mut self.queued_sound_effect,
+                    sound_effect_key,
+                    QueuedSoundEffectType::AmbientSound { ambient_key },
+                );
+            }
+
+            self.active_emitters.insert(ambient_key, emitter);
+        }
+
+        // Remove ambient sound that are out of reach.
+        difference(&mut self.previous_query_result, &mut self.query_result, &mut self.scratchpad);
+        for ambient_key in self.scratchpad.iter() {
+            let _ = self.active_emitters.remove(ambient_key);
+            let _ = self.cycling_ambient.remove(ambient_key);
+        }
+
+        // Update the previous result.
+        swap(&mut self.query_result, &mut self.previous_query_result);
+
+        // We only update the listener position once every 50 ms, so that we can
+        // properly ease the change and have no discontinuities.
+        let now = Instant::now();
+        if now.duration_since(self.last_listener_update).as_secs_f32() <= 0.05 {
+            return false;
+        }
+
+        self.last_listener_update = now;
+
+        self.update_ambient_emitters(position);
+
+        let Some(spatial_output) = self.spatial_output.as_mut() else {
+            return true;
+        };
+
+        // Kira uses a RH coordinate system, so we need to convert our LH vectors.
+        let position = Vector3::new(position.x, position.y, -position.z);
+        let orientation = listener_orientation(view_direction, look_up);
+
+        let tween = Tween {
+            duration: Duration::from_millis(50),
+            ..Default::default()
+        };
+        spatial_output.listener.set_position(position, tween);
+        spatial_output.listener.set_orientation(orientation, tween);
+
+        true
+    }
+
+    /// Moves the emitters of ambient sounds that aren't emitted from a single
+    /// point, so they are emitted from the position closest to the listener.
+    /// Without a spatial scene, the volume of the ambient sounds is adjusted
+    /// to the distance to the listener instead.
+    fn update_ambient_emitters(&mut self, listener_position: Point3<f32>) {
+        let tween = Tween {
+            duration: Duration::from_millis(50),
+            ..Default::default()
+        };
+
+        for (ambient_key, emitter) in self.active_emitters.iter_mut() {
+            let Some(sound_config) = self.ambient_sound.get(*ambient_key) else {
+                continue;
+            };
+
+            let emitter_position = sound_config.bounds.emitter_position(listener_position);
+
+            match emitter {
+                SoundEmitter::Spatial { .. } if matches!(sound_config.bounds, AmbientBounds::Sphere(_)) => {}
+                SoundEmitter::Spatial { emitter_handle, .. } => {
+                    // Kira uses a RH coordinate system, so we need to convert our LH vectors.
+                    let emitter_position = Vector3::new(emitter_position.x, emitter_position.y, -emitter_position.z);
+                    emitter_handle.set_position(emitter_position, tween);
+                }
+                SoundEmitter::Mono { position, range, handle } => {
+                    *position = emitter_position;
+
+                    let attenuation = distance_attenuation(emitter_position.distance(listener_position), *range);
+                    let gain = emphasis_gain(&self.emphasized_ambient, *ambient_key);
+                    let volume = Volume::Amplitude((sound_config.volume * gain * attenuation) as f64);
+
+                    if let Some(handle) = handle {
+                        handle.set_volume(volume, tween);
+                    }
+
+                    if let Some(playing) = self.cycling_ambient.get_mut(ambient_key) {
+                        playing.handle.set_volume(volume, tween);
+                        playing.data.settings.volume = volume.into();
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_ambient_sound(
+        &mut self,
+        sound_effect_key: SoundEffectKey,
+        bounds: AmbientBounds,
+        volume: f32,
+        cycle: Option<f32>,
+    ) -> AmbientKey {
+        let volume = normalize_volume(volume);
+
+        // Registering the same sound twice at the same spot would play it twice,
+        // doubling its volume. This is a common mistake in map data, so we merge
+        // the duplicates instead.
+        let duplicate = self.ambient_sound.iter().find_map(|(ambient_key, sound_config)| {
+            (sound_config.sound_effect_key == sound_effect_key && sound_config.bounds.is_near(&bounds, DUPLICATE_AMBIENT_EPSILON))
+                .then_some(ambient_key)
+        });
+
+        if let Some(ambient_key) = duplicate {
+            #[cfg(feature = "debug")]
+            print_debug!(
+                "[{}] merging duplicate ambient sound {:?} at the same position",
+                "error".red(),
+                sound_effect_key
+            );
+
+            let sound_config = self.ambient_sound.get_mut(ambient_key).unwrap();
+            sound_config.volume = sound_config.volume.max(volume);
+
+            return ambient_key;
+        }
+
+        self.ambient_sound
+            .insert(AmbientSoundConfig {
+                sound_effect_key,
+                bounds,
+                volume,
+                cycle,
+                priority: 0,
+            })
+            .expect("Ambient sound slab is full")
+    }
+
+    fn stop_ambient(&mut self, ambient_key: AmbientKey, fade: Duration) {
+        let tween = Tween {
+            duration: fade,
+            ..Default::default()
+        };
+
+        if let Some(mut emitter) = self.active_emitters.remove(&ambient_key)
+            && let Some(handle) = emitter.handle_mut()
+        {
+            handle.stop(tween);
+        }
+
+        if let Some(mut playing) = self.cycling_ambient.remove(&ambient_key) {
+            playing.handle.stop(tween);
+        }
+
+        let _ = self.emphasized_ambient.remove(&ambient_key);
+    }
+
+    fn clear_ambient_sound(&mut self) {
+        self.query_result.clear();
+        self.previous_query_result.clear();
+        self.scratchpad.clear();
+
+        self.ambient_sound.clear();
+        self.active_emitters.clear();
+        self.cycling_ambient.clear();
+        self.emphasized_ambient.clear();
+        self.prefetch_queue.queued.clear();
+    }
+
+    fn prepare_ambient_sound_world(&mut self) {
+        let objects: Vec<(AmbientKey, AmbientBounds)> = self.ambient_sound.iter().map(|(key, object)| (key, object.bounds)).collect();
+
+        if !objects.is_empty() {
+            self.object_kdtree = KDTree::from_objects(&objects);
+        }
+    }
+
+    fn ambient_effective_volume(&self, ambient_key: AmbientKey) -> Option<f32> {
+        if !self.active_emitters.contains_key(&ambient_key) {
+            return None;
+        }
+
+        let sound_config = self.ambient_sound.get(ambient_key)?;
+        let distance = sound_config
+            .bounds
+            .emitter_position(self.listener_position)
+            .distance(self.listener_position);
+
+        Some(
+            sound_config.volume
+                * emphasis_gain(&self.emphasized_ambient, ambient_key)
+                * distance_attenuation(distance, sound_config.bounds.range()),
+        )
+    }
+
+    fn emphasize_ambient(&mut self, ambient_key: AmbientKey, gain: f32, duration: Duration) {
+        if self.ambient_sound.get(ambient_key).is_none() {
+            return;
+        }
+
+        self.emphasized_ambient.insert(ambient_key, AmbientEmphasis {
+            gain: gain.max(0.0),
+            until: Instant::now() + duration,
+        });
+
+        self.apply_ambient_volume(ambient_key, Tween {
+            duration: Duration::from_millis(250),
+            ..Default::default()
+        });
+    }
+
+    fn release_ambient_emphasis(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<AmbientKey> = self
+            .emphasized_ambient
+            .iter()
+            .filter(|(_, emphasis)| emphasis.until <= now)
+            .map(|(ambient_key, _)| *ambient_key)
+            .collect();
+
+        for ambient_key in expired {
+            let _ = self.emphasized_ambient.remove(&ambient_key);
+
+            self.apply_ambient_volume(ambient_key, Tween {
+                duration: Duration::from_millis(500),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Sets the volume of the sounds that are playing for an active ambient
+    /// sound to its configured volume multiplied by its emphasis.
+    fn apply_ambient_volume(&mut self, ambient_key: AmbientKey, tween: Tween) {
+        let Some(sound_config) = self.ambient_sound.get(ambient_key) else {
+            return;
+        };
+        let Some(emitter) = self.active_emitters.get_mut(&ambient_key) else {
+            return;
+        };
+
+        let mut volume = sound_config.volume * emphasis_gain(&self.emphasized_ambient, ambient_key);
+
+        if let SoundEmitter::Mono { position, range, .. } = emitter {
+            volume *= distance_attenuation(position.distance(self.listener_position), *range);
+        }
+
+        let volume = Volume::Amplitude(volume as f64);
+
+        if let Some(handle) = emitter.handle_mut() {
+            handle.set_volume(volume, tween);
+        }
+
+        if let Some(playing) = self.cycling_ambient.get_mut(&ambient_key) {
+            playing.handle.set_volume(volume, tween);
+            playing.data.settings.volume = volume.into();
+        }
+    }
+
+    fn prefetch_ambient_sounds(&mut self, ambient_keys: &[AmbientKey]) {
+        let mut remaining_count = (self.cache.max_count() as usize).saturating_sub(self.cache.statistics().snapshot().count as usize);
+        let remaining_bytes = self.cache.max_size().saturating_sub(self.cache.size());
+        let mut prefetched = HashSet::new();
 
-        for ambient_key in self.scratchpad.iter().copied() {
-            let Some(sound_config) = self.ambient_sound.get(ambient_key) else {
-                #[cfg(feature = "debug")]
-                print_debug!("[{}] can't find sound config for: {:?}", "error".red(), ambient_key);
-                continue;
-            };
+        if self.prefetch_tracker.adaptive {
+            remaining_count = throttled_prefetch_count(remaining_count, self.prefetch_tracker.eviction_pressure);
+        }
 
-            // Kira uses a RH coordinate system, so we need to convert our LH vectors.
-            let position = sound_config.bounds.center();
-            let position = Vector3::new(position.x, position.y, -position.z);
-            let emitter_settings = EmitterSettings {
-                distances: EmitterDistances {
-                    min_distance: 5.0,
-                    max_distance: sound_config.bounds.radius(),
-                },
-                attenuation_function: Some(Easing::Linear),
-                enable_spatialization: true,
-                persist_until_sounds_finish: true,
-            };
-            let emitter_handle = match self.scene.add_emitter(position, emitter_settings) {
-                Ok(emitter_handle) => emitter_handle,
-                Err(_error) => {
-                    #[cfg(feature = "debug")]
-                    print_debug!("[{}] can't add ambient sound emitter: {:?}", "error".red(), _error);
-                    continue;
+        let sound_effect_keys: Vec<SoundEffectKey> = ambient_keys
+            .iter()
+            .filter_map(|ambient_key| {
+                let sound_config = self.ambient_sound.get(*ambient_key);
+
+                #[cfg(feature = "debug")]
+                if sound_config.is_none() {
+                    print_debug!("[{}] can't find sound config for: {:?}", "error".red(), ambient_key);
                 }
+
+                sound_config.map(|sound_config| sound_config.sound_effect_key)
+            })
+            .filter(|sound_effect_key| {
+                !self.cache.contains_key(sound_effect_key)
+                    && !self.compressed_cache.contains_key(sound_effect_key)
+                    && !self.pinned_sound_effect.contains_key(sound_effect_key)
+            })
+            .filter(|sound_effect_key| prefetched.insert(*sound_effect_key))
+            .collect();
+
+        self.prefetch_queue.reset(sound_effect_keys, remaining_count, remaining_bytes);
+        self.advance_prefetch();
+    }
+
+    /// Starts loading queued sound effects of
+    /// [`AudioEngine::prefetch_ambient_sounds`] while there is budget left.
+    fn advance_prefetch(&mut self) {
+        while let Some(sound_effect_key) = self.prefetch_queue.next() {
+            let Some(path) = self.sound_effect_paths.get(sound_effect_key).cloned() else {
+                self.prefetch_queue.loading.remove(&sound_effect_key);
+                continue;
             };
 
-            let sound_effect_key = sound_config.sound_effect_key;
-            if let Some(data) = self
-                .cache
-                .get(&sound_effect_key)
-                .map(|cached_sound_effect| cached_sound_effect.0.clone())
+            // Sound effects are also loaded when they are registered, so they might
+            // already be on their way into the cache. They still count towards the
+            // budget once they arrive.
+            if self.cache.contains_key(&sound_effect_key)
+                || self.compressed_cache.contains_key(&sound_effect_key)
+                || self.pinned_sound_effect.contains_key(&sound_effect_key)
             {
-                let data = adjust_ambient_sound(data, &emitter_handle, sound_config.volume);
-                match self.manager.play(data.clone()) {
-                    Ok(handle) => {
-                        if let Some(cycle) = sound_config.cycle {
-                            self.cycling_ambient.insert(ambient_key, PlayingAmbient {
-                                data,
-                                handle,
-                                cycle,
-                                last_start: Instant::now(),
-                            });
-                        }
-                    }
-                    Err(_error) => {
-                        #[cfg(feature = "debug")]
-                        print_debug!("[{}] can't ambient sound effect: {:?}", "error".red(), _error);
-                    }
-                }
-            } else {
-                queue_sound_effect_playback(
+                self.prefetch_queue.loading.remove(&sound_effect_key);
+                continue;
+            }
+
+            self.prefetch_tracker.pending.insert(sound_effect_key);
+
+            if self.loading_sound_effect.insert(sound_effect_key) {
+                spawn_async_load(
                     self.game_file_loader.clone(),
                     self.async_response_sender.clone(),
-                    &self.sound_effect_paths,
-                    &mut self.queued_sound_effect,
+                    self.load_settings.clone(),
+                    path,
+                    self.memory_sound_effects.get(&sound_effect_key).cloned(),
                     sound_effect_key,
-                    QueuedSoundEffectType::AmbientSound { ambient_key },
+                    0,
                 );
             }
-
-            self.active_emitters.insert(ambient_key, emitter_handle);
         }
+    }
 
-        // Remove ambient sound that are out of reach.
-        difference(&mut self.previous_query_result, &mut self.query_result, &mut self.scratchpad);
-        for ambient_key in self.scratchpad.iter() {
-            let _ = self.active_emitters.remove(ambient_key);
-            let _ = self.cycling_ambient.remove(ambient_key);
-        }
+    fn trim_effect_cache_except_ambients(&mut self) {
+        let ambient_sound_effects: HashSet<SoundEffectKey> = self
+            .ambient_sound
+            .iter()
+            .map(|(_, sound_config)| sound_config.sound_effect_key)
+            .collect();
+
+        self.cache
+            .retain(|sound_effect_key| ambient_sound_effects.contains(sound_effect_key));
+        self.compressed_cache
+            .retain(|sound_effect_key| ambient_sound_effects.contains(sound_effect_key));
+    }
 
-        // Update the previous result.
-        swap(&mut self.query_result, &mut self.previous_query_result);
+    fn snapshot(&self) -> AudioSnapshot {
+        let background_music_track = self
+            .music_layers
+            .get(DEFAULT_MUSIC_LAYER)
+            .filter(|playing| playing.handle.state() == PlaybackState::Playing)
+            .map(|playing| (playing.track_name.clone(), playing.handle.position()));
+        let ambient_sounds = self.ambient_sound.iter().map(|(_, sound_config)| *sound_config).collect();
 
-        // We only update the listener position once every 50 ms, so that we can
-        // properly ease the change and have no discontinuities.
-        let now = Instant::now();
-        if now.duration_since(self.last_listener_update).as_secs_f32() > 0.05 {
-            self.last_listener_update = now;
+        AudioSnapshot {
+            background_music_track,
+            ambient_sounds,
+        }
+    }
 
-            // Kira uses a RH coordinate system, so we need to convert our LH vectors.
-            let position = Vector3::new(position.x, position.y, -position.z);
-            let view_direction = Vector3::new(view_direction.x, view_direction.y, -view_direction.z).normalize();
-            let look_up = Vector3::new(look_up.x, look_up.y, -look_up.z).normalize();
-            let right = view_direction.cross(look_up).normalize();
-            let up = right.cross(view_direction);
+    fn restore(&mut self, snapshot: AudioSnapshot) {
+        match snapshot.background_music_track {
+            Some((track_name, position)) => self.restore_background_music_track(&track_name, position),
+            None => self.play_background_music_track(None),
+        }
+
+        self.restore_ambient_sound(snapshot.ambient_sounds);
+    }
 
-            let rotation_matrix = Matrix3::from_cols(right, up, -view_direction);
-            let orientation = Quaternion::from(rotation_matrix);
+    fn restore_background_music_track(&mut self, track_name: &str, position: f64) {
+        if let Some(playing) = self.music_layers.get_mut(DEFAULT_MUSIC_LAYER) {
+            if playing.track_name == track_name && playing.handle.state() == PlaybackState::Playing {
+                return;
+            }
 
-            let tween = Tween {
-                duration: Duration::from_millis(50),
+            playing.handle.stop(Tween {
+                duration: Duration::from_secs(1),
                 ..Default::default()
-            };
-            self.spatial_listener.set_position(position, tween);
-            self.spatial_listener.set_orientation(orientation, tween);
+            });
         }
-    }
 
-    fn add_ambient_sound(
-        &mut self,
-        sound_effect_key: SoundEffectKey,
-        position: Point3<f32>,
-        range: f32,
-        volume: f32,
-        cycle: Option<f32>,
-    ) -> AmbientKey {
-        self.ambient_sound
-            .insert(AmbientSoundConfig {
-                sound_effect_key,
-                bounds: Sphere::new(position, range),
-                volume,
-                cycle,
-            })
-            .expect("Ambient sound slab is full")
+        self.queued_background_music_track = None;
+        self.change_background_music_track(track_name, position);
     }
 
-    fn clear_ambient_sound(&mut self) {
-        self.query_result.clear();
-        self.previous_query_result.clear();
-        self.scratchpad.clear();
+    fn restore_ambient_sound(&mut self, mut ambient_sounds: Vec<AmbientSoundConfig>) {
+        let registered_ambient_sound: Vec<(AmbientKey, AmbientSoundConfig)> = self
+            .ambient_sound
+            .iter()
+            .map(|(ambient_key, sound_config)| (ambient_key, *sound_config))
+            .collect();
+
+        // Ambient sounds that didn't change keep their key and emitter, so they
+        // continue playing without a gap.
+        for ambient_key in match_restored_ambient_sounds(registered_ambient_sound, &mut ambient_sounds) {
+            let _ = self.ambient_sound.remove(ambient_key);
+            let _ = self.active_emitters.remove(&ambient_key);
+            let _ = self.cycling_ambient.remove(&ambient_key);
+            let _ = self.emphasized_ambient.remove(&ambient_key);
+        }
 
-        self.ambient_sound.clear();
-        self.active_emitters.clear();
-        self.cycling_ambient.clear();
+        for sound_config in ambient_sounds {
+            let _ = self.ambient_sound.insert(sound_config).expect("Ambient sound slab is full");
+        }
+
+        let active_emitters = &self.active_emitters;
+        self.previous_query_result
+            .retain(|ambient_key| active_emitters.contains_key(ambient_key));
+
+        match self.ambient_sound.count() {
+            0 => self.object_kdtree = KDTree::empty(),
+            _ => self.prepare_ambient_sound_world(),
+        }
     }
 
-    fn prepare_ambient_sound_world(&mut self) {
-        let objects: Vec<(AmbientKey, Sphere)> = self.ambient_sound.iter().map(|(key, object)| (key, object.bounds)).collect();
+    fn warm_up(&mut self, spatial_range: f32) {
+        let silence = StaticSoundData {
+            sample_rate: NORMALIZED_SAMPLE_RATE,
+            frames: Arc::new([Frame::ZERO]),
+            settings: StaticSoundSettings::default(),
+            slice: None,
+        };
 
-        if !objects.is_empty() {
-            self.object_kdtree = KDTree::from_objects(&objects);
+        let mut warm_up_sounds = vec![
+            silence.clone(),
+            silence.clone().output_destination(&self.background_music_track),
+            silence.clone().output_destination(&self.sound_effect_track),
+            silence.clone().output_destination(&self.spatial_sound_effect_track),
+        ];
+
+        if self.spatial_output.is_some() {
+            let emitter = create_emitter(self.spatial_output.as_mut(), self.listener_position, spatial_range);
+            warm_up_sounds.push(route_to_emitter(
+                silence,
+                &emitter,
+                0.0,
+                self.listener_position,
+                &self.sound_effect_track,
+            ));
+        }
+
+        for data in warm_up_sounds {
+            if let Err(_error) = self.manager.play(data) {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] can't play warm up sound: {:?}", "error".red(), _error);
+            }
         }
     }
 
     fn update(&mut self) {
+        self.playing_sound_effects
+            .retain(|playing| playing.handle.state() != PlaybackState::Stopped);
+        self.update_listener_transition();
         self.resolve_async_loads();
         self.resolve_queued_audio();
+        // Compressed sound effects are only decoded for the playbacks that were
+        // waiting for them.
+        self.decoded_compressed.clear();
+        self.release_ambient_emphasis();
         self.restart_cycling_ambient();
     }
 
     /// Audio engine will collect all static sound_effect data that finished
     /// loading. Should be called once a frame.
     fn resolve_async_loads(&mut self) {
+        let mut retries = Vec::new();
+
         while let Ok(result) = self.async_response_receiver.try_recv() {
             match result {
                 AsyncLoadResult::Loaded {
@@ -646,7 +2705,22 @@ impl<F: FileLoader> EngineContext<F> {
                 } => {
                     self.loading_sound_effect.remove(&key);
 
-                    if let Err(_error) = self.cache.insert(key, CachedSoundEffect(*sound_effect)) {
+                    let sound_effect = CachedSoundEffect(*sound_effect);
+                    let is_prefetch = self.prefetch_queue.loading.contains(&key);
+
+                    // Prefetched sound effects that don't fit into the budget would only
+                    // evict other sound effects, unless they are about to be played.
+                    if is_prefetch
+                        && !self.prefetch_queue.loaded(key, sound_effect.size())
+                        && !self.queued_sound_effect.iter().any(|queued| queued.sound_effect_key == key)
+                    {
+                        self.prefetch_tracker.pending.remove(&key);
+                        continue;
+                    }
+
+                    if let Some(pinned_sound_effect) = self.pinned_sound_effect.get_mut(&key) {
+                        pinned_sound_effect.data = Some(sound_effect.0);
+                    } else if let Err(_error) = self.cache.insert(key, sound_effect) {
                         #[cfg(feature = "debug")]
                         print_debug!(
                             "[{}] audio file is too big for cache. Path: '{}': {:?}",
@@ -656,98 +2730,222 @@ impl<F: FileLoader> EngineContext<F> {
                         );
                     }
                 }
-                AsyncLoadResult::Error {
+                AsyncLoadResult::LoadedCompressed {
                     path: _path,
                     key,
-                    message: _message,
+                    data,
+                    sound_effect,
                 } => {
                     self.loading_sound_effect.remove(&key);
+                    // Compressed sound effects are cached separately, so they don't take
+                    // from the budget of the prefetch.
+                    self.prefetch_queue.loading.remove(&key);
+
+                    if let Some(pinned_sound_effect) = self.pinned_sound_effect.get_mut(&key) {
+                        pinned_sound_effect.data = Some(*sound_effect);
+                        continue;
+                    }
+
+                    if !self.memory_sound_effects.contains_key(&key)
+                        && let Err(_error) = self.compressed_cache.insert(key, CompressedSoundEffect(data))
+                    {
+                        #[cfg(feature = "debug")]
+                        print_debug!(
+                            "[{}] audio file is too big for compressed cache. Path: '{}': {:?}",
+                            "error".red(),
+                            &_path,
+                            _error
+                        );
+                    }
+
+                    self.decoded_compressed.insert(key, *sound_effect);
+                }
+                AsyncLoadResult::Error { path, key, error } => {
+                    self.loading_sound_effect.remove(&key);
+                    self.prefetch_queue.loading.remove(&key);
+                    self.prefetch_tracker.mark_failed(key);
+
+                    if let Some(callback) = self.load_error_callback.as_mut() {
+                        callback(&path, &error);
+                        continue;
+                    }
 
                     #[cfg(feature = "debug")]
-                    print_debug!(
-                        "[{}] could not load audio file. Path: '{}' : {}",
-                        "error".red(),
-                        _path,
-                        _message
-                    );
+                    print_debug!("[{}] could not load audio file. Path: '{}' : {:?}", "error".red(), path, error);
+                }
+                AsyncLoadResult::Retry { path, key, attempt } => {
+                    // The sound effect might have been unloaded in the meantime.
+                    match self.loading_sound_effect.contains(&key) {
+                        true => retries.push((path, key, attempt)),
+                        false => {
+                            self.prefetch_queue.loading.remove(&key);
+                        }
+                    }
+                }
+                AsyncLoadResult::MusicLoaded {
+                    layer_name,
+                    track_name,
+                    music,
+                } => {
+                    // The layer might have been stopped or changed to a different track
+                    // while the track was decoded.
+                    if let Some(loading) = self.loading_music_layers.remove(&layer_name) {
+                        match loading.track_name == track_name {
+                            true => self.play_decoded_music_track(&layer_name, loading, *music),
+                            false => {
+                                self.loading_music_layers.insert(layer_name, loading);
+                            }
+                        }
+                    }
+                }
+                AsyncLoadResult::MusicError {
+                    layer_name,
+                    track_name,
+                    error: _error,
+                } => {
+                    if self
+                        .loading_music_layers
+                        .get(&layer_name)
+                        .is_some_and(|loading| loading.track_name == track_name)
+                    {
+                        self.loading_music_layers.remove(&layer_name);
+                    }
+
+                    #[cfg(feature = "debug")]
+                    print_debug!("[{}] can't decode background music track: {:?}", "error".red(), _error);
                 }
             }
         }
+
+        // Retries are only spawned after all results were resolved, so they wait for at
+        // least one frame.
+        for (path, key, attempt) in retries {
+            spawn_async_load(
+                self.game_file_loader.clone(),
+                self.async_response_sender.clone(),
+                self.load_settings.clone(),
+                path,
+                None,
+                key,
+                attempt,
+            );
+        }
+
+        self.detect_prefetch_evictions();
+        self.advance_prefetch();
+    }
+
+    /// Sound effects are only evicted when new ones are inserted into the
+    /// cache, so this only needs to run after loading sound effects.
+    fn detect_prefetch_evictions(&mut self) {
+        let evicted: Vec<SoundEffectKey> = self
+            .prefetch_tracker
+            .pending
+            .iter()
+            .copied()
+            .filter(|sound_effect_key| {
+                !self.loading_sound_effect.contains(sound_effect_key)
+                    && !self.cache.contains_key(sound_effect_key)
+                    && !self.compressed_cache.contains_key(sound_effect_key)
+                    && !self.pinned_sound_effect.contains_key(sound_effect_key)
+            })
+            .collect();
+
+        for sound_effect_key in evicted {
+            self.prefetch_tracker.mark_evicted(sound_effect_key);
+        }
     }
 
     fn resolve_queued_audio(&mut self) {
         if self.queued_background_music_track.is_some()
-            && let Some(playing) = self.current_background_music_track.as_ref()
+            && let Some(playing) = self.music_layers.get(DEFAULT_MUSIC_LAYER)
             && playing.handle.state() == PlaybackState::Stopped
         {
             let track_name = self.queued_background_music_track.take().unwrap();
-            self.change_background_music_track(&track_name)
+            self.change_background_music_track(&track_name, 0.0)
         }
 
         let now = Instant::now();
 
         self.queued_sound_effect.retain(|queued| {
-            if queued.queued_time.duration_since(now).as_secs_f32() > MAX_QUEUE_TIME_SECONDS {
+            if now.duration_since(queued.queued_time).as_secs_f32() > MAX_QUEUE_TIME_SECONDS {
                 // We waited too long.
                 return false;
             }
 
             let Some(data) = self
-                .cache
+                .pinned_sound_effect
                 .get(&queued.sound_effect_key)
-                .map(|cached_sound_effect| cached_sound_effect.0.clone())
+                .and_then(|pinned_sound_effect| pinned_sound_effect.data.clone())
+                .or_else(|| {
+                    self.cache
+                        .get(&queued.sound_effect_key)
+                        .map(|cached_sound_effect| cached_sound_effect.0.clone())
+                })
+                .or_else(|| self.decoded_compressed.get(&queued.sound_effect_key).cloned())
             else {
                 // Sound effect not loaded yet.
                 return true;
             };
 
             match queued.sound_type {
-                QueuedSoundEffectType::Sound => {
-                    if let Err(_error) = self.manager.play(data.output_destination(&self.sound_effect_track)) {
-                        #[cfg(feature = "debug")]
-                        print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
-                    }
+                QueuedSoundEffectType::Sound {
+                    category,
+                    non_interruptible,
+                } => {
+                    let track = category_track(&self.sound_effect_track, &self.sound_category_tracks, category);
+                    let data = data.output_destination(track).playback_rate(self.time_scale);
+
+                    play_effect_voice(
+                        &mut self.manager,
+                        &mut self.playing_sound_effects,
+                        self.max_effect_voices,
+                        non_interruptible,
+                        data,
+                    );
                 }
-                QueuedSoundEffectType::SpatialSound { position, range } => {
-                    let settings = EmitterSettings {
-                        distances: EmitterDistances {
-                            min_distance: 5.0,
-                            max_distance: range,
-                        },
-                        attenuation_function: Some(Easing::Linear),
-                        enable_spatialization: true,
-                        persist_until_sounds_finish: true,
-                    };
-
-                    match self.scene.add_emitter(position, settings) {
-                        Ok(emitter_handle) => {
-                            let data = adjust_ambient_sound(data, &emitter_handle, 1.0);
-
-                            if let Err(_error) = self.manager.play(data) {
-                                #[cfg(feature = "debug")]
-                                print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
-                            }
-                        }
-                        Err(_error) => {
-                            #[cfg(feature = "debug")]
-                            print_debug!("[{}] can't add spatial sound emitter: {:?}", "error".red(), _error);
-                        }
-                    };
+                QueuedSoundEffectType::SpatialSound {
+                    position,
+                    range,
+                    category,
+                    non_interruptible,
+                } => {
+                    let emitter = create_emitter(self.spatial_output.as_mut(), position, range);
+                    let track = category_track(&self.sound_effect_track, &self.sound_category_tracks, category);
+                    let data = route_to_emitter(data, &emitter, 1.0, self.listener_position, track);
+
+                    play_effect_voice(
+                        &mut self.manager,
+                        &mut self.playing_sound_effects,
+                        self.max_effect_voices,
+                        non_interruptible,
+                        data.playback_rate(self.time_scale),
+                    );
                 }
                 QueuedSoundEffectType::AmbientSound { ambient_key } => {
-                    if let Some(emitter_handle) = self.active_emitters.get(&ambient_key)
+                    if let Some(emitter) = self.active_emitters.get_mut(&ambient_key)
                         && let Some(sound_config) = self.ambient_sound.get(ambient_key)
                     {
-                        let data = adjust_ambient_sound(data, emitter_handle, sound_config.volume);
-                        match self.manager.play(data.clone()) {
+                        let data = route_to_emitter(
+                            data,
+                            emitter,
+                            sound_config.volume * emphasis_gain(&self.emphasized_ambient, ambient_key),
+                            self.listener_position,
+                            &self.sound_effect_track,
+                        );
+                        match self.manager.play(data.clone().playback_rate(self.time_scale)) {
                             Ok(handle) => {
                                 if let Some(cycle) = sound_config.cycle {
+                                    let restart_delay = jittered_cycle(&mut self.rng, cycle, self.ambient_cycle_jitter);
                                     self.cycling_ambient.insert(ambient_key, PlayingAmbient {
                                         data,
                                         handle,
                                         cycle,
+                                        restart_delay,
                                         last_start: Instant::now(),
                                     });
+                                } else {
+                                    *emitter.handle_mut() = Some(handle);
                                 }
                             }
                             Err(_error) => {
@@ -768,11 +2966,13 @@ impl<F: FileLoader> EngineContext<F> {
         let now = Instant::now();
 
         for (_, playing) in self.cycling_ambient.iter_mut().filter(|(_, playing)| {
-            playing.handle.state() != PlaybackState::Playing && now.duration_since(playing.last_start).as_secs_f32() >= playing.cycle
+            playing.handle.state() != PlaybackState::Playing
+                && now.duration_since(playing.last_start).as_secs_f32() >= playing.restart_delay
         }) {
             playing.last_start = now;
+            playing.restart_delay = jittered_cycle(&mut self.rng, playing.cycle, self.ambient_cycle_jitter);
 
-            match self.manager.play(playing.data.clone()) {
+            match self.manager.play(playing.data.clone().playback_rate(self.time_scale)) {
                 Ok(handle) => {
                     playing.handle = handle;
                 }
@@ -784,13 +2984,41 @@ impl<F: FileLoader> EngineContext<F> {
         }
     }
 
-    fn change_background_music_track(&mut self, track_name: &str) {
+    fn change_background_music_track(&mut self, track_name: &str, start_position: f64) {
+        self.change_music_layer_track(DEFAULT_MUSIC_LAYER, track_name, start_position, Volume::Amplitude(1.0).into());
+    }
+
+    fn change_music_layer_track(&mut self, layer_name: &str, track_name: &str, start_position: f64, volume: Value<Volume>) {
         let Some(path) = find_file_path(track_name) else {
             #[cfg(feature = "debug")]
             print_debug!("[{}] can't find background music track: {:?}", "error".red(), track_name);
             return;
         };
 
+        if self.music_decode_quality == MusicDecodeQuality::Reduced {
+            let previous = self.loading_music_layers.insert(layer_name.to_string(), LoadingMusicTrack {
+                track_name: track_name.to_string(),
+                start_position,
+                volume,
+            });
+
+            // The track might already be decoding, in which case it only starts with
+            // the new position and volume.
+            if previous.is_none_or(|previous| previous.track_name != track_name) {
+                spawn_music_load(
+                    self.async_response_sender.clone(),
+                    self.load_settings.clone(),
+                    layer_name.to_string(),
+                    track_name.to_string(),
+                    path,
+                );
+            }
+
+            return;
+        }
+
+        self.loading_music_layers.remove(layer_name);
+
         let data = match StreamingSoundData::from_file(path) {
             Ok(sound_effect_data) => sound_effect_data,
             Err(_error) => {
@@ -804,24 +3032,277 @@ impl<F: FileLoader> EngineContext<F> {
         // we defined the loop region to be the full region of the music. We shave off
         // 50 ms of the music, so that the music never finishes, and we properly loop
         // the music again.
-        let duration = data.duration().as_secs_f64() - 0.05;
-        let data = data.loop_region(..duration);
+        let loop_duration = data.duration().as_secs_f64() - 0.05;
+        let data = data.loop_region(..loop_duration);
+        let data = data.start_position(start_position.min(loop_duration));
+        let data = data.volume(volume);
+        let data = data.playback_rate(self.time_scale);
+        let data = data.output_destination(&self.background_music_track);
+
+        match self.manager.play(data) {
+            Ok(handle) => self.insert_music_layer(
+                layer_name,
+                track_name,
+                MusicHandle::Streaming(handle),
+                loop_duration,
+                start_position,
+            ),
+            Err(_error) => {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] can't play background music track: {:?}", "error".red(), _error);
+            }
+        }
+    }
+
+    /// Starts a music track that was decoded on the loading threads.
+    fn play_decoded_music_track(&mut self, layer_name: &str, loading: LoadingMusicTrack, data: StaticSoundData) {
+        // Same workaround as for the streamed music, see `change_music_layer_track`.
+        let loop_duration = data.duration().as_secs_f64() - 0.05;
+        let data = data.loop_region(..loop_duration);
+        let data = data.start_position(loading.start_position.min(loop_duration));
+        let data = data.volume(loading.volume);
+        let data = data.playback_rate(self.time_scale);
         let data = data.output_destination(&self.background_music_track);
 
-        let handle = match self.manager.play(data) {
-            Ok(handle) => handle,
-            Err(_error) => {
-                #[cfg(feature = "debug")]
-                print_debug!("[{}] can't play background music track: {:?}", "error".red(), _error);
-                return;
-            }
-        };
+        match self.manager.play(data) {
+            Ok(handle) => self.insert_music_layer(
+                layer_name,
+                &loading.track_name,
+                MusicHandle::Static(handle),
+                loop_duration,
+                loading.start_position,
+            ),
+            Err(_error) => {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] can't play background music track: {:?}", "error".red(), _error);
+            }
+        }
+    }
+
+    fn insert_music_layer(&mut self, layer_name: &str, track_name: &str, handle: MusicHandle, loop_duration: f64, start_position: f64) {
+        let previous = self.music_layers.insert(layer_name.to_string(), BackgroundMusicTrack {
+            track_name: track_name.to_string(),
+            handle,
+            loop_duration,
+            start_position: start_position.min(loop_duration),
+            playing_since: Some(Instant::now()),
+            playback_rate: self.time_scale,
+        });
+
+        // A paused track would otherwise stay in the manager forever once its
+        // handle is dropped.
+        if let Some(mut previous) = previous
+            && matches!(previous.handle.state(), PlaybackState::Pausing | PlaybackState::Paused)
+        {
+            previous.handle.stop(Tween::default());
+        }
+    }
+}
+
+/// Returns the view direction and up vector of something facing along the
+/// given rotation, for example a player character. Korangar uses a LH
+/// coordinate system, so an unrotated entity faces along +Z with +Y pointing
+/// up. The vectors can be passed to [`AudioEngine::set_spatial_listener`].
+pub fn facing_vectors(rotation: Quaternion<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let rotation = rotation.normalize();
+    let view_direction = rotation * Vector3::unit_z();
+    let look_up = rotation * Vector3::unit_y();
+
+    (view_direction, look_up)
+}
+
+/// The inverse of [`facing_vectors`]. Returns the rotation of something facing
+/// along the given view direction, with the given up vector.
+fn facing_rotation(view_direction: Vector3<f32>, look_up: Vector3<f32>) -> Quaternion<f32> {
+    let view_direction = view_direction.normalize();
+    let right = look_up.cross(view_direction).normalize();
+    let up = view_direction.cross(right);
+
+    Quaternion::from(Matrix3::from_cols(right, up, view_direction))
+}
+
+/// Converts a view direction and up vector in Korangar's LH coordinate system
+/// into the orientation of a listener in kira's RH coordinate system. An
+/// unrotated listener in kira looks along -Z, with +X to its right.
+pub fn listener_orientation(view_direction: Vector3<f32>, look_up: Vector3<f32>) -> Quaternion<f32> {
+    let view_direction = Vector3::new(view_direction.x, view_direction.y, -view_direction.z).normalize();
+    let look_up = Vector3::new(look_up.x, look_up.y, -look_up.z).normalize();
+    let right = view_direction.cross(look_up).normalize();
+    let up = right.cross(view_direction);
+
+    let rotation_matrix = Matrix3::from_cols(right, up, -view_direction);
+    Quaternion::from(rotation_matrix)
+}
+
+/// Creates an emitter at the given position. Without a spatial scene, the
+/// position is only used to derive the volume from the distance to the
+/// listener. The same happens if the spatial scene can't hold any more
+/// emitters, so the sound is still audible, just without panning.
+fn create_emitter(spatial_output: Option<&mut SpatialOutput>, position: Point3<f32>, range: f32) -> SoundEmitter {
+    let mono_emitter = SoundEmitter::Mono {
+        position,
+        range,
+        handle: None,
+    };
+
+    let Some(spatial_output) = spatial_output else {
+        return mono_emitter;
+    };
+
+    // Kira uses a RH coordinate system, so we need to convert our LH vectors.
+    let position = Vector3::new(position.x, position.y, -position.z);
+    let settings = EmitterSettings {
+        distances: EmitterDistances {
+            min_distance: MIN_EMITTER_DISTANCE,
+            max_distance: range,
+        },
+        attenuation_function: Some(Easing::Linear),
+        enable_spatialization: true,
+        persist_until_sounds_finish: true,
+    };
+
+    match spatial_output.scene.add_emitter(position, settings) {
+        Ok(emitter_handle) => SoundEmitter::Spatial {
+            emitter_handle,
+            handle: None,
+        },
+        Err(_error) => {
+            if !spatial_output.emitter_limit_reached {
+                spatial_output.emitter_limit_reached = true;
+
+                #[cfg(feature = "debug")]
+                print_debug!(
+                    "[{}] can't add spatial sound emitter, falling back to non-spatial playback: {:?}",
+                    "error".red(),
+                    _error
+                );
+            }
+
+            mono_emitter
+        }
+    }
+}
+
+/// Routes the sound to the emitter. Sounds of mono emitters are routed to the
+/// given track, attenuated by their distance to the listener.
+fn route_to_emitter(
+    data: StaticSoundData,
+    emitter: &SoundEmitter,
+    volume: f32,
+    listener_position: Point3<f32>,
+    mono_track: &TrackHandle,
+) -> StaticSoundData {
+    match emitter {
+        SoundEmitter::Spatial { emitter_handle, .. } => adjust_ambient_sound(data, emitter_handle, volume),
+        SoundEmitter::Mono { position, range, .. } => {
+            let attenuation = distance_attenuation(position.distance(listener_position), *range);
+            let mut data = data.output_destination(mono_track);
+            data.settings.volume = Volume::Amplitude((volume * attenuation) as f64).into();
+            data
+        }
+    }
+}
+
+/// Clamps a volume to the range of 0.0 to 1.0. NaN is mapped to 0.0, since
+/// it would otherwise propagate into the mix.
+fn normalize_volume(volume: f32) -> f32 {
+    match volume.is_nan() {
+        true => 0.0,
+        false => volume.clamp(0.0, 1.0),
+    }
+}
+
+/// Applies [`normalize_volume`] to a fixed volume. Volumes that are driven by
+/// a modulator are passed through unchanged.
+fn normalize_volume_value(volume: impl Into<Value<Volume>>) -> Value<Volume> {
+    match volume.into() {
+        Value::Fixed(volume) => Value::Fixed(Volume::Amplitude(normalize_volume(volume.as_amplitude() as f32) as f64)),
+        value => value,
+    }
+}
+
+/// Plays a sound effect, stopping the oldest interruptible sound effects if
+/// the voice limit is reached. The sound effect is dropped if there are not
+/// enough interruptible sound effects to make room for it.
+fn play_effect_voice(
+    manager: &mut AudioManager,
+    playing_sound_effects: &mut Vec<PlayingSoundEffect>,
+    max_effect_voices: usize,
+    non_interruptible: bool,
+    data: StaticSoundData,
+) -> SoundEffectPlayback {
+    if !steal_voices(playing_sound_effects, max_effect_voices - 1) {
+        return SoundEffectPlayback::Dropped;
+    }
+
+    match manager.play(data) {
+        Ok(handle) => {
+            playing_sound_effects.push(PlayingSoundEffect { handle, non_interruptible });
+            SoundEffectPlayback::Played
+        }
+        Err(_error) => {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
+
+            SoundEffectPlayback::Dropped
+        }
+    }
+}
+
+/// Stops the oldest interruptible sound effects until at most `keep` of them
+/// are still playing. Sound effects that are already fading out don't count.
+/// Returns `false` without stopping anything if there are not enough
+/// interruptible sound effects to reach the limit.
+fn steal_voices(playing_sound_effects: &mut Vec<PlayingSoundEffect>, keep: usize) -> bool {
+    playing_sound_effects.retain(|playing| playing.handle.state() != PlaybackState::Stopped);
+
+    let active = || {
+        playing_sound_effects
+            .iter()
+            .filter(|playing| playing.handle.state() != PlaybackState::Stopping)
+    };
+    let playing_count = active().count();
+    let interruptible_count = active().filter(|playing| !playing.non_interruptible).count();
+
+    let Some(mut steal_count) = voices_to_steal(playing_count, interruptible_count, keep) else {
+        return false;
+    };
+
+    for playing in playing_sound_effects.iter_mut() {
+        if steal_count == 0 {
+            break;
+        }
 
-        self.current_background_music_track = Some(BackgroundMusicTrack {
-            track_name: track_name.to_string(),
-            handle,
-        });
+        if !playing.non_interruptible && playing.handle.state() != PlaybackState::Stopping {
+            playing.handle.stop(Tween {
+                duration: VOICE_STEAL_FADE,
+                ..Default::default()
+            });
+            steal_count -= 1;
+        }
     }
+
+    true
+}
+
+/// Returns how many interruptible voices need to be stopped so that at most
+/// `keep` voices are playing, or `None` if there are not enough interruptible
+/// voices.
+fn voices_to_steal(playing_count: usize, interruptible_count: usize, keep: usize) -> Option<usize> {
+    let steal_count = playing_count.saturating_sub(keep);
+    (steal_count <= interruptible_count).then_some(steal_count)
+}
+
+/// The track a sound effect of the given category is played on. Sound effects
+/// without a category are played on the sound effect track directly.
+fn category_track<'a>(
+    sound_effect_track: &'a TrackHandle,
+    sound_category_tracks: &'a HashMap<SoundCategory, TrackHandle>,
+    category: Option<SoundCategory>,
+) -> &'a TrackHandle {
+    category
+        .and_then(|category| sound_category_tracks.get(&category))
+        .unwrap_or(sound_effect_track)
 }
 
 fn adjust_ambient_sound(mut data: StaticSoundData, emitter_handle: &EmitterHandle, volume: f32) -> StaticSoundData {
@@ -830,17 +3311,38 @@ fn adjust_ambient_sound(mut data: StaticSoundData, emitter_handle: &EmitterHandl
     data.output_destination(emitter_handle)
 }
 
+/// The gain of an emphasized ambient sound, or 1 if it isn't emphasized.
+fn emphasis_gain(emphasized_ambient: &HashMap<AmbientKey, AmbientEmphasis>, ambient_key: AmbientKey) -> f32 {
+    emphasized_ambient.get(&ambient_key).map_or(1.0, |emphasis| emphasis.gain)
+}
+
+/// Linear attenuation of a sound based on the distance to the listener, that
+/// matches the attenuation of emitters in the spatial scene.
+fn distance_attenuation(distance: f32, range: f32) -> f32 {
+    let fade_distance = (range - MIN_EMITTER_DISTANCE).max(f32::EPSILON);
+    1.0 - ((distance - MIN_EMITTER_DISTANCE) / fade_distance).clamp(0.0, 1.0)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn queue_sound_effect_playback(
     game_file_loader: Arc<impl FileLoader>,
     async_response_sender: Sender<AsyncLoadResult>,
+    load_settings: LoadSettings,
     sound_effect_paths: &GenerationalSlab<SoundEffectKey, String>,
+    memory_sound_effects: &HashMap<SoundEffectKey, Arc<[u8]>>,
+    compressed_cache: &mut SimpleCache<SoundEffectKey, CompressedSoundEffect>,
+    loading_sound_effect: &mut HashSet<SoundEffectKey>,
     queued_sound_effect: &mut Vec<QueuedSoundEffect>,
     sound_effect_key: SoundEffectKey,
     queued_sound_effect_type: QueuedSoundEffectType,
-) -> bool {
+) -> SoundEffectPlayback {
+    if queued_sound_effect.len() >= MAX_QUEUED_SOUND_EFFECTS {
+        return SoundEffectPlayback::Dropped;
+    }
+
     let Some(path) = sound_effect_paths.get(sound_effect_key).cloned() else {
         // This case could happen, if the sound effect was queued for deletion.
-        return true;
+        return SoundEffectPlayback::Dropped;
     };
 
     queued_sound_effect.push(QueuedSoundEffect {
@@ -849,38 +3351,266 @@ fn queue_sound_effect_playback(
         queued_time: Instant::now(),
     });
 
-    spawn_async_load(game_file_loader, async_response_sender, path, sound_effect_key);
-    false
+    // Only spawn a single loading task per sound effect, so repeated requests
+    // don't flood the thread pool.
+    if loading_sound_effect.insert(sound_effect_key) {
+        let memory_data = memory_sound_effects
+            .get(&sound_effect_key)
+            .or_else(|| compressed_cache.get(&sound_effect_key).map(|compressed| &compressed.0))
+            .cloned();
+        spawn_async_load(
+            game_file_loader,
+            async_response_sender,
+            load_settings,
+            path,
+            memory_data,
+            sound_effect_key,
+            0,
+        );
+    }
+
+    SoundEffectPlayback::Queued
 }
 
-/// Spawns a loading task on the standard thread pool.
+/// Spawns a loading task on the standard thread pool. Sound effects that are
+/// played from memory or are kept compressed are decoded from `memory_data`
+/// instead of being loaded through the file loader.
 fn spawn_async_load(
     game_file_loader: Arc<impl FileLoader>,
     async_response_sender: Sender<AsyncLoadResult>,
+    load_settings: LoadSettings,
     path: String,
+    memory_data: Option<Arc<[u8]>>,
     key: SoundEffectKey,
+    attempt: u32,
 ) {
-    spawn(move || {
-        let full_path = format!("{SOUND_EFFECT_BASE_PATH}\\{path}");
-
-        let data = match game_file_loader.get(&full_path) {
-            Ok(data) => data,
-            Err(error) => {
-                let message = format!("can't find audio file: {error:?}");
-                let _ = async_response_sender.send(AsyncLoadResult::Error { message, path, key });
-                return;
+    let thread_pool = load_settings.thread_pool.clone();
+    let task = move || {
+        let data: Arc<[u8]> = match memory_data {
+            Some(data) => data,
+            None => {
+                let base_path = &load_settings.sound_effect_base_path;
+                let full_path = match base_path.is_empty() {
+                    true => path.clone(),
+                    false => format!("{}\\{path}", base_path.trim_end_matches(['\\', '/'])),
+                };
+
+                match game_file_loader.get(&full_path) {
+                    Ok(data) => data.into(),
+                    // Loaded again by the engine on a later frame, so the worker isn't blocked
+                    // while waiting.
+                    Err(error) if should_retry_load(&error, attempt) => {
+                        let attempt = attempt + 1;
+                        let _ = async_response_sender.send(AsyncLoadResult::Retry { path, key, attempt });
+                        return;
+                    }
+                    Err(error) => {
+                        let error = SoundEffectLoadError::NotFound(error);
+                        let _ = async_response_sender.send(AsyncLoadResult::Error { path, key, error });
+                        return;
+                    }
+                }
             }
         };
-        let sound_effect = match StaticSoundData::from_cursor(Cursor::new(data)) {
+        let sound_effect = match StaticSoundData::from_cursor(Cursor::new(data.clone())) {
+            Ok(sound_effect) if load_settings.normalize_sample_rate => {
+                Box::new(normalize_sample_rate(sound_effect, load_settings.resample_quality))
+            }
             Ok(sound_effect) => Box::new(sound_effect),
             Err(error) => {
-                let message = format!("can't decode audio file: {error:?}");
-                let _ = async_response_sender.send(AsyncLoadResult::Error { message, path, key });
+                let error = SoundEffectLoadError::Decode(error);
+                let _ = async_response_sender.send(AsyncLoadResult::Error { path, key, error });
                 return;
             }
         };
-        let _ = async_response_sender.send(AsyncLoadResult::Loaded { path, key, sound_effect });
-    });
+
+        let result = match load_settings.compressed_threshold {
+            Some(threshold) if data.len() >= threshold => AsyncLoadResult::LoadedCompressed {
+                path,
+                key,
+                data,
+                sound_effect,
+            },
+            _ => AsyncLoadResult::Loaded { path, key, sound_effect },
+        };
+        let _ = async_response_sender.send(result);
+    };
+
+    match thread_pool {
+        Some(thread_pool) => thread_pool.spawn(task),
+        None => spawn(task),
+    }
+}
+
+/// Decodes a music track on the loading threads for
+/// [`MusicDecodeQuality::Reduced`].
+fn spawn_music_load(
+    async_response_sender: Sender<AsyncLoadResult>,
+    load_settings: LoadSettings,
+    layer_name: String,
+    track_name: String,
+    path: PathBuf,
+) {
+    let thread_pool = load_settings.thread_pool.clone();
+    let task = move || {
+        let result = match StaticSoundData::from_file(path) {
+            Ok(music) => AsyncLoadResult::MusicLoaded {
+                layer_name,
+                track_name,
+                music: Box::new(reduce_sample_rate(music, load_settings.resample_quality)),
+            },
+            Err(error) => AsyncLoadResult::MusicError {
+                layer_name,
+                track_name,
+                error,
+            },
+        };
+        let _ = async_response_sender.send(result);
+    };
+
+    match thread_pool {
+        Some(thread_pool) => thread_pool.spawn(task),
+        None => spawn(task),
+    }
+}
+
+/// Returns `true` if a sound effect that couldn't be loaded should be loaded
+/// again. Only transient failures, like a briefly locked archive, are retried.
+/// Missing files and decode errors would fail again.
+fn should_retry_load(error: &FileNotFoundError, attempt: u32) -> bool {
+    error.is_transient() && attempt < LOAD_RETRY_ATTEMPTS
+}
+
+/// Moves the eviction pressure towards 1 if a prefetched sound effect was
+/// evicted before it was played and towards 0 otherwise.
+fn update_eviction_pressure(eviction_pressure: f32, evicted: bool) -> f32 {
+    let target = match evicted {
+        true => 1.0,
+        false => 0.0,
+    };
+
+    eviction_pressure + (target - eviction_pressure) * EVICTION_PRESSURE_SMOOTHING
+}
+
+/// Reduces the number of sound effects to prefetch according to the eviction
+/// pressure. At least one sound effect is still prefetched if there is room,
+/// so the eviction pressure can recover.
+fn throttled_prefetch_count(free_count: usize, eviction_pressure: f32) -> usize {
+    let throttled_count = (free_count as f32 * (1.0 - eviction_pressure.clamp(0.0, 1.0))) as usize;
+    throttled_count.max(1).min(free_count)
+}
+
+/// Clamps every component of the point into the range of the same component
+/// of `min` and `max`.
+fn clamp_point(point: Point3<f32>, min: Point3<f32>, max: Point3<f32>) -> Point3<f32> {
+    Point3::from_vec(point.to_vec().zip(min.to_vec(), f32::max).zip(max.to_vec(), f32::min))
+}
+
+/// Returns the delay after which a cycling ambient sound is restarted, randomly
+/// deviating from the cycle by up to the given jitter fraction.
+fn jittered_cycle(rng: &mut impl Rng, cycle: f32, jitter: f32) -> f32 {
+    if jitter <= 0.0 {
+        return cycle;
+    }
+
+    cycle * (1.0 + rng.gen_range(-jitter..=jitter))
+}
+
+/// Resamples the sound effect to [`NORMALIZED_SAMPLE_RATE`], so that all
+/// cached sound effects share the same sample rate.
+fn normalize_sample_rate(mut data: StaticSoundData, resample_quality: ResampleQuality) -> StaticSoundData {
+    if data.sample_rate == NORMALIZED_SAMPLE_RATE || data.frames.is_empty() {
+        return data;
+    }
+
+    let ratio = data.sample_rate as f64 / NORMALIZED_SAMPLE_RATE as f64;
+    data.frames = resample_frames(&data.frames, ratio, resample_quality).into();
+    data.sample_rate = NORMALIZED_SAMPLE_RATE;
+    data
+}
+
+/// Resamples a music track to [`REDUCED_MUSIC_SAMPLE_RATE`]. Tracks that
+/// already have a lower sample rate are left unchanged.
+fn reduce_sample_rate(mut data: StaticSoundData, resample_quality: ResampleQuality) -> StaticSoundData {
+    if data.sample_rate <= REDUCED_MUSIC_SAMPLE_RATE || data.frames.is_empty() {
+        return data;
+    }
+
+    // Frequencies above the new Nyquist frequency would fold back into the
+    // audible range, so they are filtered out first.
+    let mut frames = data.frames.to_vec();
+    low_pass_frames(&mut frames, data.sample_rate, REDUCED_MUSIC_SAMPLE_RATE as f32 * 0.45);
+
+    let ratio = data.sample_rate as f64 / REDUCED_MUSIC_SAMPLE_RATE as f64;
+    data.frames = resample_frames(&frames, ratio, resample_quality).into();
+    data.sample_rate = REDUCED_MUSIC_SAMPLE_RATE;
+    data
+}
+
+/// Filters out frequencies above the cutoff frequency with two passes of a
+/// second order Butterworth low-pass filter.
+fn low_pass_frames(frames: &mut [Frame], sample_rate: u32, cutoff_hz: f32) {
+    let omega = std::f32::consts::TAU * cutoff_hz / sample_rate as f32;
+    let alpha = omega.sin() * std::f32::consts::FRAC_1_SQRT_2;
+    let cos_omega = omega.cos();
+    let a0 = 1.0 + alpha;
+
+    let b0 = (1.0 - cos_omega) / 2.0 / a0;
+    let b1 = (1.0 - cos_omega) / a0;
+    let a1 = -2.0 * cos_omega / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    for _ in 0..2 {
+        let (mut input_1, mut input_2) = (Frame::ZERO, Frame::ZERO);
+        let (mut output_1, mut output_2) = (Frame::ZERO, Frame::ZERO);
+
+        for frame in frames.iter_mut() {
+            let input = *frame;
+            let output = input * b0 + input_1 * b1 + input_2 * b0 - output_1 * a1 - output_2 * a2;
+
+            (input_2, input_1) = (input_1, input);
+            (output_2, output_1) = (output_1, output);
+            *frame = output;
+        }
+    }
+}
+
+/// Resamples the frames, where `ratio` is the source sample rate divided by the
+/// target sample rate.
+fn resample_frames(frames: &[Frame], ratio: f64, resample_quality: ResampleQuality) -> Vec<Frame> {
+    let last_index = frames.len() as isize - 1;
+    let frame_at = |index: isize| frames[index.clamp(0, last_index) as usize];
+    let frame_count = (frames.len() as f64 / ratio).ceil() as usize;
+
+    (0..frame_count)
+        .map(|index| {
+            let position = index as f64 * ratio;
+            let base = position.floor() as isize;
+            let fraction = (position - base as f64) as f32;
+
+            match resample_quality {
+                ResampleQuality::Fast => frame_at(position.round() as isize),
+                ResampleQuality::Balanced => {
+                    let current = frame_at(base);
+                    current + (frame_at(base + 1) - current) * fraction
+                }
+                ResampleQuality::High => {
+                    // Catmull-Rom spline through the four surrounding frames.
+                    let previous = frame_at(base - 1);
+                    let current = frame_at(base);
+                    let next = frame_at(base + 1);
+                    let after_next = frame_at(base + 2);
+
+                    let a = current * 2.0;
+                    let b = next - previous;
+                    let c = previous * 2.0 - current * 5.0 + next * 4.0 - after_next;
+                    let d = current * 3.0 - previous - next * 3.0 + after_next;
+
+                    (a + b * fraction + c * (fraction * fraction) + d * (fraction * fraction * fraction)) * 0.5
+                }
+            }
+        })
+        .collect()
 }
 
 fn parse_background_music_track_mapping(game_file_loader: &impl FileLoader) -> HashMap<String, String> {
@@ -941,6 +3671,50 @@ fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
         .map(|entry| entry.path())
 }
 
+/// Removes the ambient sounds that are already registered from the ambient
+/// sounds to restore and returns the keys of the registered ambient sounds
+/// that are not restored. Ambient sounds are matched by their configuration
+/// rather than their key, since the key of a removed ambient sound can be
+/// handed out again to a different one.
+fn match_restored_ambient_sounds<K>(registered: Vec<(K, AmbientSoundConfig)>, restored: &mut Vec<AmbientSoundConfig>) -> Vec<K> {
+    registered
+        .into_iter()
+        .filter_map(|(key, sound_config)| {
+            match restored
+                .iter()
+                .position(|restored_config| restored_config.is_same_as(&sound_config))
+            {
+                Some(index) => {
+                    restored.swap_remove(index);
+                    None
+                }
+                None => Some(key),
+            }
+        })
+        .collect()
+}
+
+/// Keeps at most `max_count` ambient sounds. Ambient sounds with a higher
+/// priority are kept first, ties are broken by the distance to the listener.
+fn limit_ambient_sounds<T: Copy>(ambient_keys: &mut Vec<T>, max_count: usize, mut priority_and_distance: impl FnMut(T) -> (u8, f32)) {
+    if ambient_keys.len() <= max_count {
+        return;
+    }
+
+    let mut ranked: Vec<(T, u8, f32)> = ambient_keys
+        .iter()
+        .map(|&ambient_key| {
+            let (priority, distance) = priority_and_distance(ambient_key);
+            (ambient_key, priority, distance)
+        })
+        .collect();
+
+    ranked.sort_by(|left, right| right.1.cmp(&left.1).then(left.2.total_cmp(&right.2)));
+
+    ambient_keys.clear();
+    ambient_keys.extend(ranked.into_iter().take(max_count).map(|(ambient_key, ..)| ambient_key));
+}
+
 fn difference<T: Ord + Copy>(vector_1: &mut [T], vector_2: &mut [T], result: &mut Vec<T>) {
     result.clear();
 
@@ -968,7 +3742,145 @@ fn difference<T: Ord + Copy>(vector_1: &mut [T], vector_2: &mut [T], result: &mu
 
 #[cfg(test)]
 mod tests {
-    use crate::difference;
+    use std::num::{NonZeroU32, NonZeroUsize};
+    use std::time::{Duration, Instant};
+
+    use cgmath::{Deg, EuclideanSpace, InnerSpace, Point3, Quaternion, Rotation3, Vector3};
+    use kira::Frame;
+    use korangar_util::collision::{Capsule, Sphere, AABB};
+    use korangar_util::container::{Cacheable, GenerationalSlab, SimpleCache};
+    use korangar_util::FileNotFoundError;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::{
+        difference, distance_attenuation, facing_rotation, facing_vectors, jittered_cycle, limit_ambient_sounds, listener_orientation,
+        low_pass_frames, match_restored_ambient_sounds, normalize_volume, resample_frames, should_retry_load, throttled_prefetch_count,
+        update_eviction_pressure, voices_to_steal, AmbientBounds, AmbientSoundConfig, ListenerTransition, PinnedSoundEffect, PrefetchQueue,
+        PrefetchTracker, ResampleQuality, SoundEffectKey, LOAD_RETRY_ATTEMPTS, MAX_PREFETCH_LOADS,
+    };
+
+    #[test]
+    fn test_eviction_pressure() {
+        let mut eviction_pressure = 0.0;
+
+        for _ in 0..50 {
+            eviction_pressure = update_eviction_pressure(eviction_pressure, true);
+        }
+        assert!(eviction_pressure > 0.99);
+
+        for _ in 0..50 {
+            eviction_pressure = update_eviction_pressure(eviction_pressure, false);
+        }
+        assert!(eviction_pressure < 0.01);
+    }
+
+    #[test]
+    fn test_voices_to_steal() {
+        assert_eq!(voices_to_steal(3, 3, 4), Some(0));
+        assert_eq!(voices_to_steal(4, 4, 3), Some(1));
+        assert_eq!(voices_to_steal(4, 1, 3), Some(1));
+        assert_eq!(voices_to_steal(4, 0, 3), None);
+        assert_eq!(voices_to_steal(5, 1, 3), None);
+    }
+
+    #[test]
+    fn test_normalize_volume() {
+        assert_eq!(normalize_volume(0.5), 0.5);
+        assert_eq!(normalize_volume(-1.0), 0.0);
+        assert_eq!(normalize_volume(2.0), 1.0);
+        assert_eq!(normalize_volume(f32::NAN), 0.0);
+        assert_eq!(normalize_volume(f32::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn test_throttled_prefetch_count() {
+        assert_eq!(throttled_prefetch_count(10, 0.0), 10);
+        assert_eq!(throttled_prefetch_count(10, 0.5), 5);
+        assert_eq!(throttled_prefetch_count(10, 1.0), 1);
+        assert_eq!(throttled_prefetch_count(0, 0.0), 0);
+    }
+
+    /// Fails the given number of times before returning the file.
+    #[test]
+    fn test_should_retry_load() {
+        let unreadable = FileNotFoundError::unreadable("test.wav".to_string());
+        assert!(should_retry_load(&unreadable, 0));
+        assert!(!should_retry_load(&unreadable, LOAD_RETRY_ATTEMPTS));
+
+        let not_found = FileNotFoundError::new("test.wav".to_string());
+        assert!(!should_retry_load(&not_found, 0));
+    }
+
+    fn assert_vector_eq(actual: Vector3<f32>, expected: Vector3<f32>) {
+        assert!((actual - expected).magnitude() < 1e-5, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_facing_vectors() {
+        let (view_direction, look_up) = facing_vectors(Quaternion::from_angle_y(Deg(0.0)));
+        assert_vector_eq(view_direction, Vector3::unit_z());
+        assert_vector_eq(look_up, Vector3::unit_y());
+
+        let (view_direction, look_up) = facing_vectors(Quaternion::from_angle_y(Deg(90.0)));
+        assert_vector_eq(view_direction, Vector3::unit_x());
+        assert_vector_eq(look_up, Vector3::unit_y());
+    }
+
+    #[test]
+    fn test_facing_rotation() {
+        for rotation in [
+            Quaternion::from_angle_y(Deg(0.0)),
+            Quaternion::from_angle_y(Deg(90.0)),
+            Quaternion::from_angle_x(Deg(30.0)),
+            Quaternion::from_axis_angle(Vector3::new(1.0, 2.0, 3.0).normalize(), Deg(170.0)),
+        ] {
+            let (view_direction, look_up) = facing_vectors(rotation);
+            let (view_direction_2, look_up_2) = facing_vectors(facing_rotation(view_direction, look_up));
+
+            assert_vector_eq(view_direction_2, view_direction);
+            assert_vector_eq(look_up_2, look_up);
+        }
+    }
+
+    #[test]
+    fn test_listener_transition() {
+        let started = Instant::now();
+        let transition = ListenerTransition {
+            start_position: Point3::new(0.0, 0.0, 0.0),
+            start_rotation: Quaternion::from_angle_y(Deg(0.0)),
+            target_position: Point3::new(10.0, 0.0, 0.0),
+            target_rotation: Quaternion::from_angle_y(Deg(90.0)),
+            started,
+            duration: Duration::from_secs(2),
+        };
+
+        let (position, rotation, complete) = transition.transform_at(started + Duration::from_secs(1));
+        let (view_direction, _) = facing_vectors(rotation);
+        assert_vector_eq(position.to_vec(), Vector3::new(5.0, 0.0, 0.0));
+        assert_vector_eq(view_direction, Quaternion::from_angle_y(Deg(45.0)) * Vector3::unit_z());
+        assert!(!complete);
+
+        let (position, _, complete) = transition.transform_at(started + Duration::from_secs(3));
+        assert_vector_eq(position.to_vec(), Vector3::new(10.0, 0.0, 0.0));
+        assert!(complete);
+    }
+
+    #[test]
+    fn test_listener_orientation() {
+        // Facing along +Z in Korangar means facing along -Z in kira, which is
+        // the unrotated orientation.
+        let orientation = listener_orientation(Vector3::unit_z(), Vector3::unit_y());
+        assert_vector_eq(orientation * Vector3::unit_x(), Vector3::unit_x());
+        assert_vector_eq(orientation * -Vector3::unit_z(), -Vector3::unit_z());
+
+        // Facing along +X in Korangar puts -Z of Korangar on the right, which
+        // is +Z in kira. Getting this wrong reverses the stereo image.
+        let (view_direction, look_up) = facing_vectors(Quaternion::from_angle_y(Deg(90.0)));
+        let orientation = listener_orientation(view_direction, look_up);
+        assert_vector_eq(orientation * Vector3::unit_x(), Vector3::unit_z());
+        assert_vector_eq(orientation * Vector3::unit_y(), Vector3::unit_y());
+    }
 
     #[test]
     fn test_difference() {
@@ -1013,4 +3925,302 @@ mod tests {
 
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_limit_ambient_sounds_by_distance() {
+        let mut ambient_keys = vec![1, 2, 3, 4];
+        let distances = [0.0, 40.0, 10.0, 30.0, 20.0];
+
+        limit_ambient_sounds(&mut ambient_keys, 2, |key: usize| (0, distances[key]));
+
+        assert_eq!(ambient_keys, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_limit_ambient_sounds_prefers_priority() {
+        let mut ambient_keys = vec![1, 2, 3];
+        let priorities = [0, 0, 0, 5];
+        let distances = [0.0, 10.0, 20.0, 500.0];
+
+        limit_ambient_sounds(&mut ambient_keys, 1, |key: usize| (priorities[key], distances[key]));
+
+        assert_eq!(ambient_keys, vec![3]);
+    }
+
+    #[test]
+    fn test_limit_ambient_sounds_below_limit() {
+        let mut ambient_keys = vec![3, 1, 2];
+
+        limit_ambient_sounds(&mut ambient_keys, 5, |_: usize| (0, 0.0));
+
+        assert_eq!(ambient_keys, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_resample_same_rate() {
+        let frames: Vec<Frame> = (0..8).map(|index| Frame::from_mono(index as f32)).collect();
+
+        for quality in [ResampleQuality::Fast, ResampleQuality::Balanced, ResampleQuality::High] {
+            assert_eq!(resample_frames(&frames, 1.0, quality), frames);
+        }
+    }
+
+    #[test]
+    fn test_resample_upsampling() {
+        let frames: Vec<Frame> = (0..4).map(|index| Frame::from_mono(index as f32)).collect();
+
+        let result = resample_frames(&frames, 0.5, ResampleQuality::Balanced);
+
+        assert_eq!(result.len(), 8);
+        assert_eq!(result[1], Frame::from_mono(0.5));
+        assert_eq!(result[2], Frame::from_mono(1.0));
+    }
+
+    #[test]
+    fn test_resample_downsampling() {
+        let frames: Vec<Frame> = (0..8).map(|index| Frame::from_mono(index as f32)).collect();
+
+        let result = resample_frames(&frames, 2.0, ResampleQuality::Fast);
+
+        assert_eq!(result, vec![
+            Frame::from_mono(0.0),
+            Frame::from_mono(2.0),
+            Frame::from_mono(4.0),
+            Frame::from_mono(6.0)
+        ]);
+    }
+
+    #[test]
+    fn test_prefetch_fills_cache() {
+        struct TestSound(usize);
+
+        impl Cacheable for TestSound {
+            fn size(&self) -> usize {
+                self.0
+            }
+        }
+
+        let mut sound_effect_paths = GenerationalSlab::<SoundEffectKey, String>::default();
+        let sound_effect_keys: Vec<SoundEffectKey> = (0..10)
+            .map(|index| sound_effect_paths.insert(format!("{index}.wav")).unwrap())
+            .collect();
+        let mut cache = SimpleCache::new(NonZeroU32::new(100).unwrap(), NonZeroUsize::new(3500).unwrap());
+        let mut prefetch_queue = PrefetchQueue::default();
+
+        prefetch_queue.reset(sound_effect_keys.iter().copied(), 100, cache.max_size() - cache.size());
+
+        // Loads finish in batches, like they would between two updates of the engine.
+        loop {
+            let batch: Vec<SoundEffectKey> = std::iter::from_fn(|| prefetch_queue.next()).collect();
+
+            if batch.is_empty() {
+                break;
+            }
+
+            assert!(batch.len() <= MAX_PREFETCH_LOADS);
+
+            for sound_effect_key in batch {
+                if prefetch_queue.loaded(sound_effect_key, 1000) {
+                    cache.insert(sound_effect_key, TestSound(1000)).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(cache.size(), 3000);
+        assert!(sound_effect_keys[..3].iter().all(|key| cache.contains_key(key)));
+        assert!(prefetch_queue.queued.is_empty());
+        assert!(prefetch_queue.loading.is_empty());
+    }
+
+    #[test]
+    fn test_prefetch_count_budget() {
+        let mut sound_effect_paths = GenerationalSlab::<SoundEffectKey, String>::default();
+        let sound_effect_keys: Vec<SoundEffectKey> = (0..10)
+            .map(|index| sound_effect_paths.insert(format!("{index}.wav")).unwrap())
+            .collect();
+        let mut prefetch_queue = PrefetchQueue::default();
+
+        prefetch_queue.reset(sound_effect_keys.iter().copied(), 2, usize::MAX);
+
+        assert_eq!(prefetch_queue.next(), Some(sound_effect_keys[0]));
+        assert_eq!(prefetch_queue.next(), Some(sound_effect_keys[1]));
+        assert_eq!(prefetch_queue.next(), None);
+    }
+
+    #[test]
+    fn test_prefetch_failed_loads() {
+        let mut sound_effect_paths = GenerationalSlab::<SoundEffectKey, String>::default();
+        let failed_key = sound_effect_paths.insert("failed.wav".to_string()).unwrap();
+        let evicted_key = sound_effect_paths.insert("evicted.wav".to_string()).unwrap();
+        let mut prefetch_tracker = PrefetchTracker::default();
+
+        prefetch_tracker.pending.extend([failed_key, evicted_key]);
+        prefetch_tracker.mark_failed(failed_key);
+        // A failed load is no longer pending, so it can't be counted as evicted later.
+        prefetch_tracker.mark_evicted(failed_key);
+        prefetch_tracker.mark_evicted(evicted_key);
+
+        let statistics = prefetch_tracker.statistics();
+        assert_eq!(statistics.failed, 1);
+        assert_eq!(statistics.evicted, 1);
+        assert_eq!(statistics.eviction_pressure, update_eviction_pressure(0.0, true));
+    }
+
+    #[test]
+    fn test_pinned_sound_effect_unpin() {
+        let mut pinned_sound_effect = PinnedSoundEffect {
+            pin_count: 2,
+            ..Default::default()
+        };
+
+        assert!(!pinned_sound_effect.unpin());
+        assert!(pinned_sound_effect.unpin());
+    }
+
+    #[test]
+    fn test_pinned_ui_sound_effect_stays_pinned() {
+        let mut pinned_sound_effect = PinnedSoundEffect {
+            pin_count: 1,
+            ui_sound: true,
+            ..Default::default()
+        };
+
+        assert!(!pinned_sound_effect.unpin());
+        assert!(!pinned_sound_effect.unpin());
+    }
+
+    #[test]
+    fn test_low_pass_frames() {
+        let root_mean_square =
+            |frames: &[Frame]| (frames.iter().map(|frame| frame.left * frame.left).sum::<f32>() / frames.len() as f32).sqrt();
+        let sine = |frequency: f32| -> Vec<Frame> {
+            (0..44100)
+                .map(|index| Frame::from_mono((std::f32::consts::TAU * frequency * index as f32 / 44100.0).sin()))
+                .collect()
+        };
+
+        let mut low = sine(440.0);
+        let mut high = sine(15000.0);
+        let input_level = root_mean_square(&low);
+
+        low_pass_frames(&mut low, 44100, 9922.5);
+        low_pass_frames(&mut high, 44100, 9922.5);
+
+        assert!((root_mean_square(&low) - input_level).abs() < 0.01);
+        assert!(root_mean_square(&high) < input_level * 0.2);
+    }
+
+    #[test]
+    fn test_jittered_cycle_without_jitter() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(jittered_cycle(&mut rng, 4.0, 0.0), 4.0);
+    }
+
+    #[test]
+    fn test_jittered_cycle_is_deterministic() {
+        let mut rng_1 = StdRng::seed_from_u64(42);
+        let mut rng_2 = StdRng::seed_from_u64(42);
+
+        for _ in 0..16 {
+            let delay = jittered_cycle(&mut rng_1, 4.0, 0.25);
+
+            assert_eq!(delay, jittered_cycle(&mut rng_2, 4.0, 0.25));
+            assert!((3.0..=5.0).contains(&delay));
+        }
+    }
+
+    #[test]
+    fn test_distance_attenuation() {
+        assert_eq!(distance_attenuation(0.0, 25.0), 1.0);
+        assert_eq!(distance_attenuation(5.0, 25.0), 1.0);
+        assert_eq!(distance_attenuation(15.0, 25.0), 0.5);
+        assert_eq!(distance_attenuation(25.0, 25.0), 0.0);
+        assert_eq!(distance_attenuation(40.0, 25.0), 0.0);
+    }
+
+    #[test]
+    fn test_distance_attenuation_small_range() {
+        assert_eq!(distance_attenuation(2.0, 3.0), 1.0);
+        assert_eq!(distance_attenuation(6.0, 3.0), 0.0);
+    }
+
+    #[test]
+    fn test_ambient_bounds_sphere() {
+        let bounds = AmbientBounds::Sphere(Sphere::new(Point3::new(0.0, 0.0, 0.0), 5.0));
+
+        assert!(bounds.contains(Point3::new(3.0, 4.0, 0.0)));
+        assert_eq!(bounds.distance(Point3::new(10.0, 0.0, 0.0)), 5.0);
+        assert_eq!(bounds.range(), 5.0);
+        assert_eq!(bounds.emitter_position(Point3::new(10.0, 0.0, 0.0)), Point3::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_ambient_bounds_aabb() {
+        let bounds = AmbientBounds::Aabb(AABB::new(Point3::new(0.0, 0.0, 0.0), Point3::new(20.0, 4.0, 10.0)));
+
+        assert!(bounds.contains(Point3::new(10.0, 2.0, 5.0)));
+        assert_eq!(bounds.distance(Point3::new(25.0, 2.0, 5.0)), 5.0);
+        assert_eq!(bounds.range(), 5.0);
+        assert_eq!(bounds.emitter_position(Point3::new(0.0, 2.0, 0.0)), Point3::new(5.0, 2.0, 5.0));
+        assert_eq!(
+            bounds.emitter_position(Point3::new(30.0, 0.0, 8.0)),
+            Point3::new(15.0, 2.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn test_ambient_bounds_capsule() {
+        let bounds = AmbientBounds::Capsule(Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 0.0, 0.0), 2.0));
+
+        assert!(bounds.contains(Point3::new(5.0, 1.0, 0.0)));
+        assert_eq!(bounds.distance(Point3::new(5.0, 5.0, 0.0)), 3.0);
+        assert_eq!(bounds.range(), 2.0);
+        assert_eq!(bounds.emitter_position(Point3::new(7.0, 0.0, 5.0)), Point3::new(7.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_match_restored_ambient_sounds() {
+        let mut sound_effect_paths = GenerationalSlab::default();
+        let sound_effect_key: SoundEffectKey = sound_effect_paths.insert("ambient.wav".to_string()).unwrap();
+        let config = |x: f32| AmbientSoundConfig {
+            sound_effect_key,
+            bounds: AmbientBounds::Sphere(Sphere::new(Point3::new(x, 0.0, 0.0), 5.0)),
+            volume: 1.0,
+            cycle: None,
+            priority: 0,
+        };
+
+        // The key of the unchanged ambient sound differs from its position in the
+        // snapshot, so it has to be matched by its configuration.
+        let registered = vec![(0, config(1.0)), (1, config(2.0)), (2, config(3.0))];
+        let mut restored = vec![config(3.0), config(4.0)];
+
+        let removed = match_restored_ambient_sounds(registered, &mut restored);
+
+        assert_eq!(removed, vec![0, 1]);
+        assert_eq!(restored.len(), 1);
+        assert!(restored[0].is_same_as(&config(4.0)));
+    }
+
+    #[test]
+    fn test_ambient_bounds_is_same_as() {
+        let sphere = AmbientBounds::Sphere(Sphere::new(Point3::new(0.0, 0.0, 0.0), 5.0));
+        let capsule = AmbientBounds::Capsule(Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 0.0), 5.0));
+
+        assert!(sphere.is_same_as(&sphere));
+        assert!(!sphere.is_same_as(&capsule));
+    }
+
+    #[test]
+    fn test_ambient_bounds_is_near() {
+        let sphere = AmbientBounds::Sphere(Sphere::new(Point3::new(0.0, 0.0, 0.0), 5.0));
+        let nearby_sphere = AmbientBounds::Sphere(Sphere::new(Point3::new(0.05, 0.0, 0.0), 5.05));
+        let distant_sphere = AmbientBounds::Sphere(Sphere::new(Point3::new(1.0, 0.0, 0.0), 5.0));
+        let capsule = AmbientBounds::Capsule(Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 0.0), 5.0));
+
+        assert!(sphere.is_near(&nearby_sphere, 0.1));
+        assert!(!sphere.is_near(&distant_sphere, 0.1));
+        assert!(!sphere.is_near(&capsule, 0.1));
+    }
 }
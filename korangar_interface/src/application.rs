@@ -92,6 +92,8 @@ where
 }
 
 pub trait ColorTrait: Clone {
+    fn rgba_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Self;
+
     fn is_transparent(&self) -> bool;
 }
 
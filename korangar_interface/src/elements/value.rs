@@ -1,4 +1,4 @@
-use crate::application::Application;
+use crate::application::{Application, ColorTrait};
 use crate::elements::{Element, ElementState};
 use crate::layout::PlacementResolver;
 use crate::theme::{InterfaceTheme, ValueTheme};
@@ -63,3 +63,77 @@ where
         );
     }
 }
+
+/// Displays the components of a color on top of a swatch of that color.
+pub struct ColorValue<App>
+where
+    App: Application,
+{
+    color: App::Color,
+    text_color: App::Color,
+    value: String,
+    state: ElementState<App>,
+}
+
+impl<App> ColorValue<App>
+where
+    App: Application,
+{
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        // Pick black or white text depending on the perceived brightness of the
+        // swatch so the components stay readable.
+        let luminance = 0.299 * red as f32 + 0.587 * green as f32 + 0.114 * blue as f32;
+        let text_color = match luminance > 127.5 {
+            true => App::Color::rgba_u8(0, 0, 0, 255),
+            false => App::Color::rgba_u8(255, 255, 255, 255),
+        };
+
+        Self {
+            color: App::Color::rgba_u8(red, green, blue, alpha),
+            text_color,
+            value: format!("{red}, {green}, {blue}, {alpha}"),
+            state: Default::default(),
+        }
+    }
+}
+
+impl<App> Element<App> for ColorValue<App>
+where
+    App: Application,
+{
+    fn get_state(&self) -> &ElementState<App> {
+        &self.state
+    }
+
+    fn get_state_mut(&mut self) -> &mut ElementState<App> {
+        &mut self.state
+    }
+
+    fn resolve(&mut self, placement_resolver: &mut PlacementResolver<App>, _application: &App, theme: &App::Theme) {
+        self.state.resolve(placement_resolver, &theme.value().size_bound());
+    }
+
+    fn render(
+        &self,
+        renderer: &App::Renderer,
+        application: &App,
+        theme: &App::Theme,
+        parent_position: App::Position,
+        screen_clip: App::Clip,
+        _hovered_element: Option<&dyn Element<App>>,
+        _focused_element: Option<&dyn Element<App>>,
+        _mouse_mode: &App::MouseInputMode,
+        _second_theme: bool,
+    ) {
+        let mut renderer = self.state.element_renderer(renderer, application, parent_position, screen_clip);
+
+        renderer.render_background(theme.value().corner_radius(), self.color.clone());
+
+        renderer.render_text(
+            &self.value,
+            theme.value().text_offset(),
+            self.text_color.clone(),
+            theme.value().font_size(),
+        );
+    }
+}
@@ -1,5 +1,35 @@
 use ragnarok_packets::*;
 
+/// The current and maximum health points of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthState {
+    pub current: usize,
+    pub maximum: usize,
+}
+
+impl HealthState {
+    pub fn new(current: usize, maximum: usize) -> Self {
+        Self { current, maximum }
+    }
+
+    /// Returns the fraction of remaining health points in the range
+    /// `0.0..=1.0`. An entity without any maximum health points is treated
+    /// as empty.
+    pub fn fraction(&self) -> f32 {
+        if self.maximum == 0 {
+            return 0.0;
+        }
+
+        (self.current as f32 / self.maximum as f32).clamp(0.0, 1.0)
+    }
+}
+
+impl From<&UpdateEntityHealthPointsPacket> for HealthState {
+    fn from(packet: &UpdateEntityHealthPointsPacket) -> Self {
+        Self::new(packet.health_points as usize, packet.maximum_health_points as usize)
+    }
+}
+
 #[derive(Debug)]
 pub struct EntityData {
     pub entity_id: EntityId,
@@ -83,3 +113,24 @@ impl From<MovingEntityAppearedPacket> for EntityData {
         }
     }
 }
+
+#[cfg(test)]
+mod health_state {
+    use crate::HealthState;
+
+    #[test]
+    fn fraction() {
+        assert_eq!(HealthState::new(50, 200).fraction(), 0.25);
+    }
+
+    #[test]
+    fn zero_maximum() {
+        assert_eq!(HealthState::new(0, 0).fraction(), 0.0);
+        assert_eq!(HealthState::new(10, 0).fraction(), 0.0);
+    }
+
+    #[test]
+    fn current_above_maximum() {
+        assert_eq!(HealthState::new(300, 200).fraction(), 1.0);
+    }
+}
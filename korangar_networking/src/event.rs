@@ -1,17 +1,22 @@
-use std::time::Instant;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
 use ragnarok_packets::*;
 
 use crate::hotkey::HotkeyState;
 use crate::items::ShopItem;
 use crate::{
-    CharacterServerLoginData, EntityData, InventoryItem, LoginServerLoginData, MessageColor, NoMetadata,
-    UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
+    parse_color_codes, CharacterServerLoginData, ChatSpan, EnterGameFailedReason, EntityData, InventoryItem, LoginServerLoginData,
+    MessageColor, NoMetadata, PartyMember, UnifiedBuyItemsResult, UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
 };
 
 /// An event triggered by one of the Ragnarok Online servers.
 #[derive(Debug)]
 pub enum NetworkEvent {
+    /// Started connecting to a server.
+    Connecting {
+        address: SocketAddr,
+    },
     LoginServerConnected {
         character_servers: Vec<CharacterServerInformation>,
         login_data: LoginServerLoginData,
@@ -37,6 +42,14 @@ pub enum NetworkEvent {
     CharacterList {
         characters: Vec<CharacterInformation>,
     },
+    /// The character server asks for a pincode. The pincode can be sent with
+    /// [`NetworkingSystem::submit_pincode`](crate::NetworkingSystem::submit_pincode),
+    /// using the given seed.
+    Pincode {
+        pincode_seed: u32,
+        account_id: AccountId,
+        state: u16,
+    },
     CharacterSelected {
         login_data: CharacterServerLoginData,
     },
@@ -44,6 +57,29 @@ pub enum NetworkEvent {
         reason: UnifiedCharacterSelectionFailedReason,
         message: &'static str,
     },
+    /// Successfully logged into the map server. The player spawns at the
+    /// given position. Always preceded by an
+    /// [`UpdateClientTick`](NetworkEvent::UpdateClientTick) with the same
+    /// client tick.
+    MapLoginSuccess {
+        position: WorldPosition,
+        client_tick: ClientTick,
+        font: u16,
+    },
+    /// The login started with
+    /// [`NetworkingSystem::enter_game`](crate::NetworkingSystem::enter_game)
+    /// finished and the player is on the map server.
+    EnteredGame {
+        map_name: String,
+        position: WorldPosition,
+        character_id: CharacterId,
+    },
+    /// The login started with
+    /// [`NetworkingSystem::enter_game`](crate::NetworkingSystem::enter_game)
+    /// failed.
+    EnterGameFailed {
+        reason: EnterGameFailedReason,
+    },
     CharacterCreated {
         character_information: CharacterInformation,
     },
@@ -59,8 +95,8 @@ pub enum NetworkEvent {
     MapServerDisconnected {
         reason: DisconnectReason,
     },
-    /// Resurrect a player.
-    ResurrectPlayer {
+    /// An entity was resurrected and should return to its living state.
+    EntityResurrected {
         entity_id: EntityId,
     },
     /// Make a player stand up.
@@ -81,6 +117,12 @@ pub enum NetworkEvent {
     EntityMove(EntityId, WorldPosition, WorldPosition, ClientTick),
     /// Player was moved to a new position on a different map or the current map
     ChangeMap(String, TilePosition),
+    /// The type and properties of the current map, e.g. whether players can
+    /// attack each other.
+    MapType {
+        map_type: u16,
+        flags: MapFlags,
+    },
     /// Update the client side [`tick
     /// counter`](crate::system::GameTimer::base_client_tick) to keep server and
     /// client synchronized.
@@ -92,24 +134,96 @@ pub enum NetworkEvent {
     ChatMessage {
         text: String,
         color: MessageColor,
+        /// The text split at its embedded color codes, see
+        /// [`parse_color_codes`](crate::parse_color_codes).
+        spans: Vec<ChatSpan>,
     },
     CharacterSlotSwitched,
     CharacterSlotSwitchFailed,
     /// Update entity details. Mostly received when the client sends
     /// [RequestDetailsPacket] after the player hovered an entity.
     UpdateEntityDetails(EntityId, String),
-    UpdateEntityHealth(EntityId, usize, usize),
+    /// The current and maximum health points of an entity changed.
+    EntityHealth {
+        entity_id: EntityId,
+        health_points: usize,
+        maximum_health_points: usize,
+    },
     DamageEffect {
         entity_id: EntityId,
         damage_amount: usize,
+        combat_result: CombatResult,
     },
     HealEffect(EntityId, usize),
+    /// The player gained base or job experience.
+    ExperienceGained {
+        amount: u64,
+        experience_type: ExperienceType,
+        source: ExperienceSource,
+    },
     UpdateStatus(StatusType),
     OpenDialog(String, EntityId),
-    AddNextButton,
-    AddCloseButton,
+    /// The NPC with the given id added a button to the dialog.
+    NpcDialogButton {
+        npc_id: EntityId,
+        kind: DialogButtonKind,
+    },
     AddChoiceButtons(Vec<String>),
+    /// An NPC wants to show an illustration. An image location of
+    /// [`ImageLocation::ClearAll`] removes all illustrations instead.
+    DisplayImage {
+        image_name: String,
+        location: ImageLocation,
+    },
+    /// The server requested to navigate the player to a target, for example
+    /// from the navigation system.
+    Navigate {
+        target_type: NavigationTargetType,
+        map_name: String,
+        position: TilePosition,
+        monster_id: u16,
+        flags: u8,
+    },
+    /// An NPC placed or removed a marker on the minimap. A marker is
+    /// identified by the NPC id and the marker id, and a marker type of
+    /// [`MarkerType::RemoveMark`] removes it again.
+    MinimapMarker {
+        npc_id: EntityId,
+        id: u8,
+        marker_type: MarkerType,
+        position: LargeTilePosition,
+        color: ColorBGRA,
+    },
     AddQuestEffect(QuestEffectPacket),
+    /// A setting of the player changed, either because the player toggled it
+    /// or because the server sent it after entering a map.
+    ConfigurationChanged {
+        option: ConfigurationOption,
+        enabled: bool,
+    },
+    /// The status of the player after entering a map.
+    InitialStatus(InitialStatusPacket),
+    /// A special effect should be displayed on an entity.
+    SpecialEffect {
+        entity_id: EntityId,
+        effect_id: EffectId,
+    },
+    /// The result of trying to use a skill, including the reason if the skill
+    /// couldn't be used.
+    SkillUseResult {
+        skill_id: SkillId,
+        result: Result<(), SkillFailureCause>,
+    },
+    /// A skill can't be used again until the delay has passed.
+    SkillCooldown {
+        skill_id: SkillId,
+        delay: Duration,
+    },
+    /// The full list of quests of the player, independent of the client
+    /// version specific layout.
+    QuestList {
+        quests: Vec<Quest>,
+    },
     RemoveQuestEffect(EntityId),
     SetInventory {
         items: Vec<InventoryItem<NoMetadata>>,
@@ -117,11 +231,47 @@ pub enum NetworkEvent {
     IventoryItemAdded {
         item: InventoryItem<NoMetadata>,
     },
+    /// The storage was opened.
+    StorageOpened {
+        items: Vec<InventoryItem<NoMetadata, StorageIndex>>,
+    },
+    StorageItemCount {
+        item_count: u16,
+        maximum_item_count: u16,
+    },
+    /// An item was moved into the storage.
+    StorageItemAdded {
+        item: InventoryItem<NoMetadata, StorageIndex>,
+    },
+    /// An item was taken out of the storage.
+    StorageItemRemoved {
+        index: StorageIndex,
+        amount: u32,
+    },
+    StorageClosed,
     SkillTree(Vec<SkillInformation>),
     UpdateEquippedPosition {
         index: InventoryIndex,
         equipped_position: EquipPosition,
     },
+    /// The items in the equip switch set changed.
+    EquipSwitchUpdated(Vec<EquippableSwitchItemInformation>),
+    /// The map server confirmed that an item was added to the equip switch
+    /// set.
+    EquipSwitchItemAdded {
+        index: InventoryIndex,
+        position: EquipPosition,
+    },
+    /// The map server confirmed that an item was removed from the equip switch
+    /// set.
+    EquipSwitchItemRemoved {
+        index: InventoryIndex,
+    },
+    /// The currently equipped items were swapped with the equip switch set.
+    FullEquipSwitchCompleted,
+    /// The map server refused to swap the currently equipped items with the
+    /// equip switch set.
+    FullEquipSwitchFailed,
     ChangeJob {
         account_id: AccountId,
         job_id: u32,
@@ -130,7 +280,31 @@ pub enum NetworkEvent {
         account_id: AccountId,
         hair_id: u32,
     },
+    /// Part of the appearance of an entity changed, for example because it
+    /// equipped a different weapon or headgear. Job and hair changes are
+    /// reported as [`NetworkEvent::ChangeJob`] and
+    /// [`NetworkEvent::ChangeHair`] instead.
+    SpriteChanged {
+        account_id: AccountId,
+        sprite_type: SpriteChangeType,
+        value: u32,
+        value2: u32,
+    },
     LoggedOut,
+    /// The map server refused to log out immediately. The logout will be
+    /// requested again automatically once the delay has passed, until it is
+    /// cancelled with
+    /// [`NetworkingSystem::cancel_log_out`](crate::NetworkingSystem::cancel_log_out).
+    /// Only reported for the first delay.
+    LogoutDelayed {
+        delay: Duration,
+    },
+    /// Emitted every second while waiting for a delayed logout.
+    LogoutCountdown {
+        remaining_seconds: u64,
+    },
+    /// The map server kept delaying the logout, so we stopped requesting it.
+    LogoutFailed,
     FriendRequest {
         requestee: Friend,
     },
@@ -143,6 +317,11 @@ pub enum NetworkEvent {
     FriendAdded {
         friend: Friend,
     },
+    /// The members of the player's party or their health or position
+    /// changed. Always contains the full member list.
+    PartyUpdate {
+        members: Vec<PartyMember>,
+    },
     FriendRemoved {
         account_id: AccountId,
         character_id: CharacterId,
@@ -158,7 +337,49 @@ pub enum NetworkEvent {
         shop_id: ShopId,
     },
     BuyingCompleted {
-        result: BuyShopItemsResult,
+        result: UnifiedBuyItemsResult,
+    },
+    /// A player opened a vending shop.
+    VendingShopOpened {
+        owner: AccountId,
+        title: String,
+    },
+    VendingShopClosed {
+        owner: AccountId,
+    },
+    /// The items of a vending shop, requested with
+    /// [`NetworkingSystem::request_vending_items`](crate::NetworkingSystem::request_vending_items).
+    /// The metadata of the items is their index in the shop.
+    VendingOpened {
+        owner: AccountId,
+        vending_id: u32,
+        items: Vec<ShopItem<InventoryIndex>>,
+    },
+    /// The server responded to buying an item from a vending shop. rAthena
+    /// only responds if the purchase failed.
+    VendingPurchaseCompleted {
+        index: InventoryIndex,
+        amount: u16,
+        result: UnifiedBuyItemsResult,
+    },
+    /// The mails inside the mailbox, opened with
+    /// [`NetworkingSystem::open_mailbox`](crate::NetworkingSystem::open_mailbox).
+    /// Large mailboxes are sent in multiple pages.
+    MailInbox {
+        messages: Vec<MailInformation>,
+        is_last_page: bool,
+    },
+    /// The content of a mail, requested with
+    /// [`NetworkingSystem::read_mail`](crate::NetworkingSystem::read_mail).
+    /// The index of the attached items is their position in the mail.
+    MailOpened {
+        mail_id: MailId,
+        text: String,
+        zeny: u64,
+        items: Vec<InventoryItem<NoMetadata>>,
+    },
+    MailSent {
+        result: SendMailResult,
     },
     SellItemList {
         items: Vec<SellItemInformation>,
@@ -171,6 +392,21 @@ pub enum NetworkEvent {
         index: InventoryIndex,
         amount: u16,
     },
+    /// The emblem of a guild, requested with
+    /// [`NetworkingSystem::request_guild_emblem`](crate::NetworkingSystem::request_guild_emblem).
+    /// The image bytes are zlib compressed.
+    GuildEmblem {
+        guild_id: GuildId,
+        emblem_id: u32,
+        image_bytes: Vec<u8>,
+    },
+}
+
+impl NetworkEvent {
+    pub(crate) fn chat_message(text: String, color: MessageColor) -> Self {
+        let spans = parse_color_codes(&text, color);
+        Self::ChatMessage { text, color, spans }
+    }
 }
 
 /// New-type so we can implement some `From` traits. This will help when
@@ -207,14 +443,41 @@ impl From<NoNetworkEvents> for NetworkEventList {
     }
 }
 
+impl Extend<NetworkEventList> for NetworkEventList {
+    fn extend<T: IntoIterator<Item = NetworkEventList>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|events| self.0.extend(events.0));
+    }
+}
+
+/// The kind of button an NPC added to an open dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogButtonKind {
+    /// Continues to the next page of the dialog.
+    Next,
+    /// Closes the dialog.
+    Close,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DisconnectReason {
     ClosedByClient,
     ConnectionError,
+    /// The server didn't accept the connection in time.
+    ConnectTimedOut {
+        address: SocketAddr,
+    },
+    /// Parsing the packet with the given header panicked. Only reported if
+    /// [`NetworkingSystem::set_catch_parse_panics`](crate::NetworkingSystem::set_catch_parse_panics)
+    /// is enabled.
+    ParseError {
+        header: PacketHeader,
+    },
 }
 
 pub(crate) trait DisconnectedEvent {
     fn create_event(reason: DisconnectReason) -> NetworkEvent;
+
+    fn is_disconnected_event(event: &NetworkEvent) -> bool;
 }
 
 pub(crate) struct LoginServerDisconnectedEvent;
@@ -225,16 +488,28 @@ impl DisconnectedEvent for LoginServerDisconnectedEvent {
     fn create_event(reason: DisconnectReason) -> NetworkEvent {
         NetworkEvent::LoginServerDisconnected { reason }
     }
+
+    fn is_disconnected_event(event: &NetworkEvent) -> bool {
+        matches!(event, NetworkEvent::LoginServerDisconnected { .. })
+    }
 }
 
 impl DisconnectedEvent for CharacterServerDisconnectedEvent {
     fn create_event(reason: DisconnectReason) -> NetworkEvent {
         NetworkEvent::CharacterServerDisconnected { reason }
     }
+
+    fn is_disconnected_event(event: &NetworkEvent) -> bool {
+        matches!(event, NetworkEvent::CharacterServerDisconnected { .. })
+    }
 }
 
 impl DisconnectedEvent for MapServerDisconnectedEvent {
     fn create_event(reason: DisconnectReason) -> NetworkEvent {
         NetworkEvent::MapServerDisconnected { reason }
     }
+
+    fn is_disconnected_event(event: &NetworkEvent) -> bool {
+        matches!(event, NetworkEvent::MapServerDisconnected { .. })
+    }
 }
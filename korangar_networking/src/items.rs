@@ -1,4 +1,8 @@
-use ragnarok_packets::{EquipPosition, EquippableItemFlags, InventoryIndex, ItemId, ItemOptions, Price, RegularItemFlags};
+use ragnarok_packets::{
+    BuyItemResult, BuyShopItemsResult, EquipPosition, EquippableItemFlags, EquippableItemInformation, InventoryIndex, ItemId,
+    ItemListIndex, ItemOptions, ItemType, LegacyEquippableItemInformation, MailItemInformation, Price, RegularItemFlags, StorageIndex,
+    StorageItemAddedPacket, VendingItemInformation, VendingPurchaseResult,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NoMetadata;
@@ -18,29 +22,254 @@ pub enum InventoryItemDetails {
         option_count: u8,
         option_data: [ItemOptions; 5], // fix count
         refinement_level: u8,
+        /// Called `enchantgrade` in rAthena. Clients older than 2020-07-24
+        /// don't receive it, so it is always 0 for them.
         enchantment_level: u8,
         flags: EquippableItemFlags,
     },
 }
 
 #[derive(Clone, Debug)]
-pub struct InventoryItem<Meta> {
+pub struct InventoryItem<Meta, Index = InventoryIndex> {
     pub metadata: Meta,
-    pub index: InventoryIndex,
+    pub index: Index,
     pub item_id: ItemId,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub slot: [u32; 4], // card ?
     pub hire_expiration_date: u32,
     pub details: InventoryItemDetails,
 }
 
-impl<Meta> InventoryItem<Meta> {
+impl<Meta, Index> InventoryItem<Meta, Index> {
     pub fn is_identifed(&self) -> bool {
         match &self.details {
             InventoryItemDetails::Regular { flags, .. } => flags.contains(RegularItemFlags::IDENTIFIED),
             InventoryItemDetails::Equippable { flags, .. } => flags.contains(EquippableItemFlags::IDENTIFIED),
         }
     }
+
+    /// Replaces the index of the item, failing if the index can't be
+    /// converted.
+    pub fn try_map_index<NewIndex>(self, map: impl FnOnce(Index) -> Option<NewIndex>) -> Option<InventoryItem<Meta, NewIndex>> {
+        let InventoryItem {
+            metadata,
+            index,
+            item_id,
+            item_type,
+            slot,
+            hire_expiration_date,
+            details,
+        } = self;
+
+        Some(InventoryItem {
+            metadata,
+            index: map(index)?,
+            item_id,
+            item_type,
+            slot,
+            hire_expiration_date,
+            details,
+        })
+    }
+}
+
+impl From<EquippableItemInformation> for InventoryItem<NoMetadata, ItemListIndex> {
+    fn from(item: EquippableItemInformation) -> Self {
+        let EquippableItemInformation {
+            index,
+            item_id,
+            item_type,
+            equip_position,
+            equipped_position,
+            slot,
+            hire_expiration_date,
+            bind_on_equip_type,
+            w_item_sprite_number,
+            option_count,
+            option_data,
+            refinement_level,
+            enchantment_level,
+            flags,
+        } = item;
+
+        InventoryItem {
+            index,
+            metadata: NoMetadata,
+            item_id,
+            item_type,
+            slot,
+            hire_expiration_date,
+            details: InventoryItemDetails::Equippable {
+                equip_position,
+                equipped_position,
+                bind_on_equip_type,
+                w_item_sprite_number,
+                option_count,
+                option_data,
+                refinement_level,
+                enchantment_level,
+                flags,
+            },
+        }
+    }
+}
+
+impl From<LegacyEquippableItemInformation> for InventoryItem<NoMetadata, ItemListIndex> {
+    fn from(item: LegacyEquippableItemInformation) -> Self {
+        let LegacyEquippableItemInformation {
+            index,
+            item_id,
+            item_type,
+            equip_position,
+            equipped_position,
+            slot,
+            hire_expiration_date,
+            bind_on_equip_type,
+            w_item_sprite_number,
+            option_count,
+            option_data,
+            refinement_level,
+            flags,
+        } = item;
+
+        InventoryItem {
+            index,
+            metadata: NoMetadata,
+            item_id,
+            item_type,
+            slot,
+            hire_expiration_date,
+            details: InventoryItemDetails::Equippable {
+                equip_position,
+                equipped_position,
+                bind_on_equip_type,
+                w_item_sprite_number,
+                option_count,
+                option_data,
+                refinement_level,
+                enchantment_level: 0,
+                flags,
+            },
+        }
+    }
+}
+
+impl From<StorageItemAddedPacket> for InventoryItem<NoMetadata, StorageIndex> {
+    fn from(packet: StorageItemAddedPacket) -> Self {
+        let StorageItemAddedPacket {
+            index,
+            amount,
+            item_id,
+            item_type,
+            is_identified,
+            is_broken,
+            refinement_level,
+            cards,
+            option_data,
+            grade,
+        } = packet;
+
+        // The packet doesn't contain the equip position, so the item type is used
+        // to tell equippable items apart.
+        let details = match item_type {
+            ItemType::Armor | ItemType::Weapon | ItemType::PetEgg | ItemType::PetArmor | ItemType::ShadowGear => {
+                InventoryItemDetails::Equippable {
+                    equip_position: EquipPosition::empty(),
+                    equipped_position: EquipPosition::empty(),
+                    bind_on_equip_type: 0,
+                    w_item_sprite_number: 0,
+                    option_count: option_data.len() as u8,
+                    option_data,
+                    refinement_level,
+                    enchantment_level: grade,
+                    flags: {
+                        let mut flags = EquippableItemFlags::empty();
+                        flags.set(EquippableItemFlags::IDENTIFIED, is_identified != 0);
+                        flags.set(EquippableItemFlags::IS_BROKEN, is_broken != 0);
+                        flags
+                    },
+                }
+            }
+            _ => InventoryItemDetails::Regular {
+                amount: u16::try_from(amount).unwrap_or(u16::MAX),
+                equipped_position: EquipPosition::empty(),
+                flags: {
+                    let mut flags = RegularItemFlags::empty();
+                    flags.set(RegularItemFlags::IDENTIFIED, is_identified != 0);
+                    flags
+                },
+            },
+        };
+
+        InventoryItem {
+            metadata: NoMetadata,
+            index,
+            item_id,
+            item_type,
+            slot: cards,
+            hire_expiration_date: 0,
+            details,
+        }
+    }
+}
+
+impl From<MailItemInformation> for InventoryItem<NoMetadata> {
+    fn from(item: MailItemInformation) -> Self {
+        let MailItemInformation {
+            amount,
+            item_id,
+            is_identified,
+            is_broken,
+            refinement_level,
+            cards,
+            location,
+            item_type,
+            view_sprite,
+            bind_on_equip_type,
+            option_data,
+            grade,
+        } = item;
+
+        let details = match item_type {
+            ItemType::Armor | ItemType::Weapon | ItemType::PetEgg | ItemType::PetArmor | ItemType::ShadowGear => {
+                InventoryItemDetails::Equippable {
+                    equip_position: EquipPosition::from_bits_truncate(location),
+                    equipped_position: EquipPosition::empty(),
+                    bind_on_equip_type,
+                    w_item_sprite_number: view_sprite,
+                    option_count: option_data.len() as u8,
+                    option_data,
+                    refinement_level,
+                    enchantment_level: grade,
+                    flags: {
+                        let mut flags = EquippableItemFlags::empty();
+                        flags.set(EquippableItemFlags::IDENTIFIED, is_identified != 0);
+                        flags.set(EquippableItemFlags::IS_BROKEN, is_broken != 0);
+                        flags
+                    },
+                }
+            }
+            _ => InventoryItemDetails::Regular {
+                amount,
+                equipped_position: EquipPosition::empty(),
+                flags: {
+                    let mut flags = RegularItemFlags::empty();
+                    flags.set(RegularItemFlags::IDENTIFIED, is_identified != 0);
+                    flags
+                },
+            },
+        };
+
+        InventoryItem {
+            metadata: NoMetadata,
+            index: InventoryIndex(0),
+            item_id,
+            item_type,
+            slot: cards,
+            hire_expiration_date: 0,
+            details,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,13 +291,80 @@ impl From<u32> for ItemQuantity {
 pub struct ShopItem<Meta> {
     pub metadata: Meta,
     pub item_id: ItemId,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub price: Price,
     pub quantity: ItemQuantity,
     pub weight: u16,
     pub location: u32,
 }
 
+/// The result of buying items from a shop or a market, independent of the
+/// packet the server responded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnifiedBuyItemsResult {
+    Success,
+    NotEnoughZeny,
+    WeightLimitExceeded,
+    TooManyItems,
+    SoldOut,
+    /// The purchase failed for another reason. Markets only report whether the
+    /// purchase succeeded, so every failure of a market purchase ends up here.
+    Failed,
+}
+
+impl From<BuyItemResult> for UnifiedBuyItemsResult {
+    fn from(result: BuyItemResult) -> Self {
+        match result {
+            BuyItemResult::Successful => Self::Success,
+            BuyItemResult::NotEnoughZeny => Self::NotEnoughZeny,
+            BuyItemResult::WeightLimitExceeded => Self::WeightLimitExceeded,
+            BuyItemResult::TooManyItems | BuyItemResult::TooManyOfThisItem => Self::TooManyItems,
+            BuyItemResult::ItemSoldOut | BuyItemResult::NotEnoughGoods => Self::SoldOut,
+            BuyItemResult::PropsOpenAir | BuyItemResult::ExchangeFailed | BuyItemResult::ExchangeWellDone | BuyItemResult::Unknown(_) => {
+                Self::Failed
+            }
+        }
+    }
+}
+
+impl From<BuyShopItemsResult> for UnifiedBuyItemsResult {
+    fn from(result: BuyShopItemsResult) -> Self {
+        match result {
+            BuyShopItemsResult::Success => Self::Success,
+            BuyShopItemsResult::Error => Self::Failed,
+        }
+    }
+}
+
+impl From<VendingItemInformation> for ShopItem<InventoryIndex> {
+    fn from(item: VendingItemInformation) -> Self {
+        ShopItem {
+            metadata: item.index,
+            item_id: item.item_id,
+            item_type: item.item_type,
+            price: item.price,
+            quantity: ItemQuantity::Fixed(item.amount as u32),
+            weight: 0,
+            location: item.location,
+        }
+    }
+}
+
+impl From<VendingPurchaseResult> for UnifiedBuyItemsResult {
+    fn from(result: VendingPurchaseResult) -> Self {
+        match result {
+            VendingPurchaseResult::Success => Self::Success,
+            VendingPurchaseResult::NotEnoughZeny => Self::NotEnoughZeny,
+            VendingPurchaseResult::WeightLimitExceeded => Self::WeightLimitExceeded,
+            VendingPurchaseResult::OutOfStock => Self::SoldOut,
+            VendingPurchaseResult::VendorTrading
+            | VendingPurchaseResult::ShopChanged
+            | VendingPurchaseResult::NoSalesInformation
+            | VendingPurchaseResult::Unknown(_) => Self::Failed,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SellItem<Meta> {
     pub metadata: Meta,
@@ -76,3 +372,74 @@ pub struct SellItem<Meta> {
     pub price: Price,
     pub overcharge_price: Price,
 }
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+    use ragnarok_packets::{EquippableItemListPacket, InventoryIndex, ItemId, ItemListIndex, LegacyEquippableItemListPacket, PacketExt};
+
+    use crate::{InventoryItem, InventoryItemDetails, NoMetadata};
+
+    // A single +7 Knife [3] with grade B, laid out like `EQUIPITEM_INFO` in
+    // rAthena, with and without the `enchantgrade` field.
+    const EQUIPPABLE_ITEM_LIST: &[u8] = &[
+        0x39, 0x0B, 0x49, 0x00, 0x00, // header, length and inventory type
+        0x02, 0x00, 0xB1, 0x04, 0x00, 0x00, 0x05, // index, item id and type
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // location and wear state
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // cards
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // hire expire date, bind on equip type and sprite
+        0x00, // option count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, // options
+        0x07, 0x02, 0x01, // refining level, enchant grade and flags
+    ];
+    const LEGACY_EQUIPPABLE_ITEM_LIST: &[u8] = &[
+        0x39, 0x0B, 0x48, 0x00, 0x00, // header, length and inventory type
+        0x02, 0x00, 0xB1, 0x04, 0x00, 0x00, 0x05, // index, item id and type
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // location and wear state
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // cards
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // hire expire date, bind on equip type and sprite
+        0x00, // option count
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, // options
+        0x07, 0x01, // refining level and flags
+    ];
+
+    fn assert_knife(item: InventoryItem<NoMetadata, ItemListIndex>, expected_enchantment_level: u8) {
+        assert_eq!(item.index.inventory_index(), Some(InventoryIndex(0)));
+        assert_eq!(item.item_id, ItemId(1201));
+        assert!(item.is_identifed());
+
+        let InventoryItemDetails::Equippable {
+            refinement_level,
+            enchantment_level,
+            ..
+        } = item.details
+        else {
+            panic!("expected an equippable item");
+        };
+
+        assert_eq!(refinement_level, 7);
+        assert_eq!(enchantment_level, expected_enchantment_level);
+    }
+
+    #[test]
+    fn equippable_item_list() {
+        let mut byte_reader = ByteReader::without_metadata(EQUIPPABLE_ITEM_LIST);
+        let packet = EquippableItemListPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.item_information.len(), 1);
+        assert_knife(packet.item_information[0].clone().into(), 2);
+    }
+
+    #[test]
+    fn legacy_equippable_item_list() {
+        let mut byte_reader = ByteReader::without_metadata(LEGACY_EQUIPPABLE_ITEM_LIST);
+        let packet = LegacyEquippableItemListPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.item_information.len(), 1);
+        assert_knife(packet.item_information[0].clone().into(), 0);
+    }
+}
@@ -5,10 +5,14 @@ mod event;
 mod hotkey;
 mod items;
 mod message;
+mod party;
+mod rate_limit;
 mod server;
+mod skill;
 
 use std::cell::RefCell;
 use std::net::{IpAddr, SocketAddr};
+use std::panic::AssertUnwindSafe;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -27,21 +31,43 @@ use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
-pub use self::entity::EntityData;
-pub use self::event::{DisconnectReason, NetworkEvent};
+pub use self::entity::{EntityData, HealthState};
+pub use self::event::{DialogButtonKind, DisconnectReason, NetworkEvent};
 pub use self::hotkey::HotkeyState;
-pub use self::items::{InventoryItem, InventoryItemDetails, ItemQuantity, NoMetadata, SellItem, ShopItem};
-pub use self::message::MessageColor;
+pub use self::items::{InventoryItem, InventoryItemDetails, ItemQuantity, NoMetadata, SellItem, ShopItem, UnifiedBuyItemsResult};
+pub use self::message::{parse_color_codes, ChatSpan, MessageColor};
+pub use self::party::PartyMember;
+pub use self::rate_limit::RateLimit;
 pub use self::server::{
-    CharacterServerLoginData, LoginServerLoginData, NotConnectedError, UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
+    CharacterServerLoginData, ClientVersion, ConnectionPrefix, EnterGameFailedReason, LoginServerLoginData, NotConnectedError,
+    SendPacketsError, UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
 };
+pub use self::skill::{MapSize, SkillCastError};
+use crate::party::update_party_members;
+use crate::rate_limit::SendQueue;
 use crate::server::NetworkTaskError;
+use crate::skill::{validate_entity_target, validate_ground_target};
+
+/// Time after which connecting to a server is considered to have failed.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of events the event buffers can hold before they need to grow.
+const DEFAULT_EVENT_CAPACITY: usize = 64;
+
+/// Inventory type of the item lists that are sent when opening the storage.
+const STORAGE_INVENTORY_TYPE: u8 = 2;
 
 /// Buffer for networking events. This struct exists to reduce heap allocations
 /// and is purely an optimization.
 pub struct NetworkEventBuffer(Vec<NetworkEvent>);
 
 impl NetworkEventBuffer {
+    /// Creates an event buffer that can hold the given number of events before
+    /// it needs to grow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
     pub fn drain(&mut self) -> std::vec::Drain<'_, NetworkEvent> {
         self.0.drain(..)
     }
@@ -82,12 +108,54 @@ impl TimeSynchronization {
     }
 }
 
+/// How often a delayed logout is requested before giving up.
+const MAXIMUM_LOGOUT_ATTEMPTS: u32 = 3;
+
+/// A logout that was delayed by the map server.
+struct PendingLogout {
+    /// `None` while waiting for the map server to answer a repeated logout
+    /// request.
+    deadline: Option<Instant>,
+    remaining_seconds: u64,
+    attempts: u32,
+}
+
+/// The current step of a login started with [`NetworkingSystem::enter_game`].
+enum EnterGameStep {
+    LoggingIn,
+    ConnectingToCharacterServer {
+        login_data: LoginServerLoginData,
+    },
+    SelectingCharacter {
+        login_data: LoginServerLoginData,
+        selected: Option<(String, CharacterId)>,
+    },
+    ConnectingToMapServer {
+        map_name: String,
+        character_id: CharacterId,
+    },
+}
+
+/// A login started with [`NetworkingSystem::enter_game`].
+struct PendingEnterGame {
+    character_slot: usize,
+    step: EnterGameStep,
+}
+
 pub struct NetworkingSystem<Callback> {
     command_sender: UnboundedSender<ServerConnectCommand>,
     time_synchronization: Arc<Mutex<TimeSynchronization>>,
     login_server_connection: ServerConnection,
     character_server_connection: ServerConnection,
     map_server_connection: ServerConnection,
+    connect_timeout: Duration,
+    catch_parse_panics: bool,
+    rate_limit: Option<RateLimit>,
+    event_capacity: usize,
+    client_version: Option<ClientVersion>,
+    map_server_connection_prefix: ConnectionPrefix,
+    pending_logout: Option<PendingLogout>,
+    pending_enter_game: Option<PendingEnterGame>,
     packet_callback: Callback,
 }
 
@@ -113,9 +181,17 @@ where
             login_server_connection: ServerConnection::Disconnected,
             character_server_connection: ServerConnection::Disconnected,
             map_server_connection: ServerConnection::Disconnected,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            catch_parse_panics: false,
+            rate_limit: None,
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+            client_version: None,
+            map_server_connection_prefix: ConnectionPrefix::None,
+            pending_logout: None,
+            pending_enter_game: None,
             packet_callback,
         };
-        let event_buffer = NetworkEventBuffer(Vec::new());
+        let event_buffer = NetworkEventBuffer::with_capacity(DEFAULT_EVENT_CAPACITY);
 
         (networking_system, event_buffer)
     }
@@ -145,6 +221,10 @@ where
                     match command {
                         ServerConnectCommand::Login {
                             address,
+                            connect_timeout,
+                            catch_parse_panics,
+                            rate_limit,
+                            event_capacity,
                             action_receiver,
                             event_sender,
                         } => {
@@ -154,14 +234,18 @@ where
                             }
 
                             let packet_handler = Self::create_login_server_packet_handler(packet_callback.clone()).unwrap();
-                            let handle = local_set.spawn_local(Self::handle_server_connection(
+                            let handle = local_set.spawn_local(Self::handle_server_connection::<LoginServerDisconnectedEvent, _>(
                                 address,
+                                connect_timeout,
+                                catch_parse_panics,
+                                rate_limit,
+                                event_capacity,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
                                 |_| LoginServerKeepalivePacket::new(),
                                 Duration::from_secs(58),
-                                false,
+                                ConnectionPrefix::None,
                                 thread_time_synchronization.clone(),
                             ));
 
@@ -169,6 +253,11 @@ where
                         }
                         ServerConnectCommand::Character {
                             address,
+                            connect_timeout,
+                            catch_parse_panics,
+                            rate_limit,
+                            event_capacity,
+                            client_version,
                             action_receiver,
                             event_sender,
                         } => {
@@ -177,15 +266,20 @@ where
                                 let _ = handle.await.unwrap();
                             }
 
-                            let packet_handler = Self::create_character_server_packet_handler(packet_callback.clone()).unwrap();
-                            let handle = local_set.spawn_local(Self::handle_server_connection(
+                            let packet_handler =
+                                Self::create_character_server_packet_handler(packet_callback.clone(), client_version).unwrap();
+                            let handle = local_set.spawn_local(Self::handle_server_connection::<CharacterServerDisconnectedEvent, _>(
                                 address,
+                                connect_timeout,
+                                catch_parse_panics,
+                                rate_limit,
+                                event_capacity,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
                                 |_| CharacterServerKeepalivePacket::new(),
                                 Duration::from_secs(10),
-                                true,
+                                ConnectionPrefix::AccountId,
                                 thread_time_synchronization.clone(),
                             ));
 
@@ -193,6 +287,12 @@ where
                         }
                         ServerConnectCommand::Map {
                             address,
+                            connect_timeout,
+                            catch_parse_panics,
+                            rate_limit,
+                            event_capacity,
+                            client_version,
+                            connection_prefix,
                             action_receiver,
                             event_sender,
                         } => {
@@ -201,9 +301,13 @@ where
                                 let _ = handle.await.unwrap();
                             }
 
-                            let packet_handler = Self::create_map_server_packet_handler(packet_callback.clone()).unwrap();
-                            let handle = local_set.spawn_local(Self::handle_server_connection(
+                            let packet_handler = Self::create_map_server_packet_handler(packet_callback.clone(), client_version).unwrap();
+                            let handle = local_set.spawn_local(Self::handle_server_connection::<MapServerDisconnectedEvent, _>(
                                 address,
+                                connect_timeout,
+                                catch_parse_panics,
+                                rate_limit,
+                                event_capacity,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
@@ -215,7 +319,7 @@ where
                                     Err(_) => RequestServerTickPacket::new(ClientTick(100)),
                                 },
                                 Duration::from_secs(10),
-                                false,
+                                connection_prefix,
                                 thread_time_synchronization.clone(),
                             ));
 
@@ -240,7 +344,15 @@ where
             } => loop {
                 match event_receiver.try_recv() {
                     Ok(login_event) => {
+                        // The connection task reports its own disconnect if it gave up on the
+                        // connection, so we don't report a connection error afterwards.
+                        let disconnected = Event::is_disconnected_event(&login_event);
                         event_buffer.0.push(login_event);
+
+                        if disconnected {
+                            *connection = ServerConnection::Disconnected;
+                            break;
+                        }
                     }
                     Err(TryRecvError::Empty) => {
                         *connection = ServerConnection::Connected {
@@ -265,45 +377,273 @@ where
     }
 
     pub fn get_events(&mut self, events: &mut NetworkEventBuffer) {
+        let first_new_event = events.0.len();
+
         Self::handle_connection::<LoginServerDisconnectedEvent>(&mut self.login_server_connection, events);
         Self::handle_connection::<CharacterServerDisconnectedEvent>(&mut self.character_server_connection, events);
         Self::handle_connection::<MapServerDisconnectedEvent>(&mut self.map_server_connection, events);
+
+        self.update_pending_logout(first_new_event, events);
+        self.update_pending_enter_game(first_new_event, events);
+    }
+
+    /// If the map server asked us to wait before logging out, we count down
+    /// and request the logout again once the delay has passed. Only the first
+    /// delay is reported, repeated delays are handled silently until
+    /// [`MAXIMUM_LOGOUT_ATTEMPTS`] is reached.
+    fn update_pending_logout(&mut self, first_new_event: usize, events: &mut NetworkEventBuffer) {
+        let mut index = first_new_event;
+
+        while index < events.0.len() {
+            match &events.0[index] {
+                NetworkEvent::LogoutDelayed { delay } => {
+                    let deadline = Some(Instant::now() + *delay);
+                    let remaining_seconds = delay.as_secs();
+
+                    match self.pending_logout.as_mut() {
+                        Some(pending_logout) if pending_logout.attempts + 1 >= MAXIMUM_LOGOUT_ATTEMPTS => {
+                            self.pending_logout = None;
+                            events.0[index] = NetworkEvent::LogoutFailed;
+                        }
+                        Some(pending_logout) => {
+                            pending_logout.deadline = deadline;
+                            pending_logout.remaining_seconds = remaining_seconds;
+                            pending_logout.attempts += 1;
+                            events.0.remove(index);
+                            continue;
+                        }
+                        None => {
+                            self.pending_logout = Some(PendingLogout {
+                                deadline,
+                                remaining_seconds,
+                                attempts: 0,
+                            });
+                        }
+                    }
+                }
+                NetworkEvent::LoggedOut | NetworkEvent::MapServerDisconnected { .. } => self.pending_logout = None,
+                _ => {}
+            }
+
+            index += 1;
+        }
+
+        let Some(pending_logout) = self.pending_logout.as_mut() else {
+            return;
+        };
+
+        let Some(deadline) = pending_logout.deadline else {
+            return;
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let remaining_seconds = remaining.as_secs_f64().ceil() as u64;
+
+        if remaining_seconds < pending_logout.remaining_seconds {
+            pending_logout.remaining_seconds = remaining_seconds;
+            events.0.push(NetworkEvent::LogoutCountdown { remaining_seconds });
+        }
+
+        if remaining.is_zero() {
+            pending_logout.deadline = None;
+
+            if self.log_out().is_err() {
+                self.pending_logout = None;
+                events.0.push(NetworkEvent::LoggedOut);
+            }
+        }
+    }
+
+    /// Drives a login started with [`NetworkingSystem::enter_game`] forward,
+    /// based on the events received from the servers.
+    fn update_pending_enter_game(&mut self, first_new_event: usize, events: &mut NetworkEventBuffer) {
+        let mut outcome = None;
+
+        for event in &events.0[first_new_event..] {
+            let Some(pending_enter_game) = self.pending_enter_game.take() else {
+                break;
+            };
+            let character_slot = pending_enter_game.character_slot;
+
+            let step = match (pending_enter_game.step, event) {
+                (
+                    EnterGameStep::LoggingIn,
+                    NetworkEvent::LoginServerConnected {
+                        character_servers,
+                        login_data,
+                    },
+                ) => {
+                    let Some(character_server) = character_servers.first() else {
+                        outcome = Some(Err(EnterGameFailedReason::NoCharacterServer));
+                        break;
+                    };
+
+                    self.disconnect_from_login_server();
+                    self.connect_to_character_server(login_data, character_server.clone());
+
+                    EnterGameStep::ConnectingToCharacterServer { login_data: *login_data }
+                }
+                (EnterGameStep::LoggingIn, NetworkEvent::LoginServerConnectionFailed { reason, .. }) => {
+                    outcome = Some(Err(EnterGameFailedReason::Login(*reason)));
+                    break;
+                }
+                (EnterGameStep::LoggingIn, NetworkEvent::LoginServerDisconnected { reason }) => {
+                    outcome = Some(Err(EnterGameFailedReason::Disconnected(*reason)));
+                    break;
+                }
+                (EnterGameStep::ConnectingToCharacterServer { login_data }, NetworkEvent::CharacterServerConnected { .. }) => {
+                    let _ = self.request_character_list();
+
+                    EnterGameStep::SelectingCharacter {
+                        login_data,
+                        selected: None,
+                    }
+                }
+                (
+                    EnterGameStep::ConnectingToCharacterServer { .. } | EnterGameStep::SelectingCharacter { .. },
+                    NetworkEvent::CharacterServerConnectionFailed { reason, .. },
+                ) => {
+                    outcome = Some(Err(EnterGameFailedReason::CharacterServer(reason.clone())));
+                    break;
+                }
+                (
+                    EnterGameStep::ConnectingToCharacterServer { .. } | EnterGameStep::SelectingCharacter { .. },
+                    NetworkEvent::CharacterServerDisconnected { reason },
+                ) => {
+                    outcome = Some(Err(EnterGameFailedReason::Disconnected(*reason)));
+                    break;
+                }
+                // Servers may send the character list more than once, so only the first one
+                // selects the character.
+                (
+                    EnterGameStep::SelectingCharacter {
+                        login_data,
+                        selected: None,
+                    },
+                    NetworkEvent::CharacterList { characters },
+                ) => {
+                    let Some(character) = characters
+                        .iter()
+                        .find(|character| character.character_number as usize == character_slot)
+                    else {
+                        outcome = Some(Err(EnterGameFailedReason::CharacterNotFound));
+                        break;
+                    };
+
+                    let map_name = character.map_name.replace(".gat", "");
+                    let _ = self.select_character(character_slot);
+
+                    EnterGameStep::SelectingCharacter {
+                        login_data,
+                        selected: Some((map_name, character.character_id)),
+                    }
+                }
+                (
+                    EnterGameStep::SelectingCharacter {
+                        login_data,
+                        selected: Some((map_name, character_id)),
+                    },
+                    NetworkEvent::CharacterSelected {
+                        login_data: character_server_login_data,
+                    },
+                ) => {
+                    self.disconnect_from_character_server();
+                    self.connect_to_map_server(&login_data, *character_server_login_data);
+
+                    EnterGameStep::ConnectingToMapServer { map_name, character_id }
+                }
+                (EnterGameStep::SelectingCharacter { .. }, NetworkEvent::CharacterSelectionFailed { reason, .. }) => {
+                    outcome = Some(Err(EnterGameFailedReason::CharacterSelection(*reason)));
+                    break;
+                }
+                (EnterGameStep::ConnectingToMapServer { map_name, character_id }, NetworkEvent::MapLoginSuccess { position, .. }) => {
+                    outcome = Some(Ok(NetworkEvent::EnteredGame {
+                        map_name,
+                        position: *position,
+                        character_id,
+                    }));
+                    break;
+                }
+                (EnterGameStep::ConnectingToMapServer { .. }, NetworkEvent::MapServerDisconnected { reason }) => {
+                    outcome = Some(Err(EnterGameFailedReason::Disconnected(*reason)));
+                    break;
+                }
+                (step, _) => step,
+            };
+
+            self.pending_enter_game = Some(PendingEnterGame { character_slot, step });
+        }
+
+        match outcome {
+            Some(Ok(event)) => events.0.push(event),
+            Some(Err(reason)) => events.0.push(NetworkEvent::EnterGameFailed { reason }),
+            None => {}
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
-    async fn handle_server_connection<PingPacket>(
+    async fn handle_server_connection<Event, PingPacket>(
         address: SocketAddr,
-        mut action_receiver: UnboundedReceiver<Vec<u8>>,
+        connect_timeout: Duration,
+        catch_parse_panics: bool,
+        rate_limit: Option<RateLimit>,
+        event_capacity: usize,
+        mut action_receiver: UnboundedReceiver<Vec<Vec<u8>>>,
         event_sender: UnboundedSender<NetworkEvent>,
         mut packet_handler: PacketHandler<NetworkEventList, (), Callback>,
         ping_factory: impl Fn(&Mutex<TimeSynchronization>) -> PingPacket,
         ping_frequency: Duration,
-        // After logging in to the character server, it sends the account id without any packet.
-        // Since our packet handler has no way of working with this, we need to add some special
-        // logic.
-        mut read_account_id: bool,
+        // Some servers send data without any packet header right after logging in. Since our
+        // packet handler has no way of working with this, we need to add some special logic.
+        mut connection_prefix: ConnectionPrefix,
         time_synchronization: Arc<Mutex<TimeSynchronization>>,
     ) -> Result<(), NetworkTaskError>
     where
+        Event: DisconnectedEvent,
         PingPacket: Packet + ClientPacket,
         Callback: PacketCallback,
     {
-        let mut stream = TcpStream::connect(address).await.map_err(|_| NetworkTaskError::FailedToConnect)?;
+        event_sender
+            .send(NetworkEvent::Connecting { address })
+            .map_err(|_| NetworkTaskError::ConnectionClosed)?;
+
+        let mut stream = match tokio::time::timeout(connect_timeout, TcpStream::connect(address)).await {
+            Ok(result) => result.map_err(|_| NetworkTaskError::FailedToConnect)?,
+            Err(_) => {
+                let _ = event_sender.send(Event::create_event(DisconnectReason::ConnectTimedOut { address }));
+                return Err(NetworkTaskError::ConnectTimedOut);
+            }
+        };
         let mut interval = tokio::time::interval(ping_frequency);
+        let mut send_queue = SendQueue::new(rate_limit, Instant::now());
         let mut buffer = [0u8; 8192];
         let mut cut_off_buffer_base = 0;
-        let mut events = Vec::new();
+        // Reused for every read, so steady traffic doesn't allocate.
+        let mut events = Vec::with_capacity(event_capacity);
 
         loop {
+            let next_send_time = send_queue.next_send_time();
+            let next_delayed_packet = next_send_time.map_or_else(tokio::time::Instant::now, tokio::time::Instant::from_std);
+
             tokio::select! {
-                // Send a packet to the server.
+                // Send packets to the server.
                 action = action_receiver.recv() => {
-                    let Some(action) = action else {
+                    let Some(packets) = action else {
                         // Channel was closed by the main thread.
                         break Ok(());
                     };
 
-                    stream.write_all(&action).await.map_err(|_| NetworkTaskError::ConnectionClosed)?;
+                    let bytes = send_queue.push(packets, Instant::now());
+
+                    if !bytes.is_empty() {
+                        stream.write_all(&bytes).await.map_err(|_| NetworkTaskError::ConnectionClosed)?;
+                    }
+                }
+                // Send a packet that was delayed by the rate limit.
+                _ = tokio::time::sleep_until(next_delayed_packet), if next_send_time.is_some() => {
+                    if let Some(packet) = send_queue.pop_delayed() {
+                        stream.write_all(&packet).await.map_err(|_| NetworkTaskError::ConnectionClosed)?;
+                    }
                 }
                 // Receive some packets from the server.
                 received_bytes = stream.read(&mut buffer[cut_off_buffer_base..]) => {
@@ -321,14 +661,29 @@ where
                     let data = &buffer[..cut_off_buffer_base + received_bytes];
                     let mut byte_reader = ByteReader::without_metadata(data);
 
-                    if read_account_id {
+                    if connection_prefix == ConnectionPrefix::AccountId {
                         let account_id = AccountId::from_bytes(&mut byte_reader).unwrap();
                         events.push(NetworkEvent::AccountId(account_id));
-                        read_account_id = false;
+                        connection_prefix = ConnectionPrefix::None;
                     }
 
+                    let mut panicked_header = None;
+
                     while !byte_reader.is_empty() {
-                        match packet_handler.process_one(&mut byte_reader) {
+                        let packet_start = byte_reader.create_save_point();
+                        let result = match catch_parse_panics {
+                            true => std::panic::catch_unwind(AssertUnwindSafe(|| packet_handler.process_one(&mut byte_reader))),
+                            false => Ok(packet_handler.process_one(&mut byte_reader)),
+                        };
+
+                        let Ok(result) = result else {
+                            // Read the header again to report which packet failed to parse.
+                            byte_reader.restore_save_point(packet_start);
+                            panicked_header = Some(PacketHeader::from_bytes(&mut byte_reader).unwrap_or(PacketHeader(0)));
+                            break;
+                        };
+
+                        match result {
                             HandlerResult::Ok(packet_events) => events.extend(packet_events.0.into_iter()),
                             HandlerResult::PacketCutOff => {
                                 let packet_start = byte_reader.get_offset();
@@ -366,6 +721,11 @@ where
 
                         event_sender.send(event).map_err(|_| NetworkTaskError::ConnectionClosed)?;
                     }
+
+                    if let Some(header) = panicked_header {
+                        let _ = event_sender.send(Event::create_event(DisconnectReason::ParseError { header }));
+                        break Err(NetworkTaskError::ParsePanicked);
+                    }
                 }
                 // Send a keep-alive packet to the server.
                 _ = interval.tick() => {
@@ -376,6 +736,76 @@ where
         }
     }
 
+    /// Sets the time after which connecting to a server is aborted. Only
+    /// affects connections that are established after the change.
+    pub fn set_connect_timeout(&mut self, connect_timeout: Duration) {
+        self.connect_timeout = connect_timeout;
+    }
+
+    /// If enabled, a panic while parsing a packet disconnects from the server
+    /// with [`DisconnectReason::ParseError`] instead of taking down the
+    /// networking thread. Disabled by default, so that panics stay visible
+    /// during development. Only affects connections that are established
+    /// after the change.
+    pub fn set_catch_parse_panics(&mut self, catch_parse_panics: bool) {
+        self.catch_parse_panics = catch_parse_panics;
+    }
+
+    /// Limits how fast packets are sent to each server. Packets exceeding the
+    /// limit are delayed, not dropped. Keepalive packets are not limited.
+    /// `None` disables the limit, which is the default. Only affects
+    /// connections that are established after the change.
+    pub fn set_rate_limit(&mut self, rate_limit: Option<RateLimit>) {
+        self.rate_limit = rate_limit;
+    }
+
+    /// Sets how many events the buffer of each connection can hold before it
+    /// needs to grow. Defaults to 64. Only affects connections that are
+    /// established after the change.
+    pub fn set_event_capacity(&mut self, event_capacity: usize) {
+        self.event_capacity = event_capacity;
+    }
+
+    /// Sets the packet version of the client for all connections. This
+    /// configures the [`ConnectionPrefix`] of the map server and picks the
+    /// layout of packets that changed between versions without changing their
+    /// header. If no version is set, the newest layouts are used. Only affects
+    /// connections that are established after the change.
+    pub fn set_client_version(&mut self, client_version: ClientVersion) {
+        self.client_version = Some(client_version);
+        self.map_server_connection_prefix = client_version.map_server_connection_prefix();
+    }
+
+    /// Returns the packet version set with
+    /// [`set_client_version`](Self::set_client_version).
+    pub fn client_version(&self) -> Option<ClientVersion> {
+        self.client_version
+    }
+
+    /// Sets the data the map server sends right after logging in. Only
+    /// affects connections that are established after the change.
+    pub fn set_map_server_connection_prefix(&mut self, connection_prefix: ConnectionPrefix) {
+        self.map_server_connection_prefix = connection_prefix;
+    }
+
+    /// Walks through the whole login, from the login server over the
+    /// character selection to the map server, using the first character
+    /// server and the character in the given slot. Emits
+    /// [`NetworkEvent::EnteredGame`] once the player is on the map server or
+    /// [`NetworkEvent::EnterGameFailed`] if any step fails.
+    ///
+    /// All other events are still emitted as usual, but they shouldn't be
+    /// used to drive the login at the same time. Once the map is loaded,
+    /// [`NetworkingSystem::map_loaded`] still needs to be called.
+    pub fn enter_game(&mut self, address: SocketAddr, username: impl Into<String>, password: impl Into<String>, character_slot: usize) {
+        self.pending_enter_game = Some(PendingEnterGame {
+            character_slot,
+            step: EnterGameStep::LoggingIn,
+        });
+
+        self.connect_to_login_server(address, username, password);
+    }
+
     pub fn connect_to_login_server(&mut self, address: SocketAddr, username: impl Into<String>, password: impl Into<String>) {
         if !matches!(self.login_server_connection, ServerConnection::Disconnected) {
             return;
@@ -387,6 +817,10 @@ where
         self.command_sender
             .send(ServerConnectCommand::Login {
                 address,
+                connect_timeout: self.connect_timeout,
+                catch_parse_panics: self.catch_parse_panics,
+                rate_limit: self.rate_limit,
+                event_capacity: self.event_capacity,
                 action_receiver,
                 event_sender,
             })
@@ -397,7 +831,7 @@ where
         self.packet_callback.outgoing_packet(&login_packet);
 
         action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
+            .send(vec![login_packet.packet_to_bytes().unwrap()])
             .expect("action receiver instantly dropped");
 
         self.login_server_connection = ServerConnection::Connected {
@@ -419,6 +853,11 @@ where
         self.command_sender
             .send(ServerConnectCommand::Character {
                 address,
+                connect_timeout: self.connect_timeout,
+                catch_parse_panics: self.catch_parse_panics,
+                rate_limit: self.rate_limit,
+                event_capacity: self.event_capacity,
+                client_version: self.client_version,
                 action_receiver,
                 event_sender,
             })
@@ -434,7 +873,7 @@ where
         self.packet_callback.outgoing_packet(&login_packet);
 
         action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
+            .send(vec![login_packet.packet_to_bytes().unwrap()])
             .expect("action receiver instantly dropped");
 
         self.character_server_connection = ServerConnection::Connected {
@@ -460,6 +899,12 @@ where
         self.command_sender
             .send(ServerConnectCommand::Map {
                 address,
+                connect_timeout: self.connect_timeout,
+                catch_parse_panics: self.catch_parse_panics,
+                rate_limit: self.rate_limit,
+                event_capacity: self.event_capacity,
+                client_version: self.client_version,
+                connection_prefix: self.map_server_connection_prefix,
                 action_receiver,
                 event_sender,
             })
@@ -478,7 +923,7 @@ where
         self.packet_callback.outgoing_packet(&login_packet);
 
         action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
+            .send(vec![login_packet.packet_to_bytes().unwrap()])
             .expect("action receiver instantly dropped");
 
         self.map_server_connection = ServerConnection::Connected {
@@ -505,7 +950,9 @@ where
                 self.packet_callback.outgoing_packet(packet);
 
                 // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                action_sender
+                    .send(vec![packet.packet_to_bytes().unwrap()])
+                    .map_err(|_| NotConnectedError)
             }
             _ => Err(NotConnectedError),
         }
@@ -517,7 +964,9 @@ where
                 self.packet_callback.outgoing_packet(packet);
 
                 // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                action_sender
+                    .send(vec![packet.packet_to_bytes().unwrap()])
+                    .map_err(|_| NotConnectedError)
             }
             _ => Err(NotConnectedError),
         }
@@ -529,12 +978,45 @@ where
                 self.packet_callback.outgoing_packet(packet);
 
                 // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                action_sender
+                    .send(vec![packet.packet_to_bytes().unwrap()])
+                    .map_err(|_| NotConnectedError)
             }
             _ => Err(NotConnectedError),
         }
     }
 
+    /// Sends multiple packets to the map server in a single write. If the
+    /// connection has a rate limit, every packet is still limited on its own.
+    pub fn send_map_server_packets<'a, P>(&mut self, packets: impl IntoIterator<Item = &'a P>) -> Result<(), SendPacketsError>
+    where
+        P: MapServerPacket + 'a,
+    {
+        match &mut self.map_server_connection {
+            ServerConnection::Connected { action_sender, .. } => {
+                let packets: Vec<&P> = packets.into_iter().collect();
+
+                // Convert all packets first, so we never send only part of them.
+                let packet_bytes = packets
+                    .iter()
+                    .map(|packet| packet.packet_to_bytes())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(SendPacketsError::ConversionFailed)?;
+
+                if packet_bytes.is_empty() {
+                    return Ok(());
+                }
+
+                for packet in packets {
+                    self.packet_callback.outgoing_packet(packet);
+                }
+
+                action_sender.send(packet_bytes).map_err(|_| SendPacketsError::NotConnected)
+            }
+            _ => Err(SendPacketsError::NotConnected),
+        }
+    }
+
     fn create_login_server_packet_handler(
         packet_callback: Callback,
     ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
@@ -585,6 +1067,7 @@ where
 
     fn create_character_server_packet_handler(
         packet_callback: Callback,
+        client_version: Option<ClientVersion>,
     ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
         let mut packet_handler = PacketHandler::<NetworkEventList, (), Callback>::with_callback(packet_callback);
 
@@ -606,10 +1089,30 @@ where
         packet_handler.register(|packet: RequestCharacterListSuccessPacket| NetworkEvent::CharacterList {
             characters: packet.character_information,
         })?;
-        packet_handler.register_noop::<CharacterListPacket>()?;
+        // Both layouts of the character list share the same header, so the right one
+        // has to be picked based on the client version. Clients that request
+        // the characters page by page get them from the pages only, so they
+        // receive a single character list.
+        match client_version {
+            Some(client_version) if !client_version.requests_character_list_pages() => {
+                match client_version.sends_character_slot_information() {
+                    true => packet_handler.register(|packet: CharacterListPacket| NetworkEvent::CharacterList {
+                        characters: packet.character_information,
+                    })?,
+                    false => packet_handler.register(|packet: LegacyCharacterListPacket| NetworkEvent::CharacterList {
+                        characters: packet.character_information,
+                    })?,
+                }
+            }
+            _ => packet_handler.register_noop::<CharacterListPacket>()?,
+        }
         packet_handler.register_noop::<CharacterSlotPagePacket>()?;
         packet_handler.register_noop::<CharacterBanListPacket>()?;
-        packet_handler.register_noop::<LoginPincodePacket>()?;
+        packet_handler.register(|packet: LoginPincodePacket| NetworkEvent::Pincode {
+            pincode_seed: packet.pincode_seed,
+            account_id: packet.account_id,
+            state: packet.state,
+        })?;
         packet_handler.register_noop::<Packet0b18>()?;
         packet_handler.register(|packet: CharacterSelectionSuccessPacket| {
             let login_data = CharacterServerLoginData {
@@ -670,6 +1173,7 @@ where
 
     fn create_map_server_packet_handler(
         packet_callback: Callback,
+        client_version: Option<ClientVersion>,
     ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
         let mut packet_handler = PacketHandler::<NetworkEventList, (), Callback>::with_callback(packet_callback);
 
@@ -680,13 +1184,14 @@ where
         //
         // This variable provides some transient storage shared by all the inventory
         // handlers.
-        let inventory_items: Rc<RefCell<Option<Vec<InventoryItem<NoMetadata>>>>> = Rc::new(RefCell::new(None));
+        let inventory_items = Rc::new(RefCell::new(None::<Vec<InventoryItem<NoMetadata, ItemListIndex>>>));
+
+        // The server sends the party member list, health and positions in separate
+        // packets, so they are combined here to always emit the full list.
+        let party_members: Rc<RefCell<Vec<PartyMember>>> = Rc::new(RefCell::new(Vec::new()));
 
         packet_handler.register(|_: MapServerPingPacket| NoNetworkEvents)?;
-        packet_handler.register(|packet: BroadcastMessagePacket| NetworkEvent::ChatMessage {
-            text: packet.message,
-            color: MessageColor::Broadcast,
-        })?;
+        packet_handler.register(|packet: BroadcastMessagePacket| NetworkEvent::chat_message(packet.message, MessageColor::Broadcast))?;
         packet_handler.register(|packet: Broadcast2MessagePacket| {
             // Drop the alpha channel because it might be 0.
             let color = MessageColor::Rgb {
@@ -694,22 +1199,13 @@ where
                 green: packet.font_color.green,
                 blue: packet.font_color.blue,
             };
-            NetworkEvent::ChatMessage {
-                text: packet.message,
-                color,
-            }
+            NetworkEvent::chat_message(packet.message, color)
         })?;
         packet_handler.register(|packet: OverheadMessagePacket| {
             // FIX: This should be a different event.
-            NetworkEvent::ChatMessage {
-                text: packet.message,
-                color: MessageColor::Broadcast,
-            }
-        })?;
-        packet_handler.register(|packet: ServerMessagePacket| NetworkEvent::ChatMessage {
-            text: packet.message,
-            color: MessageColor::Server,
+            NetworkEvent::chat_message(packet.message, MessageColor::Broadcast)
         })?;
+        packet_handler.register(|packet: ServerMessagePacket| NetworkEvent::chat_message(packet.message, MessageColor::Server))?;
         packet_handler.register(|packet: EntityMessagePacket| {
             // Drop the alpha channel because it might be 0.
             let color = MessageColor::Rgb {
@@ -717,10 +1213,7 @@ where
                 green: packet.color.green,
                 blue: packet.color.blue,
             };
-            NetworkEvent::ChatMessage {
-                text: packet.message,
-                color,
-            }
+            NetworkEvent::chat_message(packet.message, color)
         })?;
         packet_handler.register_noop::<DisplayEmotionPacket>()?;
         packet_handler.register(|packet: EntityMovePacket| {
@@ -733,7 +1226,7 @@ where
             NetworkEvent::PlayerMove(origin, destination, packet.timestamp)
         })?;
         packet_handler.register(|packet: ChangeMapPacket| NetworkEvent::ChangeMap(packet.map_name.replace(".gat", ""), packet.position))?;
-        packet_handler.register(|packet: ResurrectionPacket| NetworkEvent::ResurrectPlayer {
+        packet_handler.register(|packet: ResurrectionPacket| NetworkEvent::EntityResurrected {
             entity_id: packet.entity_id,
         })?;
         packet_handler.register(|packet: EntityAppearedPacket| NetworkEvent::AddEntity(packet.into()))?;
@@ -753,15 +1246,20 @@ where
         packet_handler.register_noop::<AchievementListPacket>()?;
         packet_handler.register_noop::<CriticalWeightUpdatePacket>()?;
         packet_handler.register(|packet: SpriteChangePacket| match packet.sprite_type {
-            SpriteChangeType::Base => Some(NetworkEvent::ChangeJob {
+            SpriteChangeType::Base => NetworkEvent::ChangeJob {
                 account_id: packet.account_id,
                 job_id: packet.value,
-            }),
-            SpriteChangeType::Hair => Some(NetworkEvent::ChangeHair {
+            },
+            SpriteChangeType::Hair => NetworkEvent::ChangeHair {
                 account_id: packet.account_id,
                 hair_id: packet.value,
-            }),
-            _ => None,
+            },
+            sprite_type => NetworkEvent::SpriteChanged {
+                account_id: packet.account_id,
+                sprite_type,
+                value: packet.value,
+                value2: packet.value2,
+            },
         })?;
         packet_handler.register({
             let inventory_items = inventory_items.clone();
@@ -806,63 +1304,78 @@ where
                 NoNetworkEvents
             }
         })?;
+        // Both layouts of the equippable item list share the same header, so the
+        // right one has to be picked based on the client version.
+        match client_version.is_none_or(ClientVersion::sends_equippable_item_enchantment_level) {
+            true => packet_handler.register({
+                let inventory_items = inventory_items.clone();
+
+                move |packet: EquippableItemListPacket| {
+                    // Item lists that are not part of an inventory are ignored.
+                    if let Some(items) = inventory_items.borrow_mut().as_mut() {
+                        items.extend(packet.item_information.into_iter().map(InventoryItem::from));
+                    }
+                    NoNetworkEvents
+                }
+            })?,
+            false => packet_handler.register({
+                let inventory_items = inventory_items.clone();
+
+                move |packet: LegacyEquippableItemListPacket| {
+                    // Item lists that are not part of an inventory are ignored.
+                    if let Some(items) = inventory_items.borrow_mut().as_mut() {
+                        items.extend(packet.item_information.into_iter().map(InventoryItem::from));
+                    }
+                    NoNetworkEvents
+                }
+            })?,
+        }
         packet_handler.register({
             let inventory_items = inventory_items.clone();
 
-            move |packet: EquippableItemListPacket| {
-                inventory_items.borrow_mut().as_mut().expect("Unexpected inventory packet").extend(
-                    packet.item_information.into_iter().map(|item| {
-                        let EquippableItemInformation {
-                            index,
-                            item_id,
-                            item_type,
-                            equip_position,
-                            equipped_position,
-                            slot,
-                            hire_expiration_date,
-                            bind_on_equip_type,
-                            w_item_sprite_number,
-                            option_count,
-                            option_data,
-                            refinement_level,
-                            enchantment_level,
-                            flags,
-                        } = item;
+            move |packet: InventoyEndPacket| {
+                let items = inventory_items.borrow_mut().take().expect("Unexpected inventory end packet");
 
-                        InventoryItem {
-                            index,
-                            metadata: NoMetadata,
-                            item_id,
-                            item_type,
-                            slot,
-                            hire_expiration_date,
-                            details: InventoryItemDetails::Equippable {
-                                equip_position,
-                                equipped_position,
-                                bind_on_equip_type,
-                                w_item_sprite_number,
-                                option_count,
-                                option_data,
-                                refinement_level,
-                                enchantment_level,
-                                flags,
-                            },
-                        }
-                    }),
-                );
-                NoNetworkEvents
+                // Storage items share the item lists with the inventory, but their index is
+                // only offset by 1 instead of 2.
+                let event = match packet.inventory_type {
+                    STORAGE_INVENTORY_TYPE => items
+                        .into_iter()
+                        .map(|item| item.try_map_index(ItemListIndex::storage_index))
+                        .collect::<Option<_>>()
+                        .map(|items| NetworkEvent::StorageOpened { items }),
+                    _ => items
+                        .into_iter()
+                        .map(|item| item.try_map_index(ItemListIndex::inventory_index))
+                        .collect::<Option<_>>()
+                        .map(|items| NetworkEvent::SetInventory { items }),
+                };
+
+                event.unwrap_or_else(|| {
+                    NetworkEvent::chat_message("Received an item list with an invalid index".to_string(), MessageColor::Error)
+                })
             }
         })?;
-        packet_handler.register({
-            let inventory_items = inventory_items.clone();
-
-            move |_: InventoyEndPacket| {
-                let items = inventory_items.borrow_mut().take().expect("Unexpected inventory end packet");
-                NetworkEvent::SetInventory { items }
-            }
+        packet_handler.register(|packet: EquippableSwitchItemListPacket| NetworkEvent::EquipSwitchUpdated(packet.item_information))?;
+        packet_handler.register(|packet: RequestEquipSwitchItemStatusPacket| {
+            (packet.failed == 0).then_some(NetworkEvent::EquipSwitchItemAdded {
+                index: packet.inventory_index,
+                position: packet.equip_position,
+            })
+        })?;
+        packet_handler.register(|packet: RequestUnequipSwitchItemStatusPacket| {
+            (packet.failed == 0).then_some(NetworkEvent::EquipSwitchItemRemoved {
+                index: packet.inventory_index,
+            })
+        })?;
+        packet_handler.register(|packet: RequestFullEquipSwitchStatusPacket| match packet.failed {
+            0 => NetworkEvent::FullEquipSwitchCompleted,
+            _ => NetworkEvent::FullEquipSwitchFailed,
+        })?;
+        packet_handler.register(|packet: MapTypePacket| NetworkEvent::MapType {
+            map_type: packet.map_type,
+            flags: packet.flags,
         })?;
-        packet_handler.register_noop::<EquippableSwitchItemListPacket>()?;
-        packet_handler.register_noop::<MapTypePacket>()?;
         packet_handler.register(|packet: UpdateSkillTreePacket| NetworkEvent::SkillTree(packet.skill_information))?;
         packet_handler.register(|packet: UpdateHotkeysPacket| NetworkEvent::SetHotkeyData {
             tab: packet.tab,
@@ -875,14 +1388,35 @@ where
                 })
                 .collect(),
         })?;
-        packet_handler.register_noop::<InitialStatusPacket>()?;
+        packet_handler.register(NetworkEvent::InitialStatus)?;
         packet_handler.register_noop::<UpdatePartyInvitationStatePacket>()?;
         packet_handler.register_noop::<UpdateShowEquipPacket>()?;
-        packet_handler.register_noop::<UpdateConfigurationPacket>()?;
-        packet_handler.register_noop::<NavigateToMonsterPacket>()?;
-        packet_handler.register_noop::<MarkMinimapPositionPacket>()?;
-        packet_handler.register(|_: NextButtonPacket| NetworkEvent::AddNextButton)?;
-        packet_handler.register(|_: CloseButtonPacket| NetworkEvent::AddCloseButton)?;
+        packet_handler.register(|packet: UpdateConfigurationPacket| NetworkEvent::ConfigurationChanged {
+            option: packet.config_type,
+            enabled: packet.value != 0,
+        })?;
+        packet_handler.register(|packet: NavigateToMonsterPacket| NetworkEvent::Navigate {
+            target_type: packet.target_type,
+            map_name: packet.map_name,
+            position: packet.target_position,
+            monster_id: packet.target_monster_id,
+            flags: packet.flags,
+        })?;
+        packet_handler.register(|packet: MarkMinimapPositionPacket| NetworkEvent::MinimapMarker {
+            npc_id: packet.npc_id,
+            id: packet.id,
+            marker_type: packet.marker_type,
+            position: packet.position,
+            color: packet.color,
+        })?;
+        packet_handler.register(|packet: NextButtonPacket| NetworkEvent::NpcDialogButton {
+            npc_id: packet.entity_id,
+            kind: DialogButtonKind::Next,
+        })?;
+        packet_handler.register(|packet: CloseButtonPacket| NetworkEvent::NpcDialogButton {
+            npc_id: packet.entity_id,
+            kind: DialogButtonKind::Close,
+        })?;
         packet_handler.register(|packet: DialogMenuPacket| {
             let choices = packet
                 .message
@@ -893,8 +1427,14 @@ where
 
             NetworkEvent::AddChoiceButtons(choices)
         })?;
-        packet_handler.register_noop::<DisplaySpecialEffectPacket>()?;
-        packet_handler.register_noop::<DisplaySkillCooldownPacket>()?;
+        packet_handler.register(|packet: DisplaySpecialEffectPacket| NetworkEvent::SpecialEffect {
+            entity_id: packet.entity_id,
+            effect_id: packet.effect_id,
+        })?;
+        packet_handler.register(|packet: DisplaySkillCooldownPacket| NetworkEvent::SkillCooldown {
+            skill_id: packet.skill_id,
+            delay: Duration::from_millis(packet.delay.into()),
+        })?;
         packet_handler.register_noop::<DisplaySkillEffectAndDamagePacket>()?;
         packet_handler.register(|packet: DisplaySkillEffectNoDamagePacket| {
             NetworkEvent::HealEffect(packet.destination_entity_id, packet.heal_amount as usize)
@@ -905,7 +1445,19 @@ where
         packet_handler.register_noop::<HuntingQuestNotificationPacket>()?;
         packet_handler.register_noop::<HuntingQuestUpdateObjectivePacket>()?;
         packet_handler.register_noop::<QuestRemovedPacket>()?;
-        packet_handler.register_noop::<QuestListPacket>()?;
+        // All layouts of the quest list share the same header, so the right one has to
+        // be picked based on the client version.
+        match client_version.map_or(QuestObjectiveLayout::Extended, ClientVersion::quest_objective_layout) {
+            QuestObjectiveLayout::Extended => packet_handler.register(|packet: QuestListPacket| NetworkEvent::QuestList {
+                quests: packet.quest_list.quests,
+            })?,
+            QuestObjectiveLayout::Hunting => packet_handler.register(|packet: HuntingQuestListPacket| NetworkEvent::QuestList {
+                quests: packet.quest_list.0.quests,
+            })?,
+            QuestObjectiveLayout::Legacy => packet_handler.register(|packet: LegacyQuestListPacket| NetworkEvent::QuestList {
+                quests: packet.quest_list.0.quests,
+            })?,
+        }
         packet_handler.register(|packet: VisualEffectPacket| {
             let path = match packet.effect {
                 VisualEffect::BaseLevelUp => "angel.str",
@@ -922,8 +1474,15 @@ where
 
             NetworkEvent::VisualEffect(path, packet.entity_id)
         })?;
-        packet_handler.register_noop::<DisplayGainedExperiencePacket>()?;
-        packet_handler.register_noop::<DisplayImagePacket>()?;
+        packet_handler.register(|packet: DisplayGainedExperiencePacket| NetworkEvent::ExperienceGained {
+            amount: packet.amount,
+            experience_type: packet.experience_type,
+            source: packet.experience_source,
+        })?;
+        packet_handler.register(|packet: DisplayImagePacket| NetworkEvent::DisplayImage {
+            image_name: packet.image_name,
+            location: packet.location,
+        })?;
         packet_handler.register_noop::<StateChangePacket>()?;
 
         packet_handler.register(|packet: QuestEffectPacket| match packet.effect {
@@ -1003,6 +1562,18 @@ where
             index: packet.index,
             amount: packet.amount,
         })?;
+        packet_handler.register(|packet: StorageItemCountPacket| NetworkEvent::StorageItemCount {
+            item_count: packet.item_count,
+            maximum_item_count: packet.maximum_item_count,
+        })?;
+        packet_handler.register(|packet: StorageItemAddedPacket| NetworkEvent::StorageItemAdded {
+            item: InventoryItem::from(packet),
+        })?;
+        packet_handler.register(|packet: StorageItemRemovedPacket| NetworkEvent::StorageItemRemoved {
+            index: packet.index,
+            amount: packet.amount,
+        })?;
+        packet_handler.register(|_: StorageClosedPacket| NetworkEvent::StorageClosed)?;
         packet_handler.register(|packet: ServerTickPacket| NetworkEvent::UpdateClientTick {
             client_tick: packet.client_tick,
             received_at: Instant::now(),
@@ -1012,33 +1583,31 @@ where
         })?;
         packet_handler
             .register(|packet: RequestEntityDetailsSuccessPacket| NetworkEvent::UpdateEntityDetails(packet.entity_id, packet.name))?;
-        packet_handler.register(|packet: UpdateEntityHealthPointsPacket| {
-            NetworkEvent::UpdateEntityHealth(
-                packet.entity_id,
-                packet.health_points as usize,
-                packet.maximum_health_points as usize,
-            )
+        packet_handler.register(|packet: UpdateEntityHealthPointsPacket| NetworkEvent::EntityHealth {
+            entity_id: packet.entity_id,
+            health_points: packet.health_points as usize,
+            maximum_health_points: packet.maximum_health_points as usize,
         })?;
         packet_handler.register_noop::<RequestPlayerAttackFailedPacket>()?;
         packet_handler.register(|packet: DamagePacket1| match packet.damage_type {
-            DamageType::Damage => Some(NetworkEvent::DamageEffect {
+            DamageType::StandUp => Some(NetworkEvent::PlayerStandUp {
                 entity_id: packet.destination_entity_id,
-                damage_amount: packet.damage_amount as usize,
             }),
-            DamageType::StandUp => Some(NetworkEvent::PlayerStandUp {
+            _ => packet.combat_result().map(|combat_result| NetworkEvent::DamageEffect {
                 entity_id: packet.destination_entity_id,
+                damage_amount: packet.damage_amount as usize,
+                combat_result,
             }),
-            _ => None,
         })?;
         packet_handler.register(|packet: DamagePacket3| match packet.damage_type {
-            DamageType::Damage => Some(NetworkEvent::DamageEffect {
+            DamageType::StandUp => Some(NetworkEvent::PlayerStandUp {
                 entity_id: packet.destination_entity_id,
-                damage_amount: packet.damage_amount as usize,
             }),
-            DamageType::StandUp => Some(NetworkEvent::PlayerStandUp {
+            _ => packet.combat_result().map(|combat_result| NetworkEvent::DamageEffect {
                 entity_id: packet.destination_entity_id,
+                damage_amount: packet.damage_amount as usize,
+                combat_result,
             }),
-            _ => None,
         })?;
         packet_handler.register(|packet: NpcDialogPacket| NetworkEvent::OpenDialog(packet.text, packet.npc_id))?;
         packet_handler.register(|packet: RequestEquipItemStatusPacket| match packet.result {
@@ -1057,26 +1626,37 @@ where
         })?;
         packet_handler.register_noop::<Packet8302>()?;
         packet_handler.register_noop::<Packet0b18>()?;
-        packet_handler.register(|packet: MapServerLoginSuccessPacket| NetworkEvent::UpdateClientTick {
-            client_tick: packet.client_tick,
-            received_at: Instant::now(),
+        packet_handler.register(|packet: MapServerLoginSuccessPacket| {
+            vec![
+                NetworkEvent::UpdateClientTick {
+                    client_tick: packet.client_tick,
+                    received_at: Instant::now(),
+                },
+                NetworkEvent::MapLoginSuccess {
+                    position: packet.position,
+                    client_tick: packet.client_tick,
+                    font: packet.font,
+                },
+            ]
         })?;
         packet_handler.register(|packet: RestartResponsePacket| match packet.result {
             RestartResponseStatus::Ok => NetworkEvent::LoggedOut,
-            RestartResponseStatus::Nothing => NetworkEvent::ChatMessage {
-                text: "Failed to log out.".to_string(),
-                color: MessageColor::Error,
-            },
+            RestartResponseStatus::Nothing => NetworkEvent::chat_message("Failed to log out.".to_string(), MessageColor::Error),
         })?;
         packet_handler.register(|packet: DisconnectResponsePacket| match packet.result {
             DisconnectResponseStatus::Ok => NetworkEvent::LoggedOut,
-            DisconnectResponseStatus::Wait10Seconds => NetworkEvent::ChatMessage {
-                text: "Please wait 10 seconds before trying to log out.".to_string(),
-                color: MessageColor::Error,
+            DisconnectResponseStatus::Wait10Seconds => NetworkEvent::LogoutDelayed {
+                delay: Duration::from_secs(10),
             },
         })?;
         packet_handler.register_noop::<UseSkillSuccessPacket>()?;
-        packet_handler.register_noop::<ToUseSkillSuccessPacket>()?;
+        packet_handler.register(|packet: ToUseSkillSuccessPacket| NetworkEvent::SkillUseResult {
+            skill_id: packet.skill_id,
+            result: match packet.flag {
+                0 => Err(packet.cause),
+                _ => Ok(()),
+            },
+        })?;
         packet_handler
             .register(|packet: NotifySkillUnitPacket| NetworkEvent::AddSkillUnit(packet.entity_id, packet.unit_id, packet.position))?;
         packet_handler.register(|packet: SkillUnitDisappearPacket| NetworkEvent::RemoveSkillUnit(packet.entity_id))?;
@@ -1094,10 +1674,7 @@ where
                 FriendRequestResult::OtherFriendListFull => format!("{}'s Friend List is full.", packet.friend.name),
             };
 
-            let mut events = vec![NetworkEvent::ChatMessage {
-                text,
-                color: MessageColor::Information,
-            }];
+            let mut events = vec![NetworkEvent::chat_message(text, MessageColor::Information)];
 
             if matches!(packet.result, FriendRequestResult::Accepted) {
                 events.push(NetworkEvent::FriendAdded { friend: packet.friend });
@@ -1110,12 +1687,83 @@ where
             character_id: packet.character_id,
         })?;
         packet_handler.register_noop::<PartyInvitePacket>()?;
+        packet_handler.register({
+            let party_members = party_members.clone();
+
+            move |packet: PartyInformationPacket| {
+                let mut party_members = party_members.borrow_mut();
+                update_party_members(&mut party_members, packet.members);
+
+                NetworkEvent::PartyUpdate {
+                    members: party_members.clone(),
+                }
+            }
+        })?;
+        packet_handler.register({
+            let party_members = party_members.clone();
+
+            move |packet: PartyMemberLeftPacket| {
+                let mut party_members = party_members.borrow_mut();
+                party_members.retain(|member| member.account_id != packet.account_id);
+
+                NetworkEvent::PartyUpdate {
+                    members: party_members.clone(),
+                }
+            }
+        })?;
+        packet_handler.register({
+            let party_members = party_members.clone();
+
+            move |packet: PartyMemberHealthPacket| {
+                let mut party_members = party_members.borrow_mut();
+
+                let member = party_members.iter_mut().find(|member| member.account_id == packet.account_id)?;
+
+                member.health_points = Some(packet.health_points);
+                member.maximum_health_points = Some(packet.maximum_health_points);
+
+                Some(NetworkEvent::PartyUpdate {
+                    members: party_members.clone(),
+                })
+            }
+        })?;
+        packet_handler.register({
+            let party_members = party_members.clone();
+
+            move |packet: PartyMemberPositionPacket| {
+                let mut party_members = party_members.borrow_mut();
+
+                let member = party_members.iter_mut().find(|member| member.account_id == packet.account_id)?;
+
+                member.position = Some(packet.position);
+
+                Some(NetworkEvent::PartyUpdate {
+                    members: party_members.clone(),
+                })
+            }
+        })?;
         packet_handler.register_noop::<StatusChangeSequencePacket>()?;
         packet_handler.register_noop::<ReputationPacket>()?;
         packet_handler.register_noop::<ClanInfoPacket>()?;
         packet_handler.register_noop::<ClanOnlineCountPacket>()?;
         packet_handler.register_noop::<ChangeMapCellPacket>()?;
-        packet_handler.register_noop::<OpenMarketPacket>()?;
+        packet_handler.register(|packet: OpenMarketPacket| {
+            let items = packet
+                .items
+                .into_iter()
+                .map(|item| ShopItem {
+                    metadata: NoMetadata,
+                    item_id: ItemId(item.name_id),
+                    item_type: item.item_type,
+                    price: item.price,
+                    quantity: items::ItemQuantity::from(item.quantity),
+                    weight: item.weight,
+                    location: item.location,
+                })
+                .collect();
+
+            NetworkEvent::OpenShop { items }
+        })?;
         packet_handler.register(|packet: BuyOrSellPacket| NetworkEvent::AskBuyOrSell { shop_id: packet.shop_id })?;
         packet_handler.register(|packet: ShopItemListPacket| {
             let items = packet
@@ -1134,10 +1782,54 @@ where
 
             NetworkEvent::OpenShop { items }
         })?;
-        packet_handler.register(|packet: BuyShopItemsResultPacket| NetworkEvent::BuyingCompleted { result: packet.result })?;
+        packet_handler.register(|packet: BuyItemsResultPacket| NetworkEvent::BuyingCompleted {
+            result: packet.result.into(),
+        })?;
+        packet_handler.register(|packet: BuyShopItemsResultPacket| NetworkEvent::BuyingCompleted {
+            result: packet.result.into(),
+        })?;
         packet_handler.register_noop::<ParameterChangePacket>()?;
         packet_handler.register(|packet: SellListPacket| NetworkEvent::SellItemList { items: packet.items })?;
         packet_handler.register(|packet: SellItemsResultPacket| NetworkEvent::SellingCompleted { result: packet.result })?;
+        packet_handler.register(|packet: VendingShopOpenedPacket| NetworkEvent::VendingShopOpened {
+            owner: packet.account_id,
+            title: packet.title,
+        })?;
+        packet_handler.register(|packet: VendingShopClosedPacket| NetworkEvent::VendingShopClosed { owner: packet.account_id })?;
+        packet_handler.register(|packet: VendingItemListPacket| NetworkEvent::VendingOpened {
+            owner: packet.account_id,
+            vending_id: packet.vending_id,
+            items: packet.items.into_iter().map(ShopItem::from).collect(),
+        })?;
+        packet_handler.register(|packet: VendingPurchaseResultPacket| NetworkEvent::VendingPurchaseCompleted {
+            index: packet.index,
+            amount: packet.amount,
+            result: packet.result.into(),
+        })?;
+        packet_handler.register(|packet: MailListPacket| NetworkEvent::MailInbox {
+            messages: packet.mails,
+            is_last_page: packet.is_end != 0,
+        })?;
+        packet_handler.register(|packet: MailContentPacket| NetworkEvent::MailOpened {
+            mail_id: packet.mail_id,
+            text: packet.text,
+            zeny: packet.zeny,
+            items: packet
+                .items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| InventoryItem {
+                    index: InventoryIndex(index as u16),
+                    ..InventoryItem::from(item)
+                })
+                .collect(),
+        })?;
+        packet_handler.register(|packet: SendMailResultPacket| NetworkEvent::MailSent { result: packet.result })?;
+        packet_handler.register(|packet: GuildEmblemPacket| NetworkEvent::GuildEmblem {
+            guild_id: packet.guild_id,
+            emblem_id: packet.emblem_id,
+            image_bytes: packet.emblem_data,
+        })?;
 
         Ok(packet_handler)
     }
@@ -1150,6 +1842,12 @@ where
         self.send_character_server_packet(&SelectCharacterPacket::new(character_slot as u8))
     }
 
+    /// Encrypts the pincode with the seed of the last [`NetworkEvent::Pincode`]
+    /// and sends it to the character server.
+    pub fn submit_pincode(&mut self, account_id: AccountId, pincode_seed: u32, pincode: &str) -> Result<(), NotConnectedError> {
+        self.send_character_server_packet(&PincodeSubmitPacket::new(account_id, encrypt_pincode(pincode_seed, pincode)))
+    }
+
     pub fn map_loaded(&mut self) -> Result<(), NotConnectedError> {
         self.send_map_server_packet(&MapLoadedPacket::default())
     }
@@ -1171,8 +1869,15 @@ where
         self.send_map_server_packet(&RestartPacket::new(RestartType::Disconnect))
     }
 
-    pub fn player_move(&mut self, position: WorldPosition) -> Result<(), NotConnectedError> {
-        self.send_map_server_packet(&RequestPlayerMovePacket::new(position))
+    /// Stops requesting a delayed logout again. Returns `false` if no logout
+    /// was pending. A logout request that was already sent can still be
+    /// accepted by the map server.
+    pub fn cancel_log_out(&mut self) -> bool {
+        self.pending_logout.take().is_some()
+    }
+
+    pub fn player_move(&mut self, position: TilePosition) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&RequestPlayerMovePacket::new(WorldPosition::from_tile(position)))
     }
 
     pub fn warp_to_map(&mut self, map_name: String, position: TilePosition) -> Result<(), NotConnectedError> {
@@ -1217,26 +1922,53 @@ where
         self.send_map_server_packet(&RequestUnequipItemPacket::new(item_index))
     }
 
-    pub fn cast_skill(&mut self, skill_id: SkillId, skill_level: SkillLevel, entity_id: EntityId) -> Result<(), NotConnectedError> {
-        self.send_map_server_packet(&UseSkillAtIdPacket::new(skill_level, skill_id, entity_id))
+    pub fn request_equip_switch_item(
+        &mut self,
+        item_index: InventoryIndex,
+        equip_position: EquipPosition,
+    ) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&RequestEquipSwitchItemPacket::new(item_index, equip_position))
     }
 
-    pub fn cast_ground_skill(
+    pub fn request_unequip_switch_item(
         &mut self,
-        skill_id: SkillId,
-        skill_level: SkillLevel,
-        target_position: TilePosition,
+        item_index: InventoryIndex,
+        equip_position: EquipPosition,
     ) -> Result<(), NotConnectedError> {
-        self.send_map_server_packet(&UseSkillOnGroundPacket::new(skill_level, skill_id, target_position))
+        self.send_map_server_packet(&RequestUnequipSwitchItemPacket::new(item_index, equip_position))
     }
 
-    pub fn cast_channeling_skill(
+    pub fn request_full_equip_switch(&mut self) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&RequestFullEquipSwitchPacket::new())
+    }
+
+    /// Casts a skill on an entity. Nothing is sent if the entity id is 0.
+    pub fn cast_skill(&mut self, skill_id: SkillId, skill_level: SkillLevel, entity_id: EntityId) -> Result<(), SkillCastError> {
+        validate_entity_target(entity_id)?;
+        self.send_map_server_packet(&UseSkillAtIdPacket::new(skill_level, skill_id, entity_id))?;
+        Ok(())
+    }
+
+    /// Casts a skill on a tile. Nothing is sent if the tile is outside of the
+    /// map.
+    pub fn cast_ground_skill(
         &mut self,
         skill_id: SkillId,
         skill_level: SkillLevel,
-        entity_id: EntityId,
-    ) -> Result<(), NotConnectedError> {
-        self.send_map_server_packet(&StartUseSkillPacket::new(skill_id, skill_level, entity_id))
+        target_position: TilePosition,
+        map_size: MapSize,
+    ) -> Result<(), SkillCastError> {
+        validate_ground_target(target_position, map_size)?;
+        self.send_map_server_packet(&UseSkillOnGroundPacket::new(skill_level, skill_id, target_position))?;
+        Ok(())
+    }
+
+    /// Starts channeling a skill on an entity. Nothing is sent if the entity
+    /// id is 0.
+    pub fn cast_channeling_skill(&mut self, skill_id: SkillId, skill_level: SkillLevel, entity_id: EntityId) -> Result<(), SkillCastError> {
+        validate_entity_target(entity_id)?;
+        self.send_map_server_packet(&StartUseSkillPacket::new(skill_id, skill_level, entity_id))?;
+        Ok(())
     }
 
     pub fn stop_channeling_skill(&mut self, skill_id: SkillId) -> Result<(), NotConnectedError> {
@@ -1292,6 +2024,31 @@ where
         self.send_map_server_packet(&SetHotkeyData2Packet::new(tab, index, hotkey_data))
     }
 
+    /// Sets the data of multiple hotkeys in the same tab with a single write.
+    pub fn set_multiple_hotkey_data(
+        &mut self,
+        tab: HotbarTab,
+        hotkeys: impl IntoIterator<Item = (HotbarSlot, HotkeyData)>,
+    ) -> Result<(), SendPacketsError> {
+        let packets: Vec<SetHotkeyData2Packet> = hotkeys
+            .into_iter()
+            .map(|(index, hotkey_data)| SetHotkeyData2Packet::new(tab, index, hotkey_data))
+            .collect();
+
+        self.send_map_server_packets(&packets)
+    }
+
+    /// Sets the data of all hotkeys of a tab with a single write, for example
+    /// to restore the hotbar after logging in.
+    pub fn set_hotbar_data(&mut self, tab: HotbarTab, hotkeys: &[HotkeyData; 38]) -> Result<(), SendPacketsError> {
+        let hotkeys = hotkeys
+            .iter()
+            .enumerate()
+            .map(|(index, hotkey_data)| (HotbarSlot(index as u16), hotkey_data.clone()));
+
+        self.set_multiple_hotkey_data(tab, hotkeys)
+    }
+
     pub fn select_buy_or_sell(&mut self, shop_id: ShopId, buy_or_sell: BuyOrSellOption) -> Result<(), NotConnectedError> {
         self.send_map_server_packet(&SelectBuyOrSellPacket::new(shop_id, buy_or_sell))
     }
@@ -1315,29 +2072,191 @@ where
     pub fn sell_items(&mut self, items: Vec<SoldItemInformation>) -> Result<(), NotConnectedError> {
         self.send_map_server_packet(&SellItemsPacket { items })
     }
+
+    /// Moves an item from the inventory into the open storage. The server
+    /// responds with a [`NetworkEvent::StorageItemAdded`].
+    pub fn move_item_to_storage(&mut self, item_index: InventoryIndex, amount: u32) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&MoveItemToStoragePacket::new(item_index, amount))
+    }
+
+    /// Moves an item from the open storage into the inventory. The index is
+    /// the index of the item inside the storage, as received with
+    /// [`NetworkEvent::StorageOpened`]. The server responds with a
+    /// [`NetworkEvent::StorageItemRemoved`].
+    pub fn move_item_from_storage(&mut self, item_index: StorageIndex, amount: u32) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&MoveItemFromStoragePacket::new(item_index, amount))
+    }
+
+    pub fn close_storage(&mut self) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&CloseStoragePacket::new())
+    }
+
+    /// Requests the items of a vending shop. The server responds with a
+    /// [`NetworkEvent::VendingOpened`].
+    pub fn request_vending_items(&mut self, owner: AccountId) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&RequestVendingItemListPacket::new(owner))
+    }
+
+    /// Buys items from a vending shop. The metadata of the items is their
+    /// index in the shop and the amount to buy, as received with
+    /// [`NetworkEvent::VendingOpened`].
+    pub fn purchase_vending_items(
+        &mut self,
+        owner: AccountId,
+        vending_id: u32,
+        items: Vec<ShopItem<(InventoryIndex, u16)>>,
+    ) -> Result<(), NotConnectedError> {
+        let items = items
+            .into_iter()
+            .map(|item| VendingPurchaseInformation {
+                amount: item.metadata.1,
+                index: item.metadata.0,
+            })
+            .collect();
+
+        self.send_map_server_packet(&PurchaseVendingItemsPacket::new(owner, vending_id, items))
+    }
+
+    /// Opens the mailbox. The server responds with one or more
+    /// [`NetworkEvent::MailInbox`].
+    pub fn open_mailbox(&mut self) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&OpenMailboxPacket::new(MailboxType::Unset, MailId(0)))
+    }
+
+    pub fn close_mailbox(&mut self) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&CloseMailboxPacket::new())
+    }
+
+    /// Requests the content of a mail. The server responds with a
+    /// [`NetworkEvent::MailOpened`].
+    pub fn read_mail(&mut self, mailbox_type: MailboxType, mail_id: MailId) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&ReadMailPacket::new(mailbox_type, mail_id))
+    }
+
+    /// Sends a mail with the given amount of zeny attached. The server
+    /// responds with a [`NetworkEvent::MailSent`].
+    pub fn send_mail(
+        &mut self,
+        receiver_name: String,
+        receiver_id: CharacterId,
+        sender_name: String,
+        title: String,
+        text: String,
+        zeny: u64,
+    ) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&SendMailPacket::new(receiver_name, sender_name, zeny, receiver_id, title, text))
+    }
+
+    /// Request the emblem of a guild. The server responds with a
+    /// [`NetworkEvent::GuildEmblem`].
+    pub fn request_guild_emblem(&mut self, guild_id: GuildId) -> Result<(), NotConnectedError> {
+        self.send_map_server_packet(&RequestGuildEmblemPacket::new(guild_id))
+    }
+}
+
+#[cfg(test)]
+mod enter_game {
+    use std::sync::{Arc, Mutex};
+
+    use ragnarok_bytes::{ByteReader, FixedByteSize, FromBytes};
+    use ragnarok_packets::handler::NoPacketCallback;
+    use ragnarok_packets::{AccountId, CharacterId, CharacterInformation, Sex};
+
+    use crate::{EnterGameStep, LoginServerLoginData, NetworkEvent, NetworkingSystem, PendingEnterGame, TimeSynchronization};
+
+    fn character(slot: u8, character_id: u32) -> CharacterInformation {
+        let bytes = vec![0; CharacterInformation::size_in_bytes()];
+        let mut character = CharacterInformation::from_bytes(&mut ByteReader::without_metadata(&bytes)).unwrap();
+
+        character.character_number = slot;
+        character.character_id = CharacterId(character_id);
+        character.map_name = "prontera.gat".to_owned();
+        character
+    }
+
+    #[test]
+    fn character_is_selected_once() {
+        let (command_sender, _command_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let time_synchronization = Arc::new(Mutex::new(TimeSynchronization::new()));
+        let (mut networking_system, mut events) = NetworkingSystem::inner_new(command_sender, time_synchronization, NoPacketCallback);
+
+        networking_system.pending_enter_game = Some(PendingEnterGame {
+            character_slot: 1,
+            step: EnterGameStep::SelectingCharacter {
+                login_data: LoginServerLoginData {
+                    account_id: AccountId(2000000),
+                    login_id1: 0,
+                    login_id2: 0,
+                    sex: Sex::Male,
+                },
+                selected: None,
+            },
+        });
+
+        events.0.push(NetworkEvent::CharacterList {
+            characters: vec![character(0, 150000), character(1, 150001)],
+        });
+        events.0.push(NetworkEvent::CharacterList { characters: Vec::new() });
+        networking_system.update_pending_enter_game(0, &mut events);
+
+        assert_eq!(events.0.len(), 2);
+
+        let Some(PendingEnterGame {
+            step:
+                EnterGameStep::SelectingCharacter {
+                    selected: Some((map_name, character_id)),
+                    ..
+                },
+            ..
+        }) = networking_system.pending_enter_game
+        else {
+            panic!("expected the character to be selected");
+        };
+
+        assert_eq!(map_name, "prontera");
+        assert_eq!(character_id, CharacterId(150001));
+    }
 }
 
 #[cfg(test)]
 mod packet_handlers {
     use ragnarok_packets::handler::NoPacketCallback;
 
-    use crate::NetworkingSystem;
+    use crate::{ClientVersion, NetworkingSystem};
+
+    /// No version, plus every version at which the registered packets change
+    /// and the version right before it. Registering a header twice panics with
+    /// the names of both packets.
+    fn client_versions() -> impl Iterator<Item = Option<ClientVersion>> {
+        let versions = ClientVersion::CHANGES
+            .into_iter()
+            .flat_map(|version| [ClientVersion(version.0 - 1), version]);
+
+        std::iter::once(None).chain(versions.map(Some))
+    }
 
     #[test]
     fn login_server() {
-        let result = NetworkingSystem::create_login_server_packet_handler(NoPacketCallback);
-        assert!(result.is_ok());
+        if let Err(error) = NetworkingSystem::create_login_server_packet_handler(NoPacketCallback) {
+            panic!("{error}");
+        }
     }
 
     #[test]
     fn character_server() {
-        let result = NetworkingSystem::create_character_server_packet_handler(NoPacketCallback);
-        assert!(result.is_ok());
+        for client_version in client_versions() {
+            if let Err(error) = NetworkingSystem::create_character_server_packet_handler(NoPacketCallback, client_version) {
+                panic!("{error}");
+            }
+        }
     }
 
     #[test]
     fn map_server() {
-        let result = NetworkingSystem::create_map_server_packet_handler(NoPacketCallback);
-        assert!(result.is_ok());
+        for client_version in client_versions() {
+            if let Err(error) = NetworkingSystem::create_map_server_packet_handler(NoPacketCallback, client_version) {
+                panic!("{error}");
+            }
+        }
     }
 }
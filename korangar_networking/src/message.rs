@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Copy)]
+use ragnarok_packets::{split_color_codes, ColorRGBA};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MessageColor {
     Rgb { red: u8, green: u8, blue: u8 },
     Broadcast,
@@ -6,3 +8,90 @@ pub enum MessageColor {
     Error,
     Information,
 }
+
+/// A part of a chat message that is displayed in a single color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatSpan {
+    pub text: String,
+    pub color: MessageColor,
+}
+
+/// Splits a chat message into [`ChatSpan`]s at the color codes embedded in the
+/// text, see [`split_color_codes`]. Text without a color code, or after a
+/// `^000000`, uses the default color of the message.
+pub fn parse_color_codes(text: &str, default_color: MessageColor) -> Vec<ChatSpan> {
+    let mut spans = Vec::new();
+
+    split_color_codes(text, None, |text, color| {
+        let color = match color {
+            Some(ColorRGBA { red, green, blue, .. }) => MessageColor::Rgb { red, green, blue },
+            None => default_color,
+        };
+
+        spans.push(ChatSpan {
+            text: text.to_owned(),
+            color,
+        });
+    });
+
+    spans
+}
+
+#[cfg(test)]
+mod color_codes {
+    use super::{parse_color_codes, ChatSpan, MessageColor};
+
+    const RED: MessageColor = MessageColor::Rgb {
+        red: 255,
+        green: 0,
+        blue: 0,
+    };
+
+    fn span(text: &str, color: MessageColor) -> ChatSpan {
+        ChatSpan {
+            text: text.to_owned(),
+            color,
+        }
+    }
+
+    #[test]
+    fn without_codes() {
+        assert_eq!(parse_color_codes("Hello", MessageColor::Server), vec![span(
+            "Hello",
+            MessageColor::Server
+        )]);
+        assert!(parse_color_codes("", MessageColor::Server).is_empty());
+    }
+
+    #[test]
+    fn color_change_and_reset() {
+        assert_eq!(
+            parse_color_codes("Hello ^FF0000Red ^000000Default", MessageColor::Server),
+            vec![
+                span("Hello ", MessageColor::Server),
+                span("Red ", RED),
+                span("Default", MessageColor::Server),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_codes_are_literal() {
+        assert_eq!(parse_color_codes("^FFInvalid ^FF00 ^ff0000Valid^", MessageColor::Server), vec![
+            span("^FFInvalid ^FF00 ", MessageColor::Server),
+            span("Valid^", RED),
+        ]);
+        assert_eq!(parse_color_codes("Text^FF00", MessageColor::Server), vec![span(
+            "Text^FF00",
+            MessageColor::Server
+        )]);
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        assert_eq!(parse_color_codes("^FF00ééé^FF0000é", MessageColor::Server), vec![
+            span("^FF00ééé", MessageColor::Server),
+            span("é", RED),
+        ]);
+    }
+}
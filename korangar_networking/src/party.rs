@@ -0,0 +1,114 @@
+use ragnarok_packets::{AccountId, CharacterId, PartyMemberInformation, TilePosition};
+
+/// A member of the party that the player is in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartyMember {
+    pub account_id: AccountId,
+    pub character_id: CharacterId,
+    pub name: String,
+    pub map_name: String,
+    pub is_leader: bool,
+    pub is_online: bool,
+    pub job: u16,
+    pub base_level: u16,
+    /// Only known once the server sent a position update for the member.
+    pub position: Option<TilePosition>,
+    /// Only known once the server sent a health update for the member.
+    pub health_points: Option<u32>,
+    pub maximum_health_points: Option<u32>,
+}
+
+impl From<PartyMemberInformation> for PartyMember {
+    fn from(member: PartyMemberInformation) -> Self {
+        Self {
+            is_leader: member.is_leader(),
+            is_online: member.is_online(),
+            account_id: member.account_id,
+            character_id: member.character_id,
+            name: member.name,
+            map_name: member.map_name,
+            job: member.job,
+            base_level: member.base_level,
+            position: None,
+            health_points: None,
+            maximum_health_points: None,
+        }
+    }
+}
+
+/// Replaces the party members with a new member list. The position and health
+/// of members that were already known are carried over, since the server only
+/// sends those in separate packets.
+pub(crate) fn update_party_members(members: &mut Vec<PartyMember>, member_information: Vec<PartyMemberInformation>) {
+    let previous_members = std::mem::take(members);
+
+    members.extend(member_information.into_iter().map(|information| {
+        let mut member = PartyMember::from(information);
+
+        if let Some(previous) = previous_members.iter().find(|previous| previous.account_id == member.account_id) {
+            member.position = previous.position;
+            member.health_points = previous.health_points;
+            member.maximum_health_points = previous.maximum_health_points;
+        }
+
+        member
+    }));
+}
+
+#[cfg(test)]
+mod update {
+    use ragnarok_packets::{AccountId, CharacterId, PartyMemberInformation, TilePosition};
+
+    use crate::party::{update_party_members, PartyMember};
+
+    fn information(account_id: u32, offline: u8) -> PartyMemberInformation {
+        PartyMemberInformation {
+            account_id: AccountId(account_id),
+            character_id: CharacterId(account_id + 100000),
+            name: format!("member {account_id}"),
+            map_name: "prontera.gat".to_owned(),
+            role: 1,
+            offline,
+            job: 0,
+            base_level: 1,
+        }
+    }
+
+    #[test]
+    fn new_members_have_no_position_or_health() {
+        let mut members = Vec::new();
+        update_party_members(&mut members, vec![information(1, 0)]);
+
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].position, None);
+        assert_eq!(members[0].health_points, None);
+    }
+
+    #[test]
+    fn known_position_and_health_are_kept() {
+        let mut members = vec![PartyMember {
+            position: Some(TilePosition { x: 10, y: 20 }),
+            health_points: Some(50),
+            maximum_health_points: Some(100),
+            ..PartyMember::from(information(1, 0))
+        }];
+
+        update_party_members(&mut members, vec![information(2, 0), information(1, 1)]);
+
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].position, None);
+        assert_eq!(members[1].position, Some(TilePosition { x: 10, y: 20 }));
+        assert_eq!(members[1].health_points, Some(50));
+        assert!(!members[1].is_online);
+    }
+
+    #[test]
+    fn removed_members_are_dropped() {
+        let mut members = vec![PartyMember::from(information(1, 0)), PartyMember::from(information(2, 0))];
+
+        update_party_members(&mut members, vec![information(2, 0)]);
+
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].account_id, AccountId(2));
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Limits how many packets are sent to a server. Some servers disconnect
+/// clients that send too many packets in a short time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Number of packets that can be sent per second on average.
+    pub packets_per_second: f32,
+    /// Number of packets that can be sent at once before the limit kicks in.
+    pub burst: u32,
+}
+
+/// Token bucket that enforces a [`RateLimit`]. Packets that exceed the limit
+/// are delayed rather than dropped.
+pub(crate) struct TokenBucket {
+    rate_limit: RateLimit,
+    /// Can become negative if packets were scheduled ahead of time.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate_limit: RateLimit, now: Instant) -> Self {
+        Self {
+            rate_limit,
+            tokens: rate_limit.burst.max(1) as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Takes a token for sending a packet and returns how long to wait before
+    /// the packet may be sent.
+    pub fn acquire(&mut self, now: Instant) -> Duration {
+        let packets_per_second = f64::from(self.rate_limit.packets_per_second);
+
+        if packets_per_second <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        let maximum_tokens = self.rate_limit.burst.max(1) as f64;
+
+        self.tokens = (self.tokens + elapsed * packets_per_second).min(maximum_tokens);
+        self.last_refill = now;
+        self.tokens -= 1.0;
+
+        match self.tokens < 0.0 {
+            true => Duration::from_secs_f64(-self.tokens / packets_per_second),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+/// Outgoing packets of a connection. Without a rate limit all packets of an
+/// action are written at once. With a rate limit every packet takes its own
+/// token, so sending a batch of packets can't bypass the limit.
+pub(crate) struct SendQueue {
+    token_bucket: Option<TokenBucket>,
+    /// Packets that were delayed by the rate limit, in the order they have to
+    /// be sent.
+    delayed_packets: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl SendQueue {
+    pub fn new(rate_limit: Option<RateLimit>, now: Instant) -> Self {
+        Self {
+            token_bucket: rate_limit.map(|rate_limit| TokenBucket::new(rate_limit, now)),
+            delayed_packets: VecDeque::new(),
+        }
+    }
+
+    /// Adds the packets of an action to the queue and returns the bytes that
+    /// can be written right away.
+    pub fn push(&mut self, packets: Vec<Vec<u8>>, now: Instant) -> Vec<u8> {
+        let Some(token_bucket) = self.token_bucket.as_mut() else {
+            return packets.concat();
+        };
+
+        let mut bytes = Vec::new();
+
+        for packet in packets {
+            let delay = token_bucket.acquire(now);
+
+            // Packets are never sent before packets that were delayed earlier.
+            if delay.is_zero() && self.delayed_packets.is_empty() {
+                bytes.extend(packet);
+            } else {
+                self.delayed_packets.push_back((now + delay, packet));
+            }
+        }
+
+        bytes
+    }
+
+    /// The time at which the next delayed packet may be sent.
+    pub fn next_send_time(&self) -> Option<Instant> {
+        self.delayed_packets.front().map(|(send_at, _)| *send_at)
+    }
+
+    /// Removes the next delayed packet from the queue.
+    pub fn pop_delayed(&mut self) -> Option<Vec<u8>> {
+        self.delayed_packets.pop_front().map(|(_, packet)| packet)
+    }
+}
+
+#[cfg(test)]
+mod token_bucket {
+    use std::time::{Duration, Instant};
+
+    use crate::rate_limit::{RateLimit, TokenBucket};
+
+    const RATE_LIMIT: RateLimit = RateLimit {
+        packets_per_second: 10.0,
+        burst: 3,
+    };
+
+    #[test]
+    fn burst_is_not_delayed() {
+        let now = Instant::now();
+        let mut token_bucket = TokenBucket::new(RATE_LIMIT, now);
+
+        for _ in 0..3 {
+            assert_eq!(token_bucket.acquire(now), Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn excess_packets_are_delayed() {
+        let now = Instant::now();
+        let mut token_bucket = TokenBucket::new(RATE_LIMIT, now);
+
+        for _ in 0..3 {
+            token_bucket.acquire(now);
+        }
+
+        assert_eq!(token_bucket.acquire(now), Duration::from_millis(100));
+        assert_eq!(token_bucket.acquire(now), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn tokens_refill_up_to_burst() {
+        let now = Instant::now();
+        let mut token_bucket = TokenBucket::new(RATE_LIMIT, now);
+
+        for _ in 0..3 {
+            token_bucket.acquire(now);
+        }
+
+        let later = now + Duration::from_secs(10);
+
+        for _ in 0..3 {
+            assert_eq!(token_bucket.acquire(later), Duration::ZERO);
+        }
+
+        assert_eq!(token_bucket.acquire(later), Duration::from_millis(100));
+    }
+}
+
+#[cfg(test)]
+mod send_queue {
+    use std::time::{Duration, Instant};
+
+    use crate::rate_limit::{RateLimit, SendQueue};
+
+    const RATE_LIMIT: RateLimit = RateLimit {
+        packets_per_second: 10.0,
+        burst: 3,
+    };
+
+    fn packets(count: u8) -> Vec<Vec<u8>> {
+        (0..count).map(|index| vec![index; 2]).collect()
+    }
+
+    #[test]
+    fn batch_without_rate_limit_is_written_at_once() {
+        let now = Instant::now();
+        let mut send_queue = SendQueue::new(None, now);
+
+        assert_eq!(send_queue.push(packets(5), now), [0, 0, 1, 1, 2, 2, 3, 3, 4, 4]);
+        assert_eq!(send_queue.next_send_time(), None);
+    }
+
+    #[test]
+    fn batch_takes_a_token_per_packet() {
+        let now = Instant::now();
+        let mut send_queue = SendQueue::new(Some(RATE_LIMIT), now);
+
+        assert_eq!(send_queue.push(packets(5), now), [0, 0, 1, 1, 2, 2]);
+
+        assert_eq!(send_queue.next_send_time(), Some(now + Duration::from_millis(100)));
+        assert_eq!(send_queue.pop_delayed(), Some(vec![3, 3]));
+        assert_eq!(send_queue.next_send_time(), Some(now + Duration::from_millis(200)));
+        assert_eq!(send_queue.pop_delayed(), Some(vec![4, 4]));
+        assert_eq!(send_queue.next_send_time(), None);
+    }
+
+    #[test]
+    fn packets_are_not_sent_before_delayed_packets() {
+        let now = Instant::now();
+        let mut send_queue = SendQueue::new(Some(RATE_LIMIT), now);
+
+        send_queue.push(packets(4), now);
+
+        // Enough time passed to refill a token, but the delayed packet still has to
+        // be sent first.
+        let later = now + Duration::from_millis(200);
+
+        assert!(send_queue.push(vec![vec![9]], later).is_empty());
+        assert_eq!(send_queue.pop_delayed(), Some(vec![3, 3]));
+        assert_eq!(send_queue.pop_delayed(), Some(vec![9]));
+    }
+}
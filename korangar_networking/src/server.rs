@@ -1,9 +1,12 @@
 use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
 
-use ragnarok_packets::{AccountId, CharacterId, Sex};
+use ragnarok_bytes::ConversionError;
+use ragnarok_packets::{AccountId, CharacterId, LoginFailedReason, QuestObjectiveLayout, Sex};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
-use crate::event::NetworkEvent;
+use crate::event::{DisconnectReason, NetworkEvent};
+use crate::rate_limit::RateLimit;
 
 #[derive(Debug, Clone, Copy)]
 pub struct LoginServerLoginData {
@@ -35,6 +38,22 @@ pub enum UnifiedCharacterSelectionFailedReason {
     MapServerUnavailable,
 }
 
+/// The reason why
+/// [`NetworkingSystem::enter_game`](crate::NetworkingSystem::enter_game)
+/// failed.
+#[derive(Debug, Clone)]
+pub enum EnterGameFailedReason {
+    Login(UnifiedLoginFailedReason),
+    CharacterServer(LoginFailedReason),
+    CharacterSelection(UnifiedCharacterSelectionFailedReason),
+    /// The login server didn't list any character servers.
+    NoCharacterServer,
+    /// There is no character in the requested slot.
+    CharacterNotFound,
+    /// The server of the current step closed the connection.
+    Disconnected(DisconnectReason),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CharacterServerLoginData {
     pub server_ip: IpAddr,
@@ -42,20 +61,120 @@ pub struct CharacterServerLoginData {
     pub character_id: CharacterId,
 }
 
+/// Data that a server sends right after logging in, without any packet header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConnectionPrefix {
+    /// The server starts sending packets right away.
+    #[default]
+    None,
+    /// The server sends the account id first. The character server always
+    /// does this. rAthena map servers do this when compiled for packet versions
+    /// older than 2007-05-21, newer versions send the account id as packet
+    /// `0x0283` instead.
+    AccountId,
+}
+
+/// The packet version of the client, as a date in the format `YYYYMMDD`. This
+/// is the same value as `PACKETVER` in rAthena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClientVersion(pub u32);
+
+impl ClientVersion {
+    /// First version where the map server sends the account id as packet
+    /// `0x0283` instead of a [`ConnectionPrefix::AccountId`].
+    const ACCOUNT_ID_PACKET: Self = Self(20070521);
+    /// First version where the client requests the characters page by page,
+    /// after receiving the character list. rAthena uses this value, which is
+    /// not an actual date.
+    const CHARACTER_LIST_PAGES: Self = Self(20130000);
+    /// First version where the character list is sent with slot information.
+    const CHARACTER_SLOT_INFORMATION: Self = Self(20100413);
+    /// First version where equippable items in the inventory are sent with
+    /// their enchantment level.
+    const EQUIPPABLE_ITEM_ENCHANTMENT_LEVEL: Self = Self(20200724);
+    /// First version where quest objectives are sent with a hunt
+    /// identification.
+    const QUEST_OBJECTIVE_HUNT_IDENTIFICATION: Self = Self(20150513);
+    /// First version where quest objectives are sent with their objective type
+    /// and level range.
+    const QUEST_OBJECTIVE_LEVEL_RANGE: Self = Self(20181010);
+    /// All versions above, so tests can cover every combination of packets.
+    #[cfg(test)]
+    pub(crate) const CHANGES: [Self; 6] = [
+        Self::ACCOUNT_ID_PACKET,
+        Self::CHARACTER_LIST_PAGES,
+        Self::CHARACTER_SLOT_INFORMATION,
+        Self::EQUIPPABLE_ITEM_ENCHANTMENT_LEVEL,
+        Self::QUEST_OBJECTIVE_HUNT_IDENTIFICATION,
+        Self::QUEST_OBJECTIVE_LEVEL_RANGE,
+    ];
+
+    /// The data the map server sends right after logging in.
+    pub fn map_server_connection_prefix(self) -> ConnectionPrefix {
+        match self < Self::ACCOUNT_ID_PACKET {
+            true => ConnectionPrefix::AccountId,
+            false => ConnectionPrefix::None,
+        }
+    }
+
+    /// Whether the character server sends slot information in front of the
+    /// characters of the character list.
+    pub fn sends_character_slot_information(self) -> bool {
+        self >= Self::CHARACTER_SLOT_INFORMATION
+    }
+
+    /// Whether the characters are requested page by page, instead of being
+    /// taken from the character list that is sent after logging in.
+    pub fn requests_character_list_pages(self) -> bool {
+        self >= Self::CHARACTER_LIST_PAGES
+    }
+
+    /// Whether the map server sends the enchantment level of equippable items
+    /// in the inventory.
+    pub fn sends_equippable_item_enchantment_level(self) -> bool {
+        self >= Self::EQUIPPABLE_ITEM_ENCHANTMENT_LEVEL
+    }
+
+    /// The layout of the quest objectives in the quest list the map server
+    /// sends.
+    pub fn quest_objective_layout(self) -> QuestObjectiveLayout {
+        match self {
+            version if version >= Self::QUEST_OBJECTIVE_LEVEL_RANGE => QuestObjectiveLayout::Extended,
+            version if version >= Self::QUEST_OBJECTIVE_HUNT_IDENTIFICATION => QuestObjectiveLayout::Hunting,
+            _ => QuestObjectiveLayout::Legacy,
+        }
+    }
+}
+
 pub(crate) enum ServerConnectCommand {
     Login {
         address: SocketAddr,
-        action_receiver: UnboundedReceiver<Vec<u8>>,
+        connect_timeout: Duration,
+        catch_parse_panics: bool,
+        rate_limit: Option<RateLimit>,
+        event_capacity: usize,
+        action_receiver: UnboundedReceiver<Vec<Vec<u8>>>,
         event_sender: UnboundedSender<NetworkEvent>,
     },
     Character {
         address: SocketAddr,
-        action_receiver: UnboundedReceiver<Vec<u8>>,
+        connect_timeout: Duration,
+        catch_parse_panics: bool,
+        rate_limit: Option<RateLimit>,
+        event_capacity: usize,
+        client_version: Option<ClientVersion>,
+        action_receiver: UnboundedReceiver<Vec<Vec<u8>>>,
         event_sender: UnboundedSender<NetworkEvent>,
     },
     Map {
         address: SocketAddr,
-        action_receiver: UnboundedReceiver<Vec<u8>>,
+        connect_timeout: Duration,
+        catch_parse_panics: bool,
+        rate_limit: Option<RateLimit>,
+        event_capacity: usize,
+        client_version: Option<ClientVersion>,
+        connection_prefix: ConnectionPrefix,
+        action_receiver: UnboundedReceiver<Vec<Vec<u8>>>,
         event_sender: UnboundedSender<NetworkEvent>,
     },
 }
@@ -63,15 +182,31 @@ pub(crate) enum ServerConnectCommand {
 #[derive(Debug)]
 pub(crate) enum NetworkTaskError {
     FailedToConnect,
+    ConnectTimedOut,
     ConnectionClosed,
+    ParsePanicked,
 }
 
 #[derive(Debug)]
 pub struct NotConnectedError;
 
+/// Error returned when sending multiple packets at once. Nothing is sent if
+/// any of the packets can't be converted to bytes.
+#[derive(Debug)]
+pub enum SendPacketsError {
+    NotConnected,
+    ConversionFailed(Box<ConversionError>),
+}
+
+impl From<NotConnectedError> for SendPacketsError {
+    fn from(_: NotConnectedError) -> Self {
+        Self::NotConnected
+    }
+}
+
 pub(crate) enum ServerConnection {
     Connected {
-        action_sender: UnboundedSender<Vec<u8>>,
+        action_sender: UnboundedSender<Vec<Vec<u8>>>,
         event_receiver: UnboundedReceiver<NetworkEvent>,
     },
     ClosingManually,
@@ -83,3 +218,44 @@ impl ServerConnection {
         std::mem::replace(self, ServerConnection::Disconnected)
     }
 }
+
+#[cfg(test)]
+mod client_version {
+    use ragnarok_packets::QuestObjectiveLayout;
+
+    use super::{ClientVersion, ConnectionPrefix};
+
+    #[test]
+    fn map_server_connection_prefix() {
+        assert_eq!(
+            ClientVersion(20070227).map_server_connection_prefix(),
+            ConnectionPrefix::AccountId
+        );
+        assert_eq!(ClientVersion(20070521).map_server_connection_prefix(), ConnectionPrefix::None);
+        assert_eq!(ClientVersion(20220406).map_server_connection_prefix(), ConnectionPrefix::None);
+    }
+
+    #[test]
+    fn character_list() {
+        assert!(!ClientVersion(20100412).sends_character_slot_information());
+        assert!(ClientVersion(20100413).sends_character_slot_information());
+        assert!(!ClientVersion(20121231).requests_character_list_pages());
+        assert!(ClientVersion(20130000).requests_character_list_pages());
+        assert!(ClientVersion(20220406).requests_character_list_pages());
+    }
+
+    #[test]
+    fn equippable_item_enchantment_level() {
+        assert!(!ClientVersion(20200723).sends_equippable_item_enchantment_level());
+        assert!(ClientVersion(20200724).sends_equippable_item_enchantment_level());
+        assert!(ClientVersion(20220406).sends_equippable_item_enchantment_level());
+    }
+
+    #[test]
+    fn quest_objective_layout() {
+        assert_eq!(ClientVersion(20150512).quest_objective_layout(), QuestObjectiveLayout::Legacy);
+        assert_eq!(ClientVersion(20150513).quest_objective_layout(), QuestObjectiveLayout::Hunting);
+        assert_eq!(ClientVersion(20181009).quest_objective_layout(), QuestObjectiveLayout::Hunting);
+        assert_eq!(ClientVersion(20181010).quest_objective_layout(), QuestObjectiveLayout::Extended);
+    }
+}
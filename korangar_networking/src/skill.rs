@@ -0,0 +1,81 @@
+use ragnarok_packets::{EntityId, TilePosition};
+
+use crate::NotConnectedError;
+
+/// The reason why a skill packet was not sent. Some servers disconnect clients
+/// that send skill packets with an invalid target, so these are caught before
+/// sending.
+#[derive(Debug)]
+pub enum SkillCastError {
+    NotConnected,
+    /// The target entity id is 0, which is never assigned to an entity.
+    InvalidTarget,
+    /// The target position is outside of the map.
+    OutOfBounds,
+}
+
+impl From<NotConnectedError> for SkillCastError {
+    fn from(_: NotConnectedError) -> Self {
+        Self::NotConnected
+    }
+}
+
+/// The size of a map in tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl MapSize {
+    /// Returns `true` if the position is a tile of the map.
+    pub fn contains(&self, position: TilePosition) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+}
+
+pub(crate) fn validate_entity_target(entity_id: EntityId) -> Result<(), SkillCastError> {
+    match entity_id.0 {
+        0 => Err(SkillCastError::InvalidTarget),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_ground_target(target_position: TilePosition, map_size: MapSize) -> Result<(), SkillCastError> {
+    match map_size.contains(target_position) {
+        true => Ok(()),
+        false => Err(SkillCastError::OutOfBounds),
+    }
+}
+
+#[cfg(test)]
+mod validation {
+    use ragnarok_packets::{EntityId, TilePosition};
+
+    use crate::skill::{validate_entity_target, validate_ground_target, MapSize, SkillCastError};
+
+    const MAP_SIZE: MapSize = MapSize { width: 100, height: 50 };
+
+    #[test]
+    fn entity_target() {
+        assert!(validate_entity_target(EntityId(150000)).is_ok());
+        assert!(matches!(
+            validate_entity_target(EntityId(0)),
+            Err(SkillCastError::InvalidTarget)
+        ));
+    }
+
+    #[test]
+    fn ground_target() {
+        assert!(validate_ground_target(TilePosition { x: 0, y: 0 }, MAP_SIZE).is_ok());
+        assert!(validate_ground_target(TilePosition { x: 99, y: 49 }, MAP_SIZE).is_ok());
+        assert!(matches!(
+            validate_ground_target(TilePosition { x: 100, y: 0 }, MAP_SIZE),
+            Err(SkillCastError::OutOfBounds)
+        ));
+        assert!(matches!(
+            validate_ground_target(TilePosition { x: 0, y: 50 }, MAP_SIZE),
+            Err(SkillCastError::OutOfBounds)
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+use cgmath::{EuclideanSpace, InnerSpace, MetricSpace, Point3, Vector3};
+#[cfg(feature = "interface")]
+use korangar_interface::elements::PrototypeElement;
+
+use crate::collision::{Insertable, Query, Sphere, AABB};
+
+/// A capsule, which is a line segment with a radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "interface", derive(PrototypeElement))]
+pub struct Capsule {
+    start: Point3<f32>,
+    end: Point3<f32>,
+    radius: f32,
+}
+
+impl Capsule {
+    /// Create a new capsule from the two end points of its segment and a
+    /// radius.
+    pub fn new(start: Point3<f32>, end: Point3<f32>, radius: f32) -> Self {
+        Capsule { start, end, radius }
+    }
+
+    /// Get the start point of the segment.
+    pub fn start(&self) -> Point3<f32> {
+        self.start
+    }
+
+    /// Get the end point of the segment.
+    pub fn end(&self) -> Point3<f32> {
+        self.end
+    }
+
+    /// Get the radius of the capsule.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Get the point on the segment that is closest to the given point.
+    pub fn closest_point_on_segment(&self, point: Point3<f32>) -> Point3<f32> {
+        let segment = self.end - self.start;
+        let length_squared = segment.magnitude2();
+
+        if length_squared == 0.0 {
+            return self.start;
+        }
+
+        let factor = ((point - self.start).dot(segment) / length_squared).clamp(0.0, 1.0);
+        self.start + segment * factor
+    }
+
+    /// Get the distance from the given point to the surface of the capsule.
+    /// Points inside the capsule have a distance of 0.
+    pub fn distance_to_point(&self, point: Point3<f32>) -> f32 {
+        (self.closest_point_on_segment(point).distance(point) - self.radius).max(0.0)
+    }
+
+    /// Check if a point is inside the capsule.
+    pub fn contains_point(&self, point: Point3<f32>) -> bool {
+        self.closest_point_on_segment(point).distance(point) <= self.radius
+    }
+
+    /// Check if this capsule intersects with a sphere.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.closest_point_on_segment(sphere.center()).distance(sphere.center()) <= self.radius + sphere.radius()
+    }
+
+    /// Check if this capsule intersects with an AABB. This is a conservative
+    /// test, that may report an intersection for AABBs that are close to the
+    /// capsule but not touching it.
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.bounding_box().intersects_aabb(aabb)
+    }
+
+    /// Get the bounding box of the capsule.
+    pub fn bounding_box(&self) -> AABB {
+        let radius = Vector3::new(self.radius, self.radius, self.radius);
+        let min = Point3::from_vec(self.start.to_vec().zip(self.end.to_vec(), f32::min));
+        let max = Point3::from_vec(self.start.to_vec().zip(self.end.to_vec(), f32::max));
+
+        AABB::new(min - radius, max + radius)
+    }
+}
+
+impl Insertable for Capsule {
+    fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.intersects_aabb(aabb)
+    }
+
+    fn bounding_box(&self) -> AABB {
+        self.bounding_box()
+    }
+}
+
+impl Query<Capsule> for Sphere {
+    fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        self.intersects_aabb(aabb)
+    }
+
+    fn intersects_object(&self, object: &Capsule) -> bool {
+        object.intersects_sphere(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Point3;
+
+    use crate::collision::{Capsule, Sphere, AABB};
+
+    #[test]
+    fn test_closest_point_on_segment() {
+        let capsule = Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 0.0, 0.0), 1.0);
+        assert_eq!(
+            capsule.closest_point_on_segment(Point3::new(5.0, 3.0, 0.0)),
+            Point3::new(5.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            capsule.closest_point_on_segment(Point3::new(-5.0, 3.0, 0.0)),
+            Point3::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            capsule.closest_point_on_segment(Point3::new(15.0, 3.0, 0.0)),
+            Point3::new(10.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_degenerate_segment() {
+        let capsule = Capsule::new(Point3::new(1.0, 1.0, 1.0), Point3::new(1.0, 1.0, 1.0), 2.0);
+        assert_eq!(
+            capsule.closest_point_on_segment(Point3::new(5.0, 5.0, 5.0)),
+            Point3::new(1.0, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let capsule = Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 0.0, 0.0), 2.0);
+        assert!(capsule.contains_point(Point3::new(5.0, 1.5, 0.0)));
+        assert!(capsule.contains_point(Point3::new(11.0, 0.0, 0.0)));
+        assert!(!capsule.contains_point(Point3::new(5.0, 3.0, 0.0)));
+    }
+
+    #[test]
+    fn test_distance_to_point() {
+        let capsule = Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 0.0, 0.0), 2.0);
+        assert_eq!(capsule.distance_to_point(Point3::new(5.0, 5.0, 0.0)), 3.0);
+        assert_eq!(capsule.distance_to_point(Point3::new(5.0, 1.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn test_intersects_sphere() {
+        let capsule = Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(10.0, 0.0, 0.0), 2.0);
+        assert!(capsule.intersects_sphere(&Sphere::new(Point3::new(5.0, 4.0, 0.0), 2.5)));
+        assert!(!capsule.intersects_sphere(&Sphere::new(Point3::new(5.0, 5.0, 0.0), 2.5)));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let capsule = Capsule::new(Point3::new(10.0, 0.0, -2.0), Point3::new(0.0, 4.0, 2.0), 1.0);
+        let bounding_box = capsule.bounding_box();
+        assert_eq!(bounding_box.min(), Point3::new(-1.0, -1.0, -3.0));
+        assert_eq!(bounding_box.max(), Point3::new(11.0, 5.0, 3.0));
+        assert!(capsule.intersects_aabb(&AABB::new(Point3::new(10.5, 0.0, 0.0), Point3::new(12.0, 1.0, 1.0))));
+    }
+}
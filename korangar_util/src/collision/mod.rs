@@ -2,6 +2,7 @@
 
 mod aabb;
 mod aligned_plane;
+mod capsule;
 mod frustum;
 mod kdtree;
 mod plane;
@@ -9,6 +10,7 @@ mod sphere;
 
 pub use aabb::AABB;
 pub use aligned_plane::{AlignedPlane, Axis};
+pub use capsule::Capsule;
 pub use frustum::Frustum;
 pub use kdtree::{Insertable, KDTree, Query};
 pub use plane::{IntersectionClassification, Plane};
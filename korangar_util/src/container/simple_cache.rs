@@ -112,6 +112,16 @@ impl<K: Clone + Eq + Hash, V: Cacheable> SimpleCache<K, V> {
         })
     }
 
+    /// Returns `true` if the cache holds a value for the given key. Unlike
+    /// [`SimpleCache::get`], this doesn't count as a use of the value.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.lookup.contains_key(key)
+    }
+
     /// Removes the value with the given key from the cache.
     #[must_use]
     pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
@@ -127,6 +137,16 @@ impl<K: Clone + Eq + Hash, V: Cacheable> SimpleCache<K, V> {
         })
     }
 
+    /// Removes all values from the cache whose key doesn't satisfy the given
+    /// predicate.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&K) -> bool) {
+        let removed_keys: Vec<K> = self.lookup.keys().filter(|key| !predicate(key)).cloned().collect();
+
+        for key in removed_keys {
+            let _ = self.remove(&key);
+        }
+    }
+
     fn update_statistics(&self) {
         self.statistics.count.store(self.cache.count(), Ordering::Release);
         self.statistics.size.store(self.cache.size(), Ordering::Release);
@@ -158,6 +178,22 @@ mod tests {
         assert_eq!(cache.get("nonexistent"), None);
     }
 
+    #[test]
+    fn test_contains_key_does_not_touch() {
+        let mut cache = SimpleCache::new(NonZeroU32::new(2).unwrap(), NonZeroUsize::new(100).unwrap());
+
+        cache.insert("key1".to_string(), vec![1]).unwrap();
+        cache.insert("key2".to_string(), vec![2]).unwrap();
+
+        assert!(cache.contains_key("key1"));
+        assert!(!cache.contains_key("nonexistent"));
+
+        // "key1" is still the least recently used value and gets evicted.
+        cache.insert("key3".to_string(), vec![3]).unwrap();
+        assert!(!cache.contains_key("key1"));
+        assert!(cache.contains_key("key2"));
+    }
+
     #[test]
     fn test_remove() {
         let mut cache = SimpleCache::new(NonZeroU32::new(2).unwrap(), NonZeroUsize::new(100).unwrap());
@@ -257,4 +293,23 @@ mod tests {
         assert_eq!(new_snapshot.count, 2);
         assert_eq!(new_snapshot.size, 5);
     }
+
+    #[test]
+    fn test_retain() {
+        let mut cache = SimpleCache::new(NonZeroU32::new(5).unwrap(), NonZeroUsize::new(100).unwrap());
+
+        cache.insert("key1".to_string(), vec![1, 2]).unwrap();
+        cache.insert("key2".to_string(), vec![3, 4, 5]).unwrap();
+        cache.insert("key3".to_string(), vec![6]).unwrap();
+
+        cache.retain(|key| key != "key2");
+
+        assert_eq!(cache.get("key1"), Some(&vec![1, 2]));
+        assert_eq!(cache.get("key2"), None);
+        assert_eq!(cache.get("key3"), Some(&vec![6]));
+
+        let snapshot = cache.statistics().snapshot();
+        assert_eq!(snapshot.count, 2);
+        assert_eq!(snapshot.size, 3);
+    }
 }
@@ -1,17 +1,34 @@
-/// Error that is thrown when a file loader can't find the requested file.
-#[repr(transparent)]
-pub struct FileNotFoundError(String);
+/// Error that is thrown when a file loader can't find or read the requested
+/// file.
+pub struct FileNotFoundError {
+    path: String,
+    transient: bool,
+}
 
 impl FileNotFoundError {
     /// Create a new [`FileNotFoundError`] with a given path.
     pub fn new(path: String) -> Self {
-        Self(path)
+        Self { path, transient: false }
+    }
+
+    /// Create a new [`FileNotFoundError`] for a file that exists but could not
+    /// be read, for example because it was locked by another process.
+    pub fn unreadable(path: String) -> Self {
+        Self { path, transient: true }
+    }
+
+    /// Returns `true` if loading the file again might succeed.
+    pub fn is_transient(&self) -> bool {
+        self.transient
     }
 }
 
 impl std::fmt::Debug for FileNotFoundError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "can't find file: {}", self.0)
+        match self.transient {
+            true => write!(f, "can't read file: {}", self.path),
+            false => write!(f, "can't find file: {}", self.path),
+        }
     }
 }
 
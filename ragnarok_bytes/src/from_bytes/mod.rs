@@ -98,3 +98,48 @@ mod from_n_bytes {
         assert_eq!(byte_reader.remaining_bytes().len(), TEST_BYTE_SIZE / 2);
     }
 }
+
+#[cfg(test)]
+mod string_padding {
+    use crate::{ByteReader, FromBytesExt};
+
+    const FIELD_SIZE: usize = 8;
+
+    #[test]
+    fn shorter_than_field() {
+        let mut byte_reader = ByteReader::without_metadata(b"Alice\0\0\0");
+        let result = String::from_n_bytes(&mut byte_reader, FIELD_SIZE);
+
+        assert_eq!(result.unwrap(), "Alice");
+        assert!(byte_reader.is_empty());
+    }
+
+    #[test]
+    fn same_length_as_field() {
+        let mut byte_reader = ByteReader::without_metadata(b"Prontera");
+        let result = String::from_n_bytes(&mut byte_reader, FIELD_SIZE);
+
+        assert_eq!(result.unwrap(), "Prontera");
+        assert!(byte_reader.is_empty());
+    }
+
+    #[test]
+    fn data_after_padding() {
+        let mut byte_reader = ByteReader::without_metadata(b"Bob\0\0\0\0\0rest");
+        let result = String::from_n_bytes(&mut byte_reader, FIELD_SIZE);
+
+        assert_eq!(result.unwrap(), "Bob");
+        assert_eq!(byte_reader.remaining_bytes(), b"rest");
+    }
+
+    #[test]
+    fn garbage_after_terminator() {
+        // Client files and servers often don't clear the bytes following the
+        // terminator, so they are ignored.
+        let mut byte_reader = ByteReader::without_metadata(b"Bob\0ab\0\0");
+        let result = String::from_n_bytes(&mut byte_reader, FIELD_SIZE);
+
+        assert_eq!(result.unwrap(), "Bob");
+        assert!(byte_reader.is_empty());
+    }
+}
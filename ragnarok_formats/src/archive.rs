@@ -17,6 +17,8 @@ pub struct Header {
 
 impl Header {
     pub const FILE_OFFSET: usize = 7;
+    /// The only GRF version that is currently supported.
+    pub const SUPPORTED_VERSION: u32 = 0x200;
 
     pub fn get_file_count(&self) -> usize {
         (self.file_count - self.reserved_files) as usize - Self::FILE_OFFSET
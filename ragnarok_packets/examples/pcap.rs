@@ -18,6 +18,10 @@ const EXPAND_BEHAVIOUR: ExpandBehaviour = ExpandBehaviour::Blacklist(&[
     TypeId::of::<MapServerPingPacket>(),
 ]);
 
+// Enable this to print the bytes of every packet, for example to use them as
+// fixtures in the conversion tests.
+const PRINT_FIXTURES: bool = false;
+
 #[allow(dead_code)]
 enum ExpandBehaviour {
     None,
@@ -149,6 +153,29 @@ fn handler<P: Packet + 'static>(server_type: ServerType, direction: Direction) -
     }
 }
 
+fn process_packets<Callback>(packet_handler: &mut PacketHandler<(), (), Callback>, payload: &[u8])
+where
+    Callback: PacketCallback,
+{
+    let mut byte_reader = ByteReader::without_metadata(payload);
+
+    loop {
+        let start = byte_reader.get_offset();
+
+        let HandlerResult::Ok(_) = packet_handler.process_one(&mut byte_reader) else {
+            break;
+        };
+
+        if PRINT_FIXTURES {
+            let bytes: Vec<String> = payload[start..byte_reader.get_offset()]
+                .iter()
+                .map(|byte| format!("0x{byte:02X}"))
+                .collect();
+            println!("[{}]", bytes.join(", "));
+        }
+    }
+}
+
 macro_rules! create_handler {
     ($server_type:expr, $direction:expr, [$($packet:ty),* $(,)?]) => {
         {
@@ -205,6 +232,7 @@ fn main() {
         CharacterSlotPagePacket,
         CharacterBanListPacket,
         LoginPincodePacket,
+        PincodeSubmitPacket,
         SelectCharacterPacket,
         CreateCharacterPacket,
         DeleteCharacterPacket,
@@ -299,6 +327,10 @@ fn main() {
         FriendRequestResultPacket,
         NotifyFriendRemovedPacket,
         PartyInvitePacket,
+        PartyInformationPacket,
+        PartyMemberLeftPacket,
+        PartyMemberHealthPacket,
+        PartyMemberPositionPacket,
         StatusChangeSequencePacket,
         ReputationPacket,
         ClanInfoPacket,
@@ -307,11 +339,24 @@ fn main() {
         OpenMarketPacket,
         BuyOrSellPacket,
         ShopItemListPacket,
+        BuyItemsResultPacket,
         BuyShopItemsResultPacket,
         ParameterChangePacket,
         SellListPacket,
         SellItemsPacket,
         SellItemsResultPacket,
+        GuildEmblemPacket,
+        StorageItemCountPacket,
+        StorageItemAddedPacket,
+        StorageItemRemovedPacket,
+        StorageClosedPacket,
+        VendingShopOpenedPacket,
+        VendingShopClosedPacket,
+        VendingItemListPacket,
+        VendingPurchaseResultPacket,
+        MailListPacket,
+        MailContentPacket,
+        SendMailResultPacket,
     ]);
 
     let mut server_map_handler = create_handler!(ServerType::Map, Direction::Outgoing, [
@@ -341,6 +386,16 @@ fn main() {
         CloseShopPacket,
         SellItemsPacket,
         RequestServerTickPacket,
+        RequestGuildEmblemPacket,
+        MoveItemToStoragePacket,
+        MoveItemFromStoragePacket,
+        CloseStoragePacket,
+        RequestVendingItemListPacket,
+        PurchaseVendingItemsPacket,
+        OpenMailboxPacket,
+        CloseMailboxPacket,
+        ReadMailPacket,
+        SendMailPacket,
     ]);
 
     println!("{}", "Listening for packets".green());
@@ -359,23 +414,17 @@ fn main() {
                     // FIX: Handle cut off packets.
 
                     if source_port == LOGIN_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_login_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut client_login_handler, tcp_slice.payload());
                     } else if destination_port == LOGIN_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_login_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut server_login_handler, tcp_slice.payload());
                     } else if source_port == CHARACTER_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_character_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut client_character_handler, tcp_slice.payload());
                     } else if destination_port == CHARACTER_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_character_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut server_character_handler, tcp_slice.payload());
                     } else if source_port == MAP_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_map_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut client_map_handler, tcp_slice.payload());
                     } else if destination_port == MAP_SERVER_PORT {
-                        let mut byte_reader = ByteReader::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_map_handler.process_one(&mut byte_reader) {}
+                        process_packets(&mut server_map_handler, tcp_slice.payload());
                     }
                 };
             }
@@ -0,0 +1,120 @@
+use ragnarok_bytes::ByteConvertable;
+
+/// Slot information that clients since 2010-04-13 receive in front of the
+/// characters of a [`CharacterListPacket`](crate::CharacterListPacket).
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct CharacterSlotInformation {
+    pub maximum_slot_count: u8,
+    pub available_slot_count: u8,
+    pub vip_slot_count: u8,
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::{
+        CharacterId, CharacterInformation, CharacterListPacket, CharacterSlotInformation, LegacyCharacterListPacket, PacketExt, Sex,
+    };
+
+    fn character_information(character_id: u32, name: &str) -> CharacterInformation {
+        CharacterInformation {
+            character_id: CharacterId(character_id),
+            experience: 1000,
+            money: 500,
+            job_experience: 200,
+            job_level: 10,
+            body_state: 0,
+            health_state: 0,
+            effect_state: 0,
+            virtue: 0,
+            honor: 0,
+            job_points: 0,
+            health_points: 120,
+            maximum_health_points: 150,
+            spell_points: 30,
+            maximum_spell_points: 40,
+            movement_speed: 150,
+            job: 0,
+            head: 2,
+            body: 0,
+            weapon: 1,
+            level: 12,
+            sp_point: 0,
+            accessory: 0,
+            shield: 0,
+            accessory2: 0,
+            accessory3: 0,
+            head_palette: 0,
+            body_palette: 0,
+            name: name.to_owned(),
+            strength: 5,
+            agility: 5,
+            vit: 5,
+            intelligence: 5,
+            dexterity: 5,
+            luck: 5,
+            character_number: 0,
+            hair_color: 1,
+            b_is_changed_char: 0,
+            map_name: "prontera.gat".to_owned(),
+            deletion_reverse_date: 0,
+            robe_palette: 0,
+            character_slot_change_count: 0,
+            character_name_change_count: 0,
+            sex: Sex::Female,
+        }
+    }
+
+    fn characters() -> Vec<CharacterInformation> {
+        vec![character_information(150000, "Alice"), character_information(150001, "Bob")]
+    }
+
+    fn round_trip<Packet: PacketExt>(packet: &Packet) -> Packet {
+        let bytes = packet.packet_to_bytes().unwrap();
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let parsed = Packet::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        parsed
+    }
+
+    #[test]
+    fn character_list() {
+        let packet = CharacterListPacket {
+            slot_information: CharacterSlotInformation {
+                maximum_slot_count: 15,
+                available_slot_count: 9,
+                vip_slot_count: 0,
+            },
+            unknown: [0; 20],
+            character_information: characters(),
+        };
+        let parsed = round_trip(&packet);
+
+        assert_eq!(parsed.slot_information, packet.slot_information);
+        assert_eq!(parsed.character_information, packet.character_information);
+    }
+
+    #[test]
+    fn legacy_character_list() {
+        let packet = LegacyCharacterListPacket {
+            unknown: [0; 20],
+            character_information: characters(),
+        };
+
+        assert_eq!(round_trip(&packet).character_information, packet.character_information);
+    }
+
+    #[test]
+    fn empty_character_list() {
+        let packet = LegacyCharacterListPacket {
+            unknown: [0; 20],
+            character_information: Vec::new(),
+        };
+
+        assert!(round_trip(&packet).character_information.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::ColorRGBA;
+
+/// Splits a text at the color codes embedded in it. A color code is a `^`
+/// followed by six hex digits (`^RRGGBB`) and applies to all following text.
+/// `^000000` resets the color to the default color, which is passed as `None`.
+/// Malformed or incomplete color codes are kept as literal text.
+///
+/// Every non-empty part of the text is passed to `push_span` together with its
+/// color. `color` is the color at the start of the text and the color at the
+/// end of the text is returned, so that it can carry over to the next line.
+pub fn split_color_codes<'a>(
+    text: &'a str,
+    mut color: Option<ColorRGBA>,
+    mut push_span: impl FnMut(&'a str, Option<ColorRGBA>),
+) -> Option<ColorRGBA> {
+    let mut span_start = 0;
+    let mut search_start = 0;
+
+    while let Some(offset) = text[search_start..].find('^') {
+        let code_start = search_start + offset;
+
+        let Some(code) = text
+            .get(code_start + 1..code_start + 7)
+            .filter(|code| code.chars().all(|character| character.is_ascii_hexdigit()))
+        else {
+            search_start = code_start + 1;
+            continue;
+        };
+
+        if code_start > span_start {
+            push_span(&text[span_start..code_start], color.clone());
+        }
+
+        color = match code {
+            "000000" => None,
+            code => Some(ColorRGBA {
+                red: u8::from_str_radix(&code[0..2], 16).unwrap(),
+                green: u8::from_str_radix(&code[2..4], 16).unwrap(),
+                blue: u8::from_str_radix(&code[4..6], 16).unwrap(),
+                alpha: 255,
+            }),
+        };
+
+        span_start = code_start + 7;
+        search_start = span_start;
+    }
+
+    if span_start < text.len() {
+        push_span(&text[span_start..], color.clone());
+    }
+
+    color
+}
@@ -0,0 +1,109 @@
+use crate::{DamagePacket1, DamagePacket3, DamageType};
+
+/// How the damage of a [`DamagePacket1`] or [`DamagePacket3`] should be
+/// displayed, derived from its [`DamageType`], damage and number of hits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatResult {
+    Normal,
+    Critical,
+    /// The attack hit but didn't deal any damage.
+    Miss,
+    LuckyDodge,
+    MultiHit {
+        hits: u16,
+    },
+    CriticalMultiHit {
+        hits: u16,
+    },
+}
+
+impl DamageType {
+    /// Returns how the damage should be displayed, or `None` if the damage
+    /// type doesn't describe an attack, for example when sitting down.
+    pub fn combat_result(&self, damage_amount: u32, number_of_hits: u16) -> Option<CombatResult> {
+        let hits = number_of_hits.max(1);
+
+        let combat_result = match self {
+            DamageType::LuckyDodge => return Some(CombatResult::LuckyDodge),
+            DamageType::PickUpItem | DamageType::SitDown | DamageType::StandUp | DamageType::TouchSkill | DamageType::Unknown(_) => {
+                return None;
+            }
+            _ if damage_amount == 0 => CombatResult::Miss,
+            DamageType::CriticalHit if hits > 1 => CombatResult::CriticalMultiHit { hits },
+            DamageType::CriticalHit => CombatResult::Critical,
+            DamageType::CriticalMultiHit => CombatResult::CriticalMultiHit { hits },
+            DamageType::MultiHitDamage | DamageType::MultiHitDamageEndure => CombatResult::MultiHit { hits },
+            DamageType::Damage | DamageType::DamageEndure | DamageType::Splash | DamageType::Skill | DamageType::RepeatDamage
+                if hits > 1 =>
+            {
+                CombatResult::MultiHit { hits }
+            }
+            DamageType::Damage | DamageType::DamageEndure | DamageType::Splash | DamageType::Skill | DamageType::RepeatDamage => {
+                CombatResult::Normal
+            }
+        };
+
+        Some(combat_result)
+    }
+}
+
+impl DamagePacket1 {
+    /// Returns how the damage should be displayed. See
+    /// [`DamageType::combat_result`].
+    pub fn combat_result(&self) -> Option<CombatResult> {
+        let damage_amount = self.damage_amount.max(0) as u32 + self.damage_amount_2.max(0) as u32;
+        self.damage_type.combat_result(damage_amount, self.number_of_hits)
+    }
+}
+
+impl DamagePacket3 {
+    /// Returns how the damage should be displayed. See
+    /// [`DamageType::combat_result`].
+    pub fn combat_result(&self) -> Option<CombatResult> {
+        let damage_amount = self.damage_amount.saturating_add(self.damage_amount_2);
+        self.damage_type.combat_result(damage_amount, self.number_of_hits)
+    }
+}
+
+#[cfg(test)]
+mod combat_result {
+    use crate::{CombatResult, DamageType};
+
+    #[test]
+    fn regular_hits() {
+        assert_eq!(DamageType::Damage.combat_result(10, 1), Some(CombatResult::Normal));
+        assert_eq!(DamageType::Skill.combat_result(10, 0), Some(CombatResult::Normal));
+        assert_eq!(DamageType::CriticalHit.combat_result(10, 1), Some(CombatResult::Critical));
+    }
+
+    #[test]
+    fn multi_hits() {
+        assert_eq!(
+            DamageType::MultiHitDamage.combat_result(10, 3),
+            Some(CombatResult::MultiHit { hits: 3 })
+        );
+        assert_eq!(
+            DamageType::Damage.combat_result(10, 2),
+            Some(CombatResult::MultiHit { hits: 2 })
+        );
+        assert_eq!(
+            DamageType::CriticalMultiHit.combat_result(10, 2),
+            Some(CombatResult::CriticalMultiHit { hits: 2 })
+        );
+    }
+
+    #[test]
+    fn misses() {
+        assert_eq!(DamageType::Damage.combat_result(0, 1), Some(CombatResult::Miss));
+        assert_eq!(DamageType::MultiHitDamage.combat_result(0, 3), Some(CombatResult::Miss));
+        assert_eq!(DamageType::LuckyDodge.combat_result(0, 1), Some(CombatResult::LuckyDodge));
+    }
+
+    #[test]
+    fn not_an_attack() {
+        assert_eq!(DamageType::SitDown.combat_result(0, 0), None);
+        assert_eq!(DamageType::StandUp.combat_result(0, 0), None);
+        assert_eq!(DamageType::PickUpItem.combat_result(0, 0), None);
+        assert_eq!(DamageType::Unknown(42).combat_result(10, 1), None);
+    }
+}
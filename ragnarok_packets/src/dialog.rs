@@ -0,0 +1,153 @@
+use crate::{split_color_codes, ColorRGBA, NpcDialogPacket};
+
+/// A part of the text of an [`NpcDialogPacket`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogSpan {
+    /// Text in a single color. A color of `None` is the default text color of
+    /// the dialog.
+    Text {
+        text: String,
+        color: Option<ColorRGBA>,
+    },
+    LineBreak,
+}
+
+impl NpcDialogPacket {
+    /// Splits the text of the dialog into [`DialogSpan`]s at its color codes
+    /// (see [`split_color_codes`]) and line breaks. Colors apply to all
+    /// following text, including text after a line break.
+    pub fn spans(&self) -> Vec<DialogSpan> {
+        let mut spans = Vec::new();
+        let mut color = None;
+
+        for (index, line) in self.text.split('\n').enumerate() {
+            if index > 0 {
+                spans.push(DialogSpan::LineBreak);
+            }
+
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            color = split_color_codes(line, color, |text, color| {
+                spans.push(DialogSpan::Text {
+                    text: text.to_owned(),
+                    color,
+                })
+            });
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::fixture::padded;
+    use crate::{
+        ColorBGRA, DisplayImagePacket, EntityId, ImageLocation, LargeTilePosition, MarkMinimapPositionPacket, MarkerType, PacketExt,
+    };
+
+    fn display_image(location: u8) -> DisplayImagePacket {
+        let mut bytes = vec![0xB3, 0x01];
+        bytes.extend_from_slice(&padded::<64>(b"kafra1.bmp"));
+        bytes.push(location);
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = DisplayImagePacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        packet
+    }
+
+    #[test]
+    fn display_image_location() {
+        let packet = display_image(2);
+
+        assert_eq!(packet.image_name, "kafra1.bmp");
+        assert_eq!(packet.location, ImageLocation::BottomRight);
+    }
+
+    #[test]
+    fn display_image_clear_all() {
+        assert_eq!(display_image(255).location, ImageLocation::ClearAll);
+    }
+
+    #[test]
+    fn mark_minimap_position() {
+        let bytes = [
+            0x44, 0x01, 0x50, 0xC3, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x9C, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+            0xFF, 0x00,
+        ];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = MarkMinimapPositionPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.npc_id, EntityId(50000));
+        assert_eq!(packet.marker_type, MarkerType::DisplayUntilLeave);
+        assert_eq!(packet.position, LargeTilePosition { x: 156, y: 42 });
+        assert_eq!(packet.id, 2);
+        assert_eq!(packet.color, ColorBGRA {
+            red: 255,
+            green: 0,
+            blue: 0,
+            alpha: 0
+        });
+    }
+}
+
+#[cfg(test)]
+mod spans {
+    use crate::{ColorRGBA, DialogSpan, EntityId, NpcDialogPacket};
+
+    const BLUE: ColorRGBA = ColorRGBA {
+        red: 0,
+        green: 0,
+        blue: 255,
+        alpha: 255,
+    };
+
+    fn spans(text: &str) -> Vec<DialogSpan> {
+        NpcDialogPacket {
+            npc_id: EntityId(0),
+            text: text.to_owned(),
+        }
+        .spans()
+    }
+
+    fn text(text: &str, color: Option<ColorRGBA>) -> DialogSpan {
+        DialogSpan::Text {
+            text: text.to_owned(),
+            color,
+        }
+    }
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(spans("[Guide]"), vec![text("[Guide]", None)]);
+        assert!(spans("").is_empty());
+    }
+
+    #[test]
+    fn color_codes() {
+        assert_eq!(spans("Talk to ^0000FFKafra^000000 first."), vec![
+            text("Talk to ", None),
+            text("Kafra", Some(BLUE)),
+            text(" first.", None),
+        ]);
+    }
+
+    #[test]
+    fn color_continues_after_line_break() {
+        assert_eq!(spans("^0000FFFirst\r\nSecond"), vec![
+            text("First", Some(BLUE)),
+            DialogSpan::LineBreak,
+            text("Second", Some(BLUE)),
+        ]);
+    }
+
+    #[test]
+    fn malformed_codes_are_literal() {
+        assert_eq!(spans("^00FF Hello^"), vec![text("^00FF Hello^", None)]);
+        assert_eq!(spans("^GGGGGGText"), vec![text("^GGGGGGText", None)]);
+    }
+}
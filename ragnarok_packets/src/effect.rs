@@ -1,8 +1,10 @@
-use korangar_interface::elements::PrototypeElement;
 use ragnarok_bytes::ByteConvertable;
 
-#[derive(Clone, Debug, ByteConvertable, PrototypeElement)]
+/// The id of a special effect. Ids that are not known to the client are
+/// preserved as [`Unknown`](Self::Unknown) instead of failing to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ByteConvertable)]
 #[numeric_type(u32)]
+#[fallback(Unknown)]
 pub enum EffectId {
     //#[numeric_value(-1)]
     //EF_NONE,
@@ -1146,4 +1148,37 @@ pub enum EffectId {
     #[numeric_value(1242)]
     SoulExplosion,
     Max,
+    Unknown(u32),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for EffectId {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteReader, FromBytes, ToBytes};
+
+    use crate::EffectId;
+
+    #[test]
+    fn known_effect_id() {
+        let bytes = 1242u32.to_bytes().unwrap();
+        let effect_id = EffectId::from_bytes(&mut ByteReader::without_metadata(&bytes)).unwrap();
+
+        assert_eq!(effect_id, EffectId::SoulExplosion);
+        assert_eq!(effect_id.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn unknown_effect_id() {
+        let bytes = 5000u32.to_bytes().unwrap();
+        let effect_id = EffectId::from_bytes(&mut ByteReader::without_metadata(&bytes)).unwrap();
+
+        assert_eq!(effect_id, EffectId::Unknown(5000));
+        assert_eq!(effect_id.to_bytes().unwrap(), bytes);
+    }
 }
@@ -0,0 +1,27 @@
+//! Helpers to build packet bytes for the conversion tests.
+//!
+//! The bytes are assembled from the packet layouts, so they only check that
+//! a packet round-trips through its own definition. Traffic of a real server
+//! can be printed as byte arrays with the `pcap` example by enabling
+//! `PRINT_FIXTURES`.
+
+/// The cards of an item without any cards.
+pub(crate) const EMPTY_CARDS: [u8; 16] = [0; 16];
+/// The random options of an item without any options.
+pub(crate) const EMPTY_OPTION_DATA: [u8; 25] = [0; 25];
+
+/// Prefixes the payload of a variable length packet with the header and the
+/// length of the packet.
+pub(crate) fn with_header(header: u16, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = header.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&(payload.len() as u16 + 4).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// Pads a string with zeros to the fixed length used in a packet.
+pub(crate) fn padded<const N: usize>(text: &[u8]) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes[..text.len()].copy_from_slice(text);
+    bytes
+}
@@ -0,0 +1,133 @@
+use ragnarok_bytes::{ByteConvertable, FixedByteSize};
+
+/// The id of a guild. A value of 0 means that the entity is not in a guild.
+#[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct GuildId(pub u32);
+
+impl GuildId {
+    /// Returns `true` if the entity is in a guild.
+    pub fn is_guild(self) -> bool {
+        self.0 != 0
+    }
+}
+
+/// The version of a guild emblem. Incremented by the server every time the
+/// guild changes its emblem, so it can be used to invalidate cached emblems.
+#[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct EmblemVersion(pub u16);
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::fixture::{padded, with_header};
+    use crate::{
+        EmblemVersion, EntityAppearedPacket, EntityId, GuildEmblemPacket, GuildId, MovingEntityAppearedPacket, PacketExt,
+        RequestGuildEmblemPacket,
+    };
+
+    /// Everything from the object type up to and including the accessory of
+    /// an entity appeared packet.
+    fn common_fields(bytes: &mut Vec<u8>) {
+        bytes.push(0x00); // object type
+        bytes.extend_from_slice(&150000u32.to_le_bytes()); // entity id
+        bytes.extend_from_slice(&150000u32.to_le_bytes()); // group id
+        bytes.extend_from_slice(&150u16.to_le_bytes()); // movement speed
+        bytes.extend_from_slice(&[0; 4]); // body state and health state
+        bytes.extend_from_slice(&[0; 4]); // effect state
+        bytes.extend_from_slice(&4001u16.to_le_bytes()); // job
+        bytes.extend_from_slice(&[0; 2]); // head
+        bytes.extend_from_slice(&[0; 8]); // weapon and shield
+        bytes.extend_from_slice(&[0; 2]); // accessory
+    }
+
+    /// Everything following the guild emblem version of an entity appeared
+    /// packet.
+    fn trailing_fields(bytes: &mut Vec<u8>, position_length: usize) {
+        bytes.extend_from_slice(&[0; 2]); // honor
+        bytes.extend_from_slice(&[0; 4]); // virtue
+        bytes.push(0x00); // is pk mode on
+        bytes.push(0x01); // sex
+        bytes.extend(std::iter::repeat_n(0, position_length)); // position
+        bytes.extend_from_slice(&[5, 5]); // x size and y size
+        bytes.extend_from_slice(&99u16.to_le_bytes()); // c level
+        bytes.extend_from_slice(&[0; 2]); // font
+        bytes.extend_from_slice(&1000i32.to_le_bytes()); // maximum health points
+        bytes.extend_from_slice(&900i32.to_le_bytes()); // health points
+        bytes.push(0x00); // is boss
+        bytes.extend_from_slice(&[0; 2]); // body
+        bytes.extend_from_slice(&padded::<24>(b"Alice")); // name
+    }
+
+    #[test]
+    fn entity_appeared_guild() {
+        let mut payload = Vec::new();
+        common_fields(&mut payload);
+        payload.extend_from_slice(&[0; 10]); // accessory 2 and 3, palettes and head direction
+        payload.extend_from_slice(&[0; 2]); // robe
+        payload.extend_from_slice(&[0x2A, 0x00, 0x00, 0x01]); // guild id
+        payload.extend_from_slice(&[0x03, 0x00]); // emblem version
+        trailing_fields(&mut payload, 3);
+
+        let bytes = with_header(0x09FE, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = EntityAppearedPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.entity_id, EntityId(150000));
+        assert_eq!(packet.guild_id, GuildId(0x0100002A));
+        assert_eq!(packet.emblem_version, EmblemVersion(3));
+        assert_eq!(packet.name, "Alice");
+    }
+
+    #[test]
+    fn moving_entity_appeared_guild() {
+        let mut payload = Vec::new();
+        common_fields(&mut payload);
+        payload.extend_from_slice(&[0; 4]); // move start time
+        payload.extend_from_slice(&[0; 10]); // accessory 2 and 3, palettes and head direction
+        payload.extend_from_slice(&[0; 2]); // robe
+        payload.extend_from_slice(&[0x2A, 0x00, 0x00, 0x01]); // guild id
+        payload.extend_from_slice(&[0x03, 0x00]); // emblem version
+        trailing_fields(&mut payload, 6);
+
+        let bytes = with_header(0x09FD, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = MovingEntityAppearedPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.guild_id, GuildId(0x0100002A));
+        assert_eq!(packet.emblem_version, EmblemVersion(3));
+        assert_eq!(packet.name, "Alice");
+    }
+
+    #[test]
+    fn guild_emblem() {
+        let bytes = [
+            0x52, 0x01, 0x0F, 0x00, 0x2A, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00, 0x78, 0x9C, 0x01,
+        ];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = GuildEmblemPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.guild_id, GuildId(0x0100002A));
+        assert_eq!(packet.emblem_id, 3);
+        assert_eq!(packet.emblem_data, [0x78, 0x9C, 0x01]);
+        assert_eq!(packet.packet_to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn request_guild_emblem() {
+        let packet = RequestGuildEmblemPacket::new(GuildId(0x0100002A));
+
+        assert_eq!(packet.packet_to_bytes().unwrap(), [0x51, 0x01, 0x2A, 0x00, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn no_guild() {
+        assert!(!GuildId(0).is_guild());
+        assert!(GuildId(42).is_guild());
+    }
+}
@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use ragnarok_bytes::{ByteReader, ConversionError, ConversionResult, FromBytes};
 
-use crate::PacketHeader;
+use crate::{ClientPacket, PacketExt, PacketHeader, ServerPacket};
 
 /// Possible results of [`PacketHandler::process_one`].
 pub enum HandlerResult<Output> {
@@ -21,8 +24,27 @@ pub enum HandlerResult<Output> {
 pub struct DuplicateHandlerError {
     /// Header of the packet.
     pub packet_header: PacketHeader,
+    /// Type name of the packet that was registered first.
+    pub first_packet: &'static str,
+    /// Type name of the packet that was registered second and replaced the
+    /// first handler.
+    pub second_packet: &'static str,
 }
 
+impl std::fmt::Display for DuplicateHandlerError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{} and {} both use the packet header 0x{:04X}",
+            self.first_packet, self.second_packet, self.packet_header.0
+        )
+    }
+}
+
+/// Name used in a [`DuplicateHandlerError`] for handlers registered with
+/// [`PacketHandler::register_dynamic`].
+const DYNAMIC_HANDLER_NAME: &str = "dynamic handler";
+
 /// Trait for monitoring the incoming and outgoing packets.
 pub trait PacketCallback: Clone + 'static {
     /// Called by the [`PacketHandler`] when a packet is received.
@@ -56,9 +78,187 @@ pub trait PacketCallback: Clone + 'static {
 #[derive(Debug, Default, Clone)]
 pub struct NoPacketCallback;
 
+/// Number of buckets in the [`PacketTiming`] histogram.
+pub const TIMING_BUCKET_COUNT: usize = 16;
+
+/// Processing time statistics of a single packet type.
+#[derive(Debug, Default, Clone)]
+pub struct PacketTiming {
+    /// Number of processed packets.
+    pub count: u32,
+    /// Time spent processing all packets.
+    pub total: Duration,
+    /// Longest time spent processing a single packet.
+    pub maximum: Duration,
+    /// Histogram of processing times. Bucket `n` counts packets that took
+    /// less than `2^n` microseconds. The last bucket also counts all packets
+    /// that took longer.
+    pub buckets: [u32; TIMING_BUCKET_COUNT],
+}
+
+impl PacketTiming {
+    fn record(&mut self, duration: Duration) {
+        let microseconds = duration.as_micros();
+        let bucket = (u128::BITS - microseconds.leading_zeros()) as usize;
+
+        self.count += 1;
+        self.total += duration;
+        self.maximum = self.maximum.max(duration);
+        self.buckets[bucket.min(TIMING_BUCKET_COUNT - 1)] += 1;
+    }
+
+    /// Average time spent processing a single packet.
+    pub fn average(&self) -> Duration {
+        self.total.checked_div(self.count).unwrap_or_default()
+    }
+}
+
 impl PacketCallback for NoPacketCallback {}
 
-pub type HandlerFunction<Output, Meta> = Box<dyn Fn(&mut ByteReader<Meta>) -> ConversionResult<Output>>;
+/// Direction of a packet as seen from the client. Used by the
+/// [`RingBufferPacketCallback`] and the [`PacketDirectionRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// The packet is sent by the server and received by the client.
+    Incoming,
+    /// The packet is sent by the client and received by the server.
+    Outgoing,
+}
+
+/// A packet recorded by the [`RingBufferPacketCallback`].
+#[derive(Debug, Clone)]
+pub struct RecordedPacket {
+    /// Header of the packet. `None` if an unknown or failed packet was too
+    /// short to contain a header.
+    pub header: Option<PacketHeader>,
+    pub direction: PacketDirection,
+    /// The bytes of the packet, including the header. For unknown and failed
+    /// packets these are all remaining bytes of the stream, since the length
+    /// of the packet can't be known.
+    pub bytes: Vec<u8>,
+    pub timestamp: Instant,
+}
+
+/// A [`PacketCallback`] that keeps the most recent packets in a ring buffer.
+/// Clones share the same buffer, so one clone can be passed to the
+/// [`PacketHandler`] while another one is used to inspect the traffic.
+#[derive(Debug, Clone)]
+pub struct RingBufferPacketCallback {
+    capacity: usize,
+    packets: Arc<Mutex<VecDeque<RecordedPacket>>>,
+}
+
+impl RingBufferPacketCallback {
+    /// Creates a new callback that keeps at most `capacity` packets.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            packets: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// Returns a copy of the recorded packets, from oldest to newest.
+    pub fn snapshot(&self) -> Vec<RecordedPacket> {
+        self.packets.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Removes all recorded packets.
+    pub fn clear(&self) {
+        self.packets.lock().unwrap().clear();
+    }
+
+    fn record(&self, header: Option<PacketHeader>, direction: PacketDirection, bytes: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut packets = self.packets.lock().unwrap();
+
+        if packets.len() == self.capacity {
+            packets.pop_front();
+        }
+
+        packets.push_back(RecordedPacket {
+            header,
+            direction,
+            bytes,
+            timestamp: Instant::now(),
+        });
+    }
+
+    fn record_packet<Packet>(&self, packet: &Packet, direction: PacketDirection)
+    where
+        Packet: ragnarok_packets::Packet,
+    {
+        // Packets that parsed successfully can also be serialized, so this
+        // should never fail.
+        let bytes = packet.packet_to_bytes().unwrap_or_default();
+        self.record(Some(Packet::HEADER), direction, bytes);
+    }
+
+    fn record_raw(&self, bytes: Vec<u8>) {
+        let header = PacketHeader::from_bytes(&mut ByteReader::without_metadata(&bytes)).ok();
+        self.record(header, PacketDirection::Incoming, bytes);
+    }
+}
+
+impl PacketCallback for RingBufferPacketCallback {
+    fn incoming_packet<Packet>(&self, packet: &Packet)
+    where
+        Packet: ragnarok_packets::Packet,
+    {
+        self.record_packet(packet, PacketDirection::Incoming);
+    }
+
+    fn outgoing_packet<Packet>(&self, packet: &Packet)
+    where
+        Packet: ragnarok_packets::Packet,
+    {
+        self.record_packet(packet, PacketDirection::Outgoing);
+    }
+
+    fn unknown_packet(&self, bytes: Vec<u8>) {
+        self.record_raw(bytes);
+    }
+
+    fn failed_packet(&self, bytes: Vec<u8>, _error: Box<ConversionError>) {
+        self.record_raw(bytes);
+    }
+}
+
+/// Maps packet headers to their [`PacketDirection`], based on the
+/// [`ClientPacket`] and [`ServerPacket`] marker traits. Useful for tooling
+/// that sees traffic in both directions, like a proxy.
+#[derive(Debug, Default, Clone)]
+pub struct PacketDirectionRegistry {
+    directions: HashMap<PacketHeader, PacketDirection>,
+}
+
+impl PacketDirectionRegistry {
+    /// Register a packet that is sent by the client.
+    pub fn register_client<Packet>(&mut self)
+    where
+        Packet: ClientPacket,
+    {
+        self.directions.insert(Packet::HEADER, PacketDirection::Outgoing);
+    }
+
+    /// Register a packet that is sent by the server.
+    pub fn register_server<Packet>(&mut self)
+    where
+        Packet: ServerPacket,
+    {
+        self.directions.insert(Packet::HEADER, PacketDirection::Incoming);
+    }
+
+    /// Returns the direction of the packet with the given header, or `None` if
+    /// no packet with that header was registered.
+    pub fn packet_direction(&self, header: PacketHeader) -> Option<PacketDirection> {
+        self.directions.get(&header).copied()
+    }
+}
+
+pub type HandlerFunction<Output, Meta> = Box<dyn FnMut(&mut ByteReader<Meta>) -> ConversionResult<Output>>;
 
 /// A struct to help with reading packets from a [`ByteReader`] and
 /// converting them to some common event type.
@@ -69,7 +269,10 @@ where
     Meta: 'static,
 {
     handlers: HashMap<PacketHeader, HandlerFunction<Output, Meta>>,
+    /// Type names of the registered packets, for reporting duplicates.
+    packet_names: HashMap<PacketHeader, &'static str>,
     packet_callback: Callback,
+    timings: Option<HashMap<PacketHeader, PacketTiming>>,
 }
 
 impl<Output, Meta, Callback> Default for PacketHandler<Output, Meta, Callback>
@@ -80,7 +283,9 @@ where
     fn default() -> Self {
         Self {
             handlers: Default::default(),
+            packet_names: Default::default(),
             packet_callback: Default::default(),
+            timings: None,
         }
     }
 }
@@ -95,11 +300,40 @@ where
     pub fn with_callback(packet_callback: Callback) -> Self {
         Self {
             handlers: Default::default(),
+            packet_names: Default::default(),
             packet_callback,
+            timings: None,
+        }
+    }
+
+    /// Enable or disable recording the processing time of each packet. Timing
+    /// is disabled by default. Disabling it clears all recorded timings.
+    pub fn set_timing_enabled(&mut self, enabled: bool) {
+        match enabled {
+            true => {
+                self.timings.get_or_insert_with(HashMap::new);
+            }
+            false => self.timings = None,
         }
     }
 
-    /// Register a new packet handler.
+    /// Returns the recorded timings of all processed packets, sorted by the
+    /// total time spent processing them. Empty if timing is disabled.
+    pub fn timing_report(&self) -> Vec<(PacketHeader, PacketTiming)> {
+        let mut report: Vec<(PacketHeader, PacketTiming)> = self
+            .timings
+            .iter()
+            .flatten()
+            .map(|(header, timing)| (*header, timing.clone()))
+            .collect();
+
+        report.sort_by_key(|(_, timing)| Reverse(timing.total));
+        report
+    }
+
+    /// Register a new packet handler. Registering two packets with the same
+    /// header returns an error and panics in debug builds, since the second
+    /// handler would silently replace the first one.
     pub fn register<Packet, Return>(&mut self, handler: impl Fn(Packet) -> Return + 'static) -> Result<(), DuplicateHandlerError>
     where
         Packet: ragnarok_packets::Packet,
@@ -117,15 +351,14 @@ where
             }),
         );
 
-        match old_handler.is_some() {
-            true => Err(DuplicateHandlerError {
-                packet_header: Packet::HEADER,
-            }),
-            false => Ok(()),
-        }
+        let result = self.check_duplicate(Packet::HEADER, std::any::type_name::<Packet>(), old_handler.is_some());
+        debug_assert!(result.is_ok(), "{}", result.as_ref().unwrap_err());
+        result
     }
 
-    /// Register a noop packet handler.
+    /// Register a noop packet handler. Like [`register`](Self::register), this
+    /// panics in debug builds if another packet with the same header was
+    /// already registered.
     pub fn register_noop<Packet>(&mut self) -> Result<(), DuplicateHandlerError>
     where
         Packet: ragnarok_packets::Packet,
@@ -142,9 +375,90 @@ where
             }),
         );
 
-        match old_handler.is_some() {
+        let result = self.check_duplicate(Packet::HEADER, std::any::type_name::<Packet>(), old_handler.is_some());
+        debug_assert!(result.is_ok(), "{}", result.as_ref().unwrap_err());
+        result
+    }
+
+    /// Register an additional packet handler for a packet that might already
+    /// have a handler. All handlers are invoked in the order they were
+    /// registered and their outputs are concatenated.
+    pub fn register_additional<Packet, Return>(&mut self, handler: impl Fn(Packet) -> Return + 'static)
+    where
+        Packet: ragnarok_packets::Packet,
+        Return: Into<Output>,
+        Output: Extend<Output> + 'static,
+    {
+        // The incoming packet callback should only be called once per packet.
+        let packet_callback = (!self.handlers.contains_key(&Packet::HEADER)).then(|| self.packet_callback.clone());
+        let additional_handler = move |byte_reader: &mut ByteReader<Meta>| -> ConversionResult<Output> {
+            let packet = Packet::payload_from_bytes(byte_reader)?;
+
+            if let Some(packet_callback) = &packet_callback {
+                packet_callback.incoming_packet(&packet);
+            }
+
+            Ok(handler(packet).into())
+        };
+
+        let combined_handler: HandlerFunction<Output, Meta> = match self.handlers.remove(&Packet::HEADER) {
+            Some(mut previous_handler) => Box::new(move |byte_reader| {
+                let save_point = byte_reader.create_save_point();
+                let mut output = previous_handler(byte_reader)?;
+
+                // Every handler parses the packet on its own, so we need to rewind.
+                byte_reader.restore_save_point(save_point);
+                output.extend(std::iter::once(additional_handler(byte_reader)?));
+
+                Ok(output)
+            }),
+            None => Box::new(additional_handler),
+        };
+
+        self.handlers.insert(Packet::HEADER, combined_handler);
+        self.packet_names.entry(Packet::HEADER).or_insert(std::any::type_name::<Packet>());
+    }
+
+    /// Register a handler that decodes the packet with the given header
+    /// manually, without a type implementing
+    /// [`Packet`](ragnarok_packets::Packet). This is useful for
+    /// experimenting with undocumented packets.
+    ///
+    /// The header is already consumed when the handler is called, so the
+    /// handler must read exactly the remaining bytes of the packet, including
+    /// the packet length for packets with a variable size. Since there is no
+    /// packet type, the [`PacketCallback`] is not notified about packets
+    /// handled this way.
+    pub fn register_dynamic<Return>(
+        &mut self,
+        packet_header: PacketHeader,
+        mut handler: impl FnMut(&mut ByteReader<Meta>) -> ConversionResult<Return> + 'static,
+    ) -> Result<(), DuplicateHandlerError>
+    where
+        Return: Into<Output>,
+    {
+        let old_handler = self
+            .handlers
+            .insert(packet_header, Box::new(move |byte_reader| handler(byte_reader).map(Into::into)));
+
+        self.check_duplicate(packet_header, DYNAMIC_HANDLER_NAME, old_handler.is_some())
+    }
+
+    /// Records the name of a newly registered packet and returns an error if
+    /// it replaced the handler of another packet.
+    fn check_duplicate(
+        &mut self,
+        packet_header: PacketHeader,
+        packet_name: &'static str,
+        replaced_handler: bool,
+    ) -> Result<(), DuplicateHandlerError> {
+        let first_packet = self.packet_names.insert(packet_header, packet_name);
+
+        match replaced_handler {
             true => Err(DuplicateHandlerError {
-                packet_header: Packet::HEADER,
+                packet_header,
+                first_packet: first_packet.unwrap_or(DYNAMIC_HANDLER_NAME),
+                second_packet: packet_name,
             }),
             false => Ok(()),
         }
@@ -160,7 +474,7 @@ where
             return HandlerResult::PacketCutOff;
         };
 
-        let Some(handler) = self.handlers.get(&header) else {
+        let Some(handler) = self.handlers.get_mut(&header) else {
             byte_reader.restore_save_point(save_point);
 
             self.packet_callback.unknown_packet(byte_reader.remaining_bytes());
@@ -168,7 +482,21 @@ where
             return HandlerResult::UnhandledPacket;
         };
 
-        match handler(byte_reader) {
+        let result = match self.timings.as_mut() {
+            Some(timings) => {
+                let start = Instant::now();
+                let result = handler(byte_reader);
+
+                if result.is_ok() {
+                    timings.entry(header).or_default().record(start.elapsed());
+                }
+
+                result
+            }
+            None => handler(byte_reader),
+        };
+
+        match result {
             Ok(output) => HandlerResult::Ok(output),
             // Cut-off packet (probably).
             Err(error) if error.is_byte_reader_too_short() => {
@@ -185,3 +513,183 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ragnarok_bytes::{ByteReader, FromBytes};
+
+    use super::{
+        HandlerResult, NoPacketCallback, PacketCallback, PacketDirection, PacketDirectionRegistry, PacketHandler, RingBufferPacketCallback,
+    };
+    use crate::{Packet, PacketHeader, RequestServerTickPacket, ServerTickPacket};
+
+    #[derive(Default)]
+    struct Ticks(Vec<u32>);
+
+    impl From<u32> for Ticks {
+        fn from(tick: u32) -> Self {
+            Self(vec![tick])
+        }
+    }
+
+    impl Extend<Ticks> for Ticks {
+        fn extend<T: IntoIterator<Item = Ticks>>(&mut self, iter: T) {
+            iter.into_iter().for_each(|ticks| self.0.extend(ticks.0));
+        }
+    }
+
+    #[test]
+    fn additional_handlers_run_in_order() {
+        let mut packet_handler = PacketHandler::<Ticks, (), NoPacketCallback>::default();
+
+        packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0).unwrap();
+        packet_handler.register_additional(|packet: ServerTickPacket| packet.client_tick.0 + 1);
+        packet_handler.register_additional(|packet: ServerTickPacket| packet.client_tick.0 + 2);
+
+        let bytes = [0x7F, 0x00, 0x0A, 0x00, 0x00, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+
+        let HandlerResult::Ok(ticks) = packet_handler.process_one(&mut byte_reader) else {
+            panic!("packet was not handled");
+        };
+
+        assert_eq!(ticks.0, vec![10, 11, 12]);
+        assert!(byte_reader.is_empty());
+    }
+
+    #[test]
+    fn dynamic_handler() {
+        let mut packet_handler = PacketHandler::<Ticks, (), NoPacketCallback>::default();
+        let mut count = 0;
+
+        packet_handler
+            .register_dynamic(PacketHeader(0xFFFF), move |byte_reader| {
+                count += 1;
+                let tick = u16::from_bytes(byte_reader)?;
+                Ok(tick as u32 + count)
+            })
+            .unwrap();
+
+        let bytes = [0xFF, 0xFF, 0x0A, 0x00, 0xFF, 0xFF, 0x0A, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+
+        let HandlerResult::Ok(first) = packet_handler.process_one(&mut byte_reader) else {
+            panic!("packet was not handled");
+        };
+        let HandlerResult::Ok(second) = packet_handler.process_one(&mut byte_reader) else {
+            panic!("packet was not handled");
+        };
+
+        assert_eq!(first.0, vec![11]);
+        assert_eq!(second.0, vec![12]);
+        assert!(byte_reader.is_empty());
+
+        assert!(packet_handler.register_dynamic(PacketHeader(0xFFFF), |_| Ok(0)).is_err());
+    }
+
+    #[test]
+    fn duplicate_handler_names() {
+        let mut packet_handler = PacketHandler::<Ticks, (), NoPacketCallback>::default();
+
+        packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0).unwrap();
+
+        let error = packet_handler
+            .register_dynamic(ServerTickPacket::HEADER, u32::from_bytes)
+            .unwrap_err();
+
+        assert_eq!(error.packet_header, ServerTickPacket::HEADER);
+        assert_eq!(error.first_packet, "ragnarok_packets::ServerTickPacket");
+        assert_eq!(error.second_packet, "dynamic handler");
+        assert_eq!(
+            error.to_string(),
+            "ragnarok_packets::ServerTickPacket and dynamic handler both use the packet header 0x007F"
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "dynamic handler and ragnarok_packets::ServerTickPacket both use the packet header 0x007F")]
+    fn duplicate_handler_panics() {
+        let mut packet_handler = PacketHandler::<Ticks, (), NoPacketCallback>::default();
+
+        packet_handler.register_dynamic(ServerTickPacket::HEADER, u32::from_bytes).unwrap();
+        let _ = packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0);
+    }
+
+    #[test]
+    fn timing_report() {
+        let mut packet_handler = PacketHandler::<Ticks, (), NoPacketCallback>::default();
+
+        packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0).unwrap();
+
+        let bytes = [0x7F, 0x00, 0x0A, 0x00, 0x00, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let _ = packet_handler.process_one(&mut byte_reader);
+
+        assert!(packet_handler.timing_report().is_empty());
+
+        packet_handler.set_timing_enabled(true);
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let _ = packet_handler.process_one(&mut byte_reader);
+
+        let report = packet_handler.timing_report();
+
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, ServerTickPacket::HEADER);
+        assert_eq!(report[0].1.count, 1);
+        assert_eq!(report[0].1.buckets.iter().sum::<u32>(), 1);
+    }
+
+    #[test]
+    fn ring_buffer_callback() {
+        let packet_callback = RingBufferPacketCallback::new(2);
+        let mut packet_handler = PacketHandler::<Ticks, (), RingBufferPacketCallback>::with_callback(packet_callback.clone());
+
+        packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0).unwrap();
+
+        let bytes = [0x7F, 0x00, 0x0A, 0x00, 0x00, 0x00];
+        let _ = packet_handler.process_one(&mut ByteReader::without_metadata(&bytes));
+
+        let unknown_bytes = [0xFF, 0xFF, 0x01];
+        let _ = packet_handler.process_one(&mut ByteReader::without_metadata(&unknown_bytes));
+
+        let snapshot = packet_callback.snapshot();
+
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].header, Some(ServerTickPacket::HEADER));
+        assert_eq!(snapshot[0].direction, PacketDirection::Incoming);
+        assert_eq!(snapshot[0].bytes, bytes);
+        assert_eq!(snapshot[1].header, Some(PacketHeader(0xFFFF)));
+        assert_eq!(snapshot[1].bytes, unknown_bytes);
+
+        // The oldest packet is dropped once the buffer is full.
+        packet_callback.outgoing_packet(&RequestServerTickPacket::new(crate::ClientTick(20)));
+
+        let snapshot = packet_callback.snapshot();
+
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].header, Some(PacketHeader(0xFFFF)));
+        assert_eq!(snapshot[1].header, Some(RequestServerTickPacket::HEADER));
+        assert_eq!(snapshot[1].direction, PacketDirection::Outgoing);
+        assert!(snapshot[0].timestamp <= snapshot[1].timestamp);
+    }
+
+    #[test]
+    fn packet_direction_registry() {
+        let mut registry = PacketDirectionRegistry::default();
+
+        registry.register_client::<RequestServerTickPacket>();
+        registry.register_server::<ServerTickPacket>();
+
+        assert_eq!(
+            registry.packet_direction(RequestServerTickPacket::HEADER),
+            Some(PacketDirection::Outgoing)
+        );
+        assert_eq!(
+            registry.packet_direction(ServerTickPacket::HEADER),
+            Some(PacketDirection::Incoming)
+        );
+        assert_eq!(registry.packet_direction(PacketHeader(0xFFFF)), None);
+    }
+}
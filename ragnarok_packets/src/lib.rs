@@ -1,17 +1,41 @@
+mod character;
+mod color_code;
+mod combat;
+mod dialog;
+mod effect;
+#[cfg(test)]
+mod fixture;
+mod guild;
 pub mod handler;
+mod mail;
+mod party;
+mod pincode;
 mod position;
+mod quest;
+mod status;
+mod storage;
+mod vending;
 
 use std::net::Ipv4Addr;
 
-use ragnarok_bytes::{
-    ByteConvertable, ByteReader, ConversionError, ConversionResult, ConversionResultExt, FixedByteSize, FromBytes, ToBytes,
-};
+use ragnarok_bytes::{ByteConvertable, ByteReader, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes};
 #[cfg(feature = "derive")]
 pub use ragnarok_procedural::{CharacterServer, ClientPacket, LoginServer, MapServer, Packet, ServerPacket};
 #[cfg(not(feature = "derive"))]
 use ragnarok_procedural::{CharacterServer, ClientPacket, LoginServer, MapServer, Packet, ServerPacket};
 
+pub use self::character::CharacterSlotInformation;
+pub use self::color_code::split_color_codes;
+pub use self::combat::CombatResult;
+pub use self::dialog::DialogSpan;
+pub use self::effect::EffectId;
+pub use self::guild::{EmblemVersion, GuildId};
+pub use self::mail::{MailFlags, MailId};
+pub use self::pincode::encrypt_pincode;
 pub use self::position::{Direction, WorldPosition, WorldPosition2};
+pub use self::quest::{HuntingQuestList, LegacyQuestList, Quest, QuestDetails, QuestList, QuestObjectiveLayout};
+pub use self::status::StatusType;
+pub use self::storage::StorageIndex;
 
 // To make proc macros work in korangar_interface.
 extern crate self as ragnarok_packets;
@@ -142,6 +166,37 @@ pub struct ShopId(pub u32);
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct Price(pub u32);
 
+/// The attack range of an entity, as sent by the server.
+#[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct AttackRange(pub u16);
+
+impl AttackRange {
+    /// The attack range in tiles. The server measures the attack range in
+    /// tiles already, so this is the raw value.
+    pub fn in_tiles(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Checks if the target is within attack range of the source. Like the
+    /// server, this uses the square area around the source, so diagonal
+    /// tiles count as a distance of one.
+    pub fn is_in_range(self, source: TilePosition, target: TilePosition) -> bool {
+        let distance = source.x.abs_diff(target.x).max(source.y.abs_diff(target.y));
+        distance as usize <= self.in_tiles()
+    }
+}
+
+/// The movement speed of a character if no status changes it, in milliseconds
+/// per tile.
+pub const DEFAULT_MOVEMENT_SPEED: u16 = 150;
+
+/// Converts a movement speed as sent by the server, which is the time in
+/// milliseconds it takes to walk a single (straight) tile, to tiles per second.
+pub fn movement_speed_to_tiles_per_second(movement_speed: u16) -> f32 {
+    1000.0 / movement_speed.max(1) as f32
+}
+
 #[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ServerAddress(pub [u8; 4]);
@@ -174,8 +229,7 @@ pub struct LargeTilePosition {
     pub y: u32,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
-#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
 pub struct ColorBGRA {
     pub blue: u8,
     pub green: u8,
@@ -183,8 +237,22 @@ pub struct ColorBGRA {
     pub alpha: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
-#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[cfg(feature = "interface")]
+impl ColorBGRA {
+    /// Converts the color to the color type of the interface.
+    pub fn to_interface_color<Color: korangar_interface::application::ColorTrait>(&self) -> Color {
+        Color::rgba_u8(self.red, self.green, self.blue, self.alpha)
+    }
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for ColorBGRA {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        color_element(display, self.red, self.green, self.blue, self.alpha)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
 pub struct ColorRGBA {
     pub red: u8,
     pub green: u8,
@@ -192,6 +260,37 @@ pub struct ColorRGBA {
     pub alpha: u8,
 }
 
+#[cfg(feature = "interface")]
+impl ColorRGBA {
+    /// Converts the color to the color type of the interface.
+    pub fn to_interface_color<Color: korangar_interface::application::ColorTrait>(&self) -> Color {
+        Color::rgba_u8(self.red, self.green, self.blue, self.alpha)
+    }
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for ColorRGBA {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        color_element(display, self.red, self.green, self.blue, self.alpha)
+    }
+}
+
+/// Displays a packet color as a swatch rather than its individual components.
+#[cfg(feature = "interface")]
+fn color_element<App: korangar_interface::application::Application>(
+    display: String,
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8,
+) -> korangar_interface::elements::ElementCell<App> {
+    use korangar_interface::elements::{ColorValue, Container, ElementWrap, StaticLabel};
+
+    let elements = vec![StaticLabel::new(display).wrap(), ColorValue::new(red, green, blue, alpha).wrap()];
+
+    Container::new(elements).wrap()
+}
+
 /// Item index is always actual index + 2.
 #[derive(Clone, Copy, Debug, FixedByteSize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
@@ -199,13 +298,37 @@ pub struct InventoryIndex(pub u16);
 
 impl FromBytes for InventoryIndex {
     fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
-        u16::from_bytes(byte_reader).map(|raw| Self(raw - 2))
+        let raw = u16::from_bytes(byte_reader)?;
+        ItemListIndex(raw)
+            .inventory_index()
+            .ok_or_else(|| ConversionError::from_message(format!("invalid inventory index {raw}")))
     }
 }
 
 impl ToBytes for InventoryIndex {
     fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        u16::to_bytes(&(self.0 + 2))
+        let raw = self
+            .0
+            .checked_add(2)
+            .ok_or_else(|| ConversionError::from_message(format!("inventory index {} is out of range", self.0)))?;
+        u16::to_bytes(&raw)
+    }
+}
+
+/// Index of an item in an item list. The storage uses the same item lists as
+/// the inventory, but its index is only offset by 1, so the index can only be
+/// resolved once the type of the list is known.
+#[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ItemListIndex(pub u16);
+
+impl ItemListIndex {
+    pub fn inventory_index(self) -> Option<InventoryIndex> {
+        self.0.checked_sub(2).map(InventoryIndex)
+    }
+
+    pub fn storage_index(self) -> Option<StorageIndex> {
+        self.0.checked_sub(1).map(StorageIndex)
     }
 }
 
@@ -285,14 +408,29 @@ pub struct CharacterServerLoginSuccessPacket {
     pub unused: [u8; 20],
 }
 
+/// Sent by the character server to provide the characters associated with the
+/// account. Sent to clients since 2010-04-13, which receive slot information
+/// in front of the characters.
 #[derive(Debug, Clone, Packet, ServerPacket, CharacterServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x006B)]
 #[variable_length]
 pub struct CharacterListPacket {
-    pub maximum_slot_count: u8,
-    pub available_slot_count: u8,
-    pub vip_slot_count: u8,
+    pub slot_information: CharacterSlotInformation,
+    #[new_default]
+    pub unknown: [u8; 20],
+    #[repeating_remaining]
+    pub character_information: Vec<CharacterInformation>,
+}
+
+/// Sent instead of [`CharacterListPacket`] to clients older than 2010-04-13,
+/// which don't receive any slot information. Both packets share the same
+/// header, so the layout has to be chosen based on the version of the client.
+#[derive(Debug, Clone, Packet, ServerPacket, CharacterServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x006B)]
+#[variable_length]
+pub struct LegacyCharacterListPacket {
     #[new_default]
     pub unknown: [u8; 20],
     #[repeating_remaining]
@@ -332,6 +470,18 @@ pub struct LoginPincodePacket {
     pub state: u16,
 }
 
+/// Sent by the client to the character server to submit the pincode after
+/// receiving a [LoginPincodePacket]. The pincode needs to be encrypted with
+/// [`encrypt_pincode`].
+#[derive(Debug, Clone, Packet, ClientPacket, CharacterServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x08B8)]
+pub struct PincodeSubmitPacket {
+    pub account_id: AccountId,
+    #[length(4)]
+    pub pincode: String,
+}
+
 #[derive(Debug, Clone, Packet, ServerPacket, CharacterServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x0B18)]
@@ -465,6 +615,27 @@ pub struct LoginServerKeepalivePacket {
     pub user_id: UserId,
 }
 
+/// The type of a character server, shown on the server selection. Types that
+/// are not known to the client are preserved as [`Unknown`](Self::Unknown).
+#[derive(Copy, Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
+#[numeric_type(u16)]
+#[fallback(Unknown)]
+pub enum ServerType {
+    Normal,
+    Maintenance,
+    Over18,
+    Paying,
+    FreeToPlay,
+    Unknown(u16),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for ServerType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct CharacterServerInformation {
@@ -473,12 +644,19 @@ pub struct CharacterServerInformation {
     #[length(20)]
     pub server_name: String,
     pub user_count: u16,
-    pub server_type: u16, // ServerType
-    pub display_new: u16, // bool16 ?
+    pub server_type: ServerType,
+    pub display_new: u16,
     #[new_value([0; 128])]
     pub unknown: [u8; 128],
 }
 
+impl CharacterServerInformation {
+    /// Whether the server should be marked as new on the server selection.
+    pub fn is_new(&self) -> bool {
+        self.display_new != 0
+    }
+}
+
 /// Sent by the client to the character server after after successfully logging
 /// into the login server.
 /// Attempts to log into the character server using the provided information.
@@ -536,7 +714,7 @@ pub struct CreateCharacterPacket {
     pub sex: Sex,
 }
 
-#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
+#[derive(Debug, Clone, PartialEq, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct CharacterInformation {
     pub character_id: CharacterId,
@@ -912,12 +1090,102 @@ impl ToBytes for RegularItemFlags {
     }
 }
 
+/// The type of an item as sent by the server. Unknown values are preserved so
+/// they can be re-encoded without losing information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemType {
+    Healing,
+    Usable,
+    Etc,
+    Armor,
+    Weapon,
+    Card,
+    PetEgg,
+    PetArmor,
+    Ammo,
+    DelayConsume,
+    ShadowGear,
+    Cash,
+    Unknown(u8),
+}
+
+impl ItemType {
+    pub fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => Self::Healing,
+            2 => Self::Usable,
+            3 => Self::Etc,
+            4 => Self::Armor,
+            5 => Self::Weapon,
+            6 => Self::Card,
+            7 => Self::PetEgg,
+            8 => Self::PetArmor,
+            10 => Self::Ammo,
+            11 => Self::DelayConsume,
+            12 => Self::ShadowGear,
+            18 => Self::Cash,
+            other => Self::Unknown(other),
+        }
+    }
+
+    pub fn to_raw(self) -> u8 {
+        match self {
+            Self::Healing => 0,
+            Self::Usable => 2,
+            Self::Etc => 3,
+            Self::Armor => 4,
+            Self::Weapon => 5,
+            Self::Card => 6,
+            Self::PetEgg => 7,
+            Self::PetArmor => 8,
+            Self::Ammo => 10,
+            Self::DelayConsume => 11,
+            Self::ShadowGear => 12,
+            Self::Cash => 18,
+            Self::Unknown(raw) => raw,
+        }
+    }
+
+    pub fn is_equippable(self) -> bool {
+        matches!(self, Self::Armor | Self::Weapon | Self::PetArmor | Self::ShadowGear)
+    }
+
+    pub fn is_consumable(self) -> bool {
+        matches!(self, Self::Healing | Self::Usable | Self::DelayConsume | Self::Cash)
+    }
+}
+
+impl FixedByteSize for ItemType {
+    fn size_in_bytes() -> usize {
+        u8::size_in_bytes()
+    }
+}
+
+impl FromBytes for ItemType {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        u8::from_bytes(byte_reader).map(Self::from_raw)
+    }
+}
+
+impl ToBytes for ItemType {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.to_raw().to_bytes()
+    }
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for ItemType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct RegularItemInformation {
-    pub index: InventoryIndex,
+    pub index: ItemListIndex,
     pub item_id: ItemId,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub amount: u16,
     pub equipped_position: EquipPosition,
     pub slot: [u32; 4], // card ?
@@ -966,9 +1234,9 @@ impl ToBytes for EquippableItemFlags {
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct EquippableItemInformation {
-    pub index: InventoryIndex,
+    pub index: ItemListIndex,
     pub item_id: ItemId,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub equip_position: EquipPosition,
     pub equipped_position: EquipPosition,
     pub slot: [u32; 4], // card ?
@@ -992,11 +1260,44 @@ pub struct EquippableItemListPacket {
     pub item_information: Vec<EquippableItemInformation>,
 }
 
+/// Layout of [`EquippableItemInformation`] used by clients older than
+/// 2020-07-24, which don't send the enchantment level of the item.
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct LegacyEquippableItemInformation {
+    pub index: ItemListIndex,
+    pub item_id: ItemId,
+    pub item_type: ItemType,
+    pub equip_position: EquipPosition,
+    pub equipped_position: EquipPosition,
+    pub slot: [u32; 4], // card ?
+    pub hire_expiration_date: u32,
+    pub bind_on_equip_type: u16,
+    pub w_item_sprite_number: u16,
+    pub option_count: u8,
+    pub option_data: [ItemOptions; 5], // fix count
+    pub refinement_level: u8,
+    pub flags: EquippableItemFlags,
+}
+
+/// Sent instead of [`EquippableItemListPacket`] to clients older than
+/// 2020-07-24. Both packets share the same header, so the layout has to be
+/// chosen based on the version of the client.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0B39)]
+#[variable_length]
+pub struct LegacyEquippableItemListPacket {
+    pub inventory_type: u8,
+    #[repeating_remaining]
+    pub item_information: Vec<LegacyEquippableItemInformation>,
+}
+
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct EquippableSwitchItemInformation {
     pub index: InventoryIndex,
-    pub position: u32,
+    pub position: EquipPosition,
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
@@ -1013,7 +1314,61 @@ pub struct EquippableSwitchItemListPacket {
 #[header(0x099B)]
 pub struct MapTypePacket {
     pub map_type: u16,
-    pub flags: u32,
+    pub flags: MapFlags,
+}
+
+bitflags::bitflags! {
+    /// Properties of the current map (called "mapproperty" by rAthena). Bits
+    /// that are not known to Korangar are preserved when reading and writing.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    pub struct MapFlags: u32 {
+        /// Attacking non-party members is allowed (PvP).
+        const PARTY = 1;
+        /// Attacking non-guild members is allowed (GvG).
+        const GUILD = 2;
+        /// The map is a siege map and guild emblems are shown over the
+        /// characters.
+        const SIEGE = 4;
+        /// Effects should be reduced automatically.
+        const MINIMAL_EFFECTS = 8;
+        const NO_LOCK_ON = 16;
+        /// The PK counter should be shown.
+        const COUNT_PK = 32;
+        /// Parties can't be created or modified.
+        const NO_PARTY_FORMATION = 64;
+        const BATTLEGROUND = 128;
+        /// Costume sprites are not shown.
+        const NO_COSTUME = 256;
+        /// The cart inventory can be opened.
+        const USE_CART = 512;
+        const SUMMON_STAR_MIRACLE = 1024;
+    }
+}
+
+impl MapFlags {
+    /// Returns `true` if players can attack each other on this map.
+    pub fn is_pk(&self) -> bool {
+        self.intersects(Self::PARTY | Self::GUILD | Self::SIEGE)
+    }
+}
+
+impl FixedByteSize for MapFlags {
+    fn size_in_bytes() -> usize {
+        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
+    }
+}
+
+impl FromBytes for MapFlags {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        <Self as bitflags::Flags>::Bits::from_bytes(byte_reader).map(Self::from_bits_retain)
+    }
+}
+
+impl ToBytes for MapFlags {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.bits().to_bytes()
+    }
 }
 
 /// Sent by the map server to the client when there is a new chat message from
@@ -1079,169 +1434,6 @@ pub struct DisplayEmotionPacket {
     pub emotion: u8,
 }
 
-/// Every value that can be set from the server through [UpdateStatusPacket],
-/// [UpdateStatusPacket1], [UpdateStatusPacket2], and [UpdateStatusPacket3].
-/// All UpdateStatusPackets do the same, they just have different sizes
-/// correlating to the space the updated value requires.
-#[derive(Debug, Clone)]
-pub enum StatusType {
-    Weight(u32),
-    MaximumWeight(u32),
-    MovementSpeed(u32),
-    BaseLevel(u32),
-    JobLevel(u32),
-    Karma(u32),
-    Manner(u32),
-    StatusPoint(u32),
-    SkillPoint(u32),
-    Hit(u32),
-    Flee1(u32),
-    Flee2(u32),
-    MaximumHealthPoints(u32),
-    MaximumSpellPoints(u32),
-    HealthPoints(u32),
-    SpellPoints(u32),
-    AttackSpeed(u32),
-    Attack1(u32),
-    Defense1(u32),
-    MagicDefense1(u32),
-    Attack2(u32),
-    Defense2(u32),
-    MagicDefense2(u32),
-    Critical(u32),
-    MagicAttack1(u32),
-    MagicAttack2(u32),
-    Zeny(u32),
-    BaseExperience(u64),
-    JobExperience(u64),
-    NextBaseExperience(u64),
-    NextJobExperience(u64),
-    SpUstr(u8),
-    SpUagi(u8),
-    SpUvit(u8),
-    SpUint(u8),
-    SpUdex(u8),
-    SpUluk(u8),
-    Strength(u32, u32),
-    Agility(u32, u32),
-    Vitality(u32, u32),
-    Intelligence(u32, u32),
-    Dexterity(u32, u32),
-    Luck(u32, u32),
-    CartInfo(u16, u32, u32),
-    ActivityPoints(u32),
-    TraitPoint(u32),
-    MaximumActivityPoints(u32),
-    Power(u32, u32),
-    Stamina(u32, u32),
-    Wisdom(u32, u32),
-    Spell(u32, u32),
-    Concentration(u32, u32),
-    Creativity(u32, u32),
-    SpUpow(u8),
-    SpUsta(u8),
-    SpUwis(u8),
-    SpUspl(u8),
-    SpUcon(u8),
-    SpUcrt(u8),
-    PhysicalAttack(u32),
-    SpellMagicAttack(u32),
-    Resistance(u32),
-    MagicResistance(u32),
-    HealingPlus(u32),
-    CriticalDamageRate(u32),
-}
-
-impl FromBytes for StatusType {
-    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
-        let status = match u16::from_bytes(byte_reader).trace::<Self>()? {
-            0 => u32::from_bytes(byte_reader).map(Self::MovementSpeed),
-            1 => u64::from_bytes(byte_reader).map(Self::BaseExperience),
-            2 => u64::from_bytes(byte_reader).map(Self::JobExperience),
-            3 => u32::from_bytes(byte_reader).map(Self::Karma),
-            4 => u32::from_bytes(byte_reader).map(Self::Manner),
-            5 => u32::from_bytes(byte_reader).map(Self::HealthPoints),
-            6 => u32::from_bytes(byte_reader).map(Self::MaximumHealthPoints),
-            7 => u32::from_bytes(byte_reader).map(Self::SpellPoints),
-            8 => u32::from_bytes(byte_reader).map(Self::MaximumSpellPoints),
-            9 => u32::from_bytes(byte_reader).map(Self::StatusPoint),
-            11 => u32::from_bytes(byte_reader).map(Self::BaseLevel),
-            12 => u32::from_bytes(byte_reader).map(Self::SkillPoint),
-            13 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Strength(a, u32::from_bytes(byte_reader)?))),
-            14 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Agility(a, u32::from_bytes(byte_reader)?))),
-            15 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Vitality(a, u32::from_bytes(byte_reader)?))),
-            16 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Intelligence(a, u32::from_bytes(byte_reader)?))),
-            17 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Dexterity(a, u32::from_bytes(byte_reader)?))),
-            18 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Luck(a, u32::from_bytes(byte_reader)?))),
-            20 => u32::from_bytes(byte_reader).map(Self::Zeny),
-            22 => u64::from_bytes(byte_reader).map(Self::NextBaseExperience),
-            23 => u64::from_bytes(byte_reader).map(Self::NextJobExperience),
-            24 => u32::from_bytes(byte_reader).map(Self::Weight),
-            25 => u32::from_bytes(byte_reader).map(Self::MaximumWeight),
-            32 => u8::from_bytes(byte_reader).map(Self::SpUstr),
-            33 => u8::from_bytes(byte_reader).map(Self::SpUagi),
-            34 => u8::from_bytes(byte_reader).map(Self::SpUvit),
-            35 => u8::from_bytes(byte_reader).map(Self::SpUint),
-            36 => u8::from_bytes(byte_reader).map(Self::SpUdex),
-            37 => u8::from_bytes(byte_reader).map(Self::SpUluk),
-            41 => u32::from_bytes(byte_reader).map(Self::Attack1),
-            42 => u32::from_bytes(byte_reader).map(Self::Attack2),
-            43 => u32::from_bytes(byte_reader).map(Self::MagicAttack1),
-            44 => u32::from_bytes(byte_reader).map(Self::MagicAttack2),
-            45 => u32::from_bytes(byte_reader).map(Self::Defense1),
-            46 => u32::from_bytes(byte_reader).map(Self::Defense2),
-            47 => u32::from_bytes(byte_reader).map(Self::MagicDefense1),
-            48 => u32::from_bytes(byte_reader).map(Self::MagicDefense2),
-            49 => u32::from_bytes(byte_reader).map(Self::Hit),
-            50 => u32::from_bytes(byte_reader).map(Self::Flee1),
-            51 => u32::from_bytes(byte_reader).map(Self::Flee2),
-            52 => u32::from_bytes(byte_reader).map(Self::Critical),
-            53 => u32::from_bytes(byte_reader).map(Self::AttackSpeed),
-            55 => u32::from_bytes(byte_reader).map(Self::JobLevel),
-            99 => u16::from_bytes(byte_reader)
-                .and_then(|a| Ok(Self::CartInfo(a, u32::from_bytes(byte_reader)?, u32::from_bytes(byte_reader)?))),
-            219 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Power(a, u32::from_bytes(byte_reader)?))),
-            220 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Stamina(a, u32::from_bytes(byte_reader)?))),
-            221 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Wisdom(a, u32::from_bytes(byte_reader)?))),
-            222 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Spell(a, u32::from_bytes(byte_reader)?))),
-            223 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Concentration(a, u32::from_bytes(byte_reader)?))),
-            224 => u32::from_bytes(byte_reader).and_then(|a| Ok(Self::Creativity(a, u32::from_bytes(byte_reader)?))),
-            225 => u32::from_bytes(byte_reader).map(Self::PhysicalAttack),
-            226 => u32::from_bytes(byte_reader).map(Self::SpellMagicAttack),
-            227 => u32::from_bytes(byte_reader).map(Self::Resistance),
-            228 => u32::from_bytes(byte_reader).map(Self::MagicResistance),
-            229 => u32::from_bytes(byte_reader).map(Self::HealingPlus),
-            230 => u32::from_bytes(byte_reader).map(Self::CriticalDamageRate),
-            231 => u32::from_bytes(byte_reader).map(Self::TraitPoint),
-            232 => u32::from_bytes(byte_reader).map(Self::ActivityPoints),
-            233 => u32::from_bytes(byte_reader).map(Self::MaximumActivityPoints),
-            247 => u8::from_bytes(byte_reader).map(Self::SpUpow),
-            248 => u8::from_bytes(byte_reader).map(Self::SpUsta),
-            249 => u8::from_bytes(byte_reader).map(Self::SpUwis),
-            250 => u8::from_bytes(byte_reader).map(Self::SpUspl),
-            251 => u8::from_bytes(byte_reader).map(Self::SpUcon),
-            252 => u8::from_bytes(byte_reader).map(Self::SpUcrt),
-            invalid => Err(ConversionError::from_message(format!("invalid status code {invalid}"))),
-        };
-
-        status.trace::<Self>()
-    }
-}
-
-impl ToBytes for StatusType {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        panic!("this should be derived");
-    }
-}
-
-// TODO: make StatusType derivable
-#[cfg(feature = "interface")]
-impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for StatusType {
-    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
-        format!("{self:?}").to_element(display)
-    }
-}
-
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x00B0)]
@@ -1259,9 +1451,12 @@ pub struct StatusChangeSequencePacket {
     pub state: u8,
 }
 
-/// Sent by the character server to the client when loading onto a new map.
-/// This packet is ignored by Korangar since all of the provided values are set
-/// again individually using the UpdateStatusPackets.
+/// Sent by the map server to the client when loading onto a new map. Most of
+/// the provided values are sent again individually using the
+/// UpdateStatusPackets.
+///
+/// The left side values are the ones shown on the left of the `+` in the
+/// status window, the right side values the ones shown on the right of it.
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x00BD)]
@@ -1271,8 +1466,8 @@ pub struct InitialStatusPacket {
     pub required_strength: u8,
     pub agility: u8,
     pub required_agility: u8,
-    pub vitatity: u8,
-    pub required_vitatity: u8,
+    pub vitality: u8,
+    pub required_vitality: u8,
     pub intelligence: u8,
     pub required_intelligence: u8,
     pub dexterity: u8,
@@ -1280,17 +1475,17 @@ pub struct InitialStatusPacket {
     pub luck: u8,
     pub required_luck: u8,
     pub left_attack: u16,
-    pub rigth_attack: u16,
-    pub rigth_magic_attack: u16,
+    pub right_attack: u16,
+    pub right_magic_attack: u16,
     pub left_magic_attack: u16,
     pub left_defense: u16,
-    pub rigth_defense: u16,
-    pub rigth_magic_defense: u16,
+    pub right_defense: u16,
     pub left_magic_defense: u16,
-    pub hit: u16, // ?
+    pub right_magic_defense: u16,
+    pub hit: u16,
     pub flee: u16,
     pub flee2: u16,
-    pub crit: u16,
+    pub critical: u16,
     pub attack_speed: u16,
     /// Always 0 on rAthena
     #[new_default]
@@ -1325,7 +1520,7 @@ pub struct UpdateStatusPacket3 {
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x013A)]
 pub struct UpdateAttackRangePacket {
-    pub attack_range: u16,
+    pub attack_range: AttackRange,
 }
 
 #[derive(Debug, Clone, Packet, ClientPacket, CharacterServer)]
@@ -1391,7 +1586,7 @@ pub struct UpdateEntityHealthPointsPacket {
 }
 
 #[derive(Debug, Clone, ByteConvertable)]
-#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[fallback(Unknown)]
 pub enum DamageType {
     Damage,
     PickUpItem,
@@ -1407,6 +1602,14 @@ pub enum DamageType {
     LuckyDodge,
     TouchSkill, // Not confirmed
     CriticalMultiHit,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for DamageType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
@@ -1503,6 +1706,71 @@ pub struct EntityDisappearedPacket {
     pub reason: DisappearanceReason,
 }
 
+bitflags::bitflags! {
+    /// Visual status flags of an entity (called "option" by rAthena). Bits that
+    /// are not known to Korangar are preserved when reading and writing.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    pub struct EffectState: u32 {
+        const NONE = 0;
+        const SIGHT = 1;
+        const HIDE = 2;
+        const CLOAK = 4;
+        const CART1 = 8;
+        const FALCON = 16;
+        const RIDING = 32;
+        const INVISIBLE = 64;
+        const CART2 = 128;
+        const CART3 = 256;
+        const CART4 = 512;
+        const CART5 = 1024;
+        const ORCISH = 2048;
+        const WEDDING = 4096;
+        const RUWACH = 8192;
+        const CHASE_WALK = 16384;
+        const FLYING = 32768;
+        const XMAS = 65536;
+        const TRANSFORM = 131072;
+        const SUMMER = 262144;
+        const DRAGON1 = 524288;
+        const WUG = 1048576;
+        const WUG_RIDER = 2097152;
+        const MADOGEAR = 4194304;
+        const DRAGON2 = 8388608;
+        const DRAGON3 = 16777216;
+        const DRAGON4 = 33554432;
+        const DRAGON5 = 67108864;
+        const HANBOK = 134217728;
+        const OKTOBERFEST = 268435456;
+        const SUMMER2 = 536870912;
+    }
+}
+
+impl EffectState {
+    /// Returns only the bits that don't correspond to any named flag.
+    pub fn unknown(&self) -> u32 {
+        self.bits() & !Self::all().bits()
+    }
+}
+
+impl FixedByteSize for EffectState {
+    fn size_in_bytes() -> usize {
+        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
+    }
+}
+
+impl FromBytes for EffectState {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        <Self as bitflags::Flags>::Bits::from_bytes(byte_reader).map(Self::from_bits_retain)
+    }
+}
+
+impl ToBytes for EffectState {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.bits().to_bytes()
+    }
+}
+
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x09FD)]
@@ -1514,7 +1782,7 @@ pub struct MovingEntityAppearedPacket {
     pub movement_speed: u16,
     pub body_state: u16,
     pub health_state: u16,
-    pub effect_state: u32,
+    pub effect_state: EffectState,
     pub job: u16,
     pub head: u16,
     pub weapon: u32,
@@ -1527,8 +1795,8 @@ pub struct MovingEntityAppearedPacket {
     pub body_palette: u16,
     pub head_direction: u16,
     pub robe: u16,
-    pub guild_id: u32, // may be reversed - or completely wrong
-    pub emblem_version: u16,
+    pub guild_id: GuildId,
+    pub emblem_version: EmblemVersion,
     pub honor: u16,
     pub virtue: u32,
     pub is_pk_mode_on: u8,
@@ -1567,7 +1835,7 @@ pub struct EntityAppearedPacket {
     pub movement_speed: u16,
     pub body_state: u16,
     pub health_state: u16,
-    pub effect_state: u32,
+    pub effect_state: EffectState,
     pub job: u16,
     pub head: u16,
     pub weapon: u32,
@@ -1579,8 +1847,8 @@ pub struct EntityAppearedPacket {
     pub body_palette: u16,
     pub head_direction: u16,
     pub robe: u16,
-    pub guild_id: u32, // may be reversed - or completely wrong
-    pub emblem_version: u16,
+    pub guild_id: GuildId,
+    pub emblem_version: EmblemVersion,
     pub honor: u16,
     pub virtue: u32,
     pub is_pk_mode_on: u8,
@@ -1609,7 +1877,7 @@ pub struct EntityAppeared2Packet {
     pub movement_speed: u16,
     pub body_state: u16,
     pub health_state: u16,
-    pub effect_state: u32,
+    pub effect_state: EffectState,
     pub job: u16,
     pub head: u16,
     pub weapon: u32,
@@ -1621,8 +1889,8 @@ pub struct EntityAppeared2Packet {
     pub body_palette: u16,
     pub head_direction: u16,
     pub robe: u16,
-    pub guild_id: u32, // may be reversed - or completely wrong
-    pub emblem_version: u16,
+    pub guild_id: GuildId,
+    pub emblem_version: EmblemVersion,
     pub honor: u16,
     pub virtue: u32,
     pub is_pk_mode_on: u8,
@@ -1741,15 +2009,62 @@ pub struct UpdateShowEquipPacket {
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x02D9)]
 pub struct UpdateConfigurationPacket {
-    pub config_type: u32,
-    pub value: u32, // only enabled and disabled ?
+    pub config_type: ConfigurationOption,
+    /// 0 if the option is disabled.
+    pub value: u32,
+}
+
+/// A setting of the player that is stored by the server. Options that are not
+/// known to the client are preserved as [`Unknown`](Self::Unknown).
+#[derive(Copy, Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
+#[numeric_type(u32)]
+#[fallback(Unknown)]
+pub enum ConfigurationOption {
+    /// Other players may look at the equipment of the player.
+    OpenEquipmentWindow,
+    /// Other players may summon the player with skills, for example with the
+    /// marriage skills.
+    Call,
+    PetAutoFeed,
+    HomunculusAutoFeed,
+    Unknown(u32),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for ConfigurationOption {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+/// What a navigation request points to. Types that are not known to the
+/// client are preserved as [`Unknown`](Self::Unknown).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
+#[fallback(Unknown)]
+pub enum NavigationTargetType {
+    /// Navigate to the target position on the target map.
+    Coordinates,
+    /// Same as [`Coordinates`](Self::Coordinates), but fails if the player is
+    /// already on the target map.
+    CoordinatesOnMap,
+    /// Navigate to the monster with the target monster id.
+    #[numeric_value(3)]
+    Entity,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for NavigationTargetType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x08E2)]
 pub struct NavigateToMonsterPacket {
-    pub target_type: u8, // 3 - entity; 0 - coordinates; 1 - coordinates but fails if you're alweady on the map
+    pub target_type: NavigationTargetType,
     pub flags: u8,
     pub hide_window: u8,
     #[length(16)]
@@ -1758,15 +2073,20 @@ pub struct NavigateToMonsterPacket {
     pub target_monster_id: u16,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Copy, Debug, Clone, PartialEq, Eq, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum MarkerType {
+    /// Show the marker for 15 seconds.
     DisplayFor15Seconds,
+    /// Show the marker until the player leaves the map.
     DisplayUntilLeave,
+    /// Remove a marker that was previously placed.
     RemoveMark,
 }
 
+/// Sent by the map server when an NPC places or removes a marker on the
+/// minimap. Markers are identified by the NPC and the marker id.
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x0144)]
@@ -1775,7 +2095,9 @@ pub struct MarkMinimapPositionPacket {
     pub marker_type: MarkerType,
     pub position: LargeTilePosition,
     pub id: u8,
-    pub color: ColorRGBA,
+    /// Sent as a little endian `0xRRGGBB` integer, so the alpha channel is
+    /// always 0.
+    pub color: ColorBGRA,
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
@@ -1807,7 +2129,7 @@ pub struct DialogMenuPacket {
 #[header(0x01F3)]
 pub struct DisplaySpecialEffectPacket {
     pub entity_id: EntityId,
-    pub effect_id: u32,
+    pub effect_id: EffectId,
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
@@ -1815,7 +2137,8 @@ pub struct DisplaySpecialEffectPacket {
 #[header(0x043D)]
 pub struct DisplaySkillCooldownPacket {
     pub skill_id: SkillId,
-    pub until: ClientTick,
+    /// Time until the skill can be used again, in milliseconds.
+    pub delay: u32,
 }
 
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
@@ -1937,42 +2260,41 @@ pub struct QuestRemovedPacket {
     pub quest_id: u32,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+/// The quests of the player. Sent to clients since 2018-10-10, which receive
+/// [`QuestObjectiveLayout::Extended`] objectives.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-pub struct QuestDetails {
-    pub hunt_identification: u32,
-    pub objective_type: u32,
-    pub mob_id: u32,
-    pub minimum_level: u16,
-    pub maximum_level: u16,
-    pub kill_count: u16,
-    pub total_count: u16,
-    #[length(24)]
-    pub mob_name: String,
+#[header(0x09F8)]
+#[variable_length]
+pub struct QuestListPacket {
+    #[length_remaining]
+    pub quest_list: QuestList,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+/// Sent instead of [`QuestListPacket`] to clients from 2015-05-13 up to
+/// 2018-10-10, which receive [`QuestObjectiveLayout::Hunting`] objectives.
+/// All quest list packets share the same header, so the layout has to be
+/// chosen based on the version of the client.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-pub struct Quest {
-    pub quest_id: u32,
-    pub active: u8,
-    pub remaining_time: u32, // TODO: double check these
-    pub expire_time: u32,    // TODO: double check these
-    #[new_derive]
-    pub objective_count: u16,
-    #[repeating(objective_count)]
-    pub objective_details: Vec<QuestDetails>,
+#[header(0x09F8)]
+#[variable_length]
+pub struct HuntingQuestListPacket {
+    #[length_remaining]
+    pub quest_list: HuntingQuestList,
 }
 
+/// Sent instead of [`QuestListPacket`] to clients older than 2015-05-13,
+/// which receive [`QuestObjectiveLayout::Legacy`] objectives. All quest list
+/// packets share the same header, so the layout has to be chosen based on the
+/// version of the client.
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x09F8)]
 #[variable_length]
-pub struct QuestListPacket {
-    #[new_derive]
-    pub quest_count: u32,
-    #[repeating(quest_count)]
-    pub quests: Vec<Quest>,
+pub struct LegacyQuestListPacket {
+    #[length_remaining]
+    pub quest_list: LegacyQuestList,
 }
 
 #[derive(Debug, Clone, ByteConvertable)]
@@ -1999,7 +2321,7 @@ pub struct VisualEffectPacket {
     pub effect: VisualEffect,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum ExperienceType {
@@ -2008,7 +2330,7 @@ pub enum ExperienceType {
     JobExperience,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum ExperienceSource {
@@ -2026,7 +2348,8 @@ pub struct DisplayGainedExperiencePacket {
     pub experience_source: ExperienceSource,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+/// Where an illustration of a [`DisplayImagePacket`] is shown on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum ImageLocation {
     BottomLeft,
@@ -2034,10 +2357,13 @@ pub enum ImageLocation {
     BottomRight,
     MiddleFloating,
     MiddleColorless,
+    /// Removes all illustrations that are currently shown.
     #[numeric_value(255)]
     ClearAll,
 }
 
+/// Sent by NPC scripts to show an illustration, for example during quest
+/// cutscenes.
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x01B3)]
@@ -2054,7 +2380,7 @@ pub struct StateChangePacket {
     pub entity_id: EntityId,
     pub body_state: u16,
     pub health_state: u16,
-    pub effect_state: u32,
+    pub effect_state: EffectState,
     pub is_pk_mode_on: u8,
 }
 
@@ -2082,7 +2408,7 @@ pub struct ItemPickupPacket {
     pub is_broken: u8,
     pub cards: [u32; 4],
     pub equip_position: EquipPosition,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub result: ItemPickupResult,
     pub hire_expiration_date: u32,
     pub bind_on_equip_type: u16,
@@ -2295,10 +2621,65 @@ pub struct RequestUnequipItemPacket {
     pub inventory_index: InventoryIndex,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+/// Sent by the client to the map server to add an item to the equip switch
+/// set.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-pub enum RequestUnequipItemStatus {
-    Success,
+#[header(0x0A97)]
+pub struct RequestEquipSwitchItemPacket {
+    pub inventory_index: InventoryIndex,
+    pub equip_position: EquipPosition,
+}
+
+/// Sent by the map server as a response to [RequestEquipSwitchItemPacket].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A98)]
+pub struct RequestEquipSwitchItemStatusPacket {
+    pub inventory_index: InventoryIndex,
+    pub equip_position: EquipPosition,
+    pub failed: u16,
+}
+
+/// Sent by the client to the map server to remove an item from the equip
+/// switch set.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A99)]
+pub struct RequestUnequipSwitchItemPacket {
+    pub inventory_index: InventoryIndex,
+    pub equip_position: EquipPosition,
+}
+
+/// Sent by the map server as a response to [RequestUnequipSwitchItemPacket].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A9A)]
+pub struct RequestUnequipSwitchItemStatusPacket {
+    pub inventory_index: InventoryIndex,
+    pub equip_position: EquipPosition,
+    pub failed: u16,
+}
+
+/// Sent by the client to the map server to swap the currently equipped items
+/// with the items in the equip switch set.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A9C)]
+pub struct RequestFullEquipSwitchPacket {}
+
+/// Sent by the map server as a response to [RequestFullEquipSwitchPacket].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A9D)]
+pub struct RequestFullEquipSwitchStatusPacket {
+    pub failed: u16,
+}
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum RequestUnequipItemStatus {
+    Success,
     Failed,
 }
 
@@ -2402,6 +2783,32 @@ pub struct EndUseSkillPacket {
     pub skill_id: SkillId,
 }
 
+/// Elemental property of a skill, attack or entity. Elements that are not
+/// known to the client are preserved as [`Unknown`](Self::Unknown).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[numeric_type(u32)]
+#[fallback(Unknown)]
+pub enum Element {
+    Neutral,
+    Water,
+    Earth,
+    Fire,
+    Wind,
+    Poison,
+    Holy,
+    Shadow,
+    Ghost,
+    Undead,
+    Unknown(u32),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for Element {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
 #[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x07FB)]
@@ -2410,7 +2817,7 @@ pub struct UseSkillSuccessPacket {
     pub destination_entity: EntityId,
     pub position: TilePosition,
     pub skill_id: SkillId,
-    pub element: u32,
+    pub element: Element,
     pub delay_time: u32,
     pub disposable: u8,
 }
@@ -2422,8 +2829,62 @@ pub struct ToUseSkillSuccessPacket {
     pub skill_id: SkillId,
     pub btype: i32,
     pub item_id: ItemId,
+    /// 1 if the skill was used successfully, 0 otherwise.
     pub flag: u8,
-    pub cause: u8,
+    pub cause: SkillFailureCause,
+}
+
+/// The reason why a skill couldn't be used. Causes that are not known to the
+/// client are preserved as [`Unknown`](Self::Unknown).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
+#[fallback(Unknown)]
+pub enum SkillFailureCause {
+    Level,
+    InsufficientSpellPoints,
+    InsufficientHealthPoints,
+    InsufficientItems,
+    SkillInterval,
+    InsufficientZeny,
+    WrongWeapon,
+    RedGemstone,
+    BlueGemstone,
+    Overweight,
+    Failed,
+    InvalidTarget,
+    TooManyAncilla,
+    HolyWater,
+    Ancilla,
+    DuplicateInRange,
+    NeedOtherSkill,
+    NeedHelper,
+    InvalidDirection,
+    TooManySummons,
+    NoSummons,
+    NoImitationSkill,
+    Duplicate,
+    Condition,
+    Paintbrush,
+    Dragon,
+    Position,
+    InsufficientHelperSpellPoints,
+    NearWall,
+    NeedOnePercentExperience,
+    InsufficientChorusSpellPoints,
+    WeaponBlocking,
+    PoisoningWeapon,
+    Madogear,
+    NeedKunai,
+    InvalidPlayerTarget,
+    Size,
+    Cannonball,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for SkillFailureCause {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
 }
 
 #[derive(Debug, Clone, ByteConvertable)]
@@ -2764,6 +3225,63 @@ pub struct PartyInvitePacket {
     pub party_name: String,
 }
 
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct PartyMemberInformation {
+    pub account_id: AccountId,
+    pub character_id: CharacterId,
+    #[length(24)]
+    pub name: String,
+    #[length(16)]
+    pub map_name: String,
+    /// 0 if the member is the party leader.
+    pub role: u8,
+    /// 1 if the member is offline.
+    pub offline: u8,
+    pub job: u16,
+    pub base_level: u16,
+}
+
+/// Sent by the map server when joining a party or when the party changes.
+/// Contains the full list of party members.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0AE5)]
+#[variable_length]
+pub struct PartyInformationPacket {
+    #[length(24)]
+    pub party_name: String,
+    #[repeating_remaining]
+    pub members: Vec<PartyMemberInformation>,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0105)]
+pub struct PartyMemberLeftPacket {
+    pub account_id: AccountId,
+    #[length(24)]
+    pub name: String,
+    pub reason: u8,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x080E)]
+pub struct PartyMemberHealthPacket {
+    pub account_id: AccountId,
+    pub health_points: u32,
+    pub maximum_health_points: u32,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0107)]
+pub struct PartyMemberPositionPacket {
+    pub account_id: AccountId,
+    pub position: TilePosition,
+}
+
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ReputationEntry {
@@ -2839,7 +3357,7 @@ pub struct ChangeMapCellPacket {
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct MarketItemInformation {
     pub name_id: u32,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub price: Price,
     pub quantity: u32,
     pub weight: u16,
@@ -2861,7 +3379,7 @@ pub struct ShopItemInformation {
     pub item_id: ItemId,
     pub price: Price,
     pub discount_price: Price,
-    pub item_type: u8,
+    pub item_type: ItemType,
     pub view_sprite: u16,
     pub location: u32,
 }
@@ -2897,13 +3415,13 @@ pub struct SelectBuyOrSellPacket {
     pub option: BuyOrSellOption,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
-#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
+#[fallback(Unknown)]
 pub enum BuyItemResult {
     #[numeric_value(0)]
     Successful,
     #[numeric_value(1)]
-    NotEoughZeny,
+    NotEnoughZeny,
     #[numeric_value(2)]
     WeightLimitExceeded,
     #[numeric_value(3)]
@@ -2920,6 +3438,14 @@ pub enum BuyItemResult {
     ItemSoldOut,
     #[numeric_value(14)]
     NotEnoughGoods,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for BuyItemResult {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
 }
 
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
@@ -2938,6 +3464,14 @@ pub struct BuyItemsPacket {
     pub items: Vec<BuyItemInformation>,
 }
 
+/// Sent by the map server in response to a [`BuyItemsPacket`].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00CA)]
+pub struct BuyItemsResultPacket {
+    pub result: BuyItemResult,
+}
+
 #[derive(Debug, Clone, FixedByteSize, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct BuyShopItemInformation {
@@ -3032,3 +3566,370 @@ pub enum SellItemsResult {
 pub struct SellItemsResultPacket {
     pub result: SellItemsResult,
 }
+
+/// Sent by the client to request the emblem of a guild. The server responds
+/// with a [`GuildEmblemPacket`].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0151)]
+pub struct RequestGuildEmblemPacket {
+    pub guild_id: GuildId,
+}
+
+/// Sent by the map server in response to a [`RequestGuildEmblemPacket`].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0152)]
+#[variable_length]
+pub struct GuildEmblemPacket {
+    pub guild_id: GuildId,
+    pub emblem_id: u32,
+    /// The zlib compressed emblem image, usually a 24x24 bitmap.
+    #[repeating_remaining]
+    pub emblem_data: Vec<u8>,
+}
+
+/// Sent by the map server after the storage item list to tell how many items
+/// are in the storage.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00F2)]
+pub struct StorageItemCountPacket {
+    pub item_count: u16,
+    pub maximum_item_count: u16,
+}
+
+/// Sent by the map server when an item was moved into the storage.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0A0A)]
+pub struct StorageItemAddedPacket {
+    pub index: StorageIndex,
+    pub amount: u32,
+    pub item_id: ItemId,
+    pub item_type: ItemType,
+    pub is_identified: u8,
+    pub is_broken: u8,
+    pub refinement_level: u8,
+    pub cards: [u32; 4],
+    pub option_data: [ItemOptions; 5], // fix count
+    pub grade: u8,
+}
+
+/// Sent by the map server when an item was taken out of the storage.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00F6)]
+pub struct StorageItemRemovedPacket {
+    pub index: StorageIndex,
+    pub amount: u32,
+}
+
+/// Sent by the client to move an item from the inventory into the storage.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0364)]
+pub struct MoveItemToStoragePacket {
+    pub index: InventoryIndex,
+    pub amount: u32,
+}
+
+/// Sent by the client to move an item from the storage into the inventory.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0365)]
+pub struct MoveItemFromStoragePacket {
+    pub index: StorageIndex,
+    pub amount: u32,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00F7)]
+pub struct CloseStoragePacket {}
+
+/// Sent by the map server when the storage was closed, either as a response
+/// to [`CloseStoragePacket`] or because the player moved away.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00F8)]
+pub struct StorageClosedPacket {}
+
+/// Sent by the map server to show the sign of a player that opened a vending
+/// shop.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0131)]
+pub struct VendingShopOpenedPacket {
+    pub account_id: AccountId,
+    #[length(80)]
+    pub title: String,
+}
+
+/// Sent by the map server when a player closed their vending shop.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0132)]
+pub struct VendingShopClosedPacket {
+    pub account_id: AccountId,
+}
+
+/// Sent by the client to look at the items of a vending shop. The server
+/// responds with a [`VendingItemListPacket`].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0130)]
+pub struct RequestVendingItemListPacket {
+    pub account_id: AccountId,
+}
+
+#[derive(Debug, Clone, FixedByteSize, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct VendingItemInformation {
+    pub price: Price,
+    pub amount: u16,
+    /// The index of the item in the cart of the vendor.
+    pub index: InventoryIndex,
+    pub item_type: ItemType,
+    pub item_id: ItemId,
+    pub is_identified: u8,
+    pub is_broken: u8,
+    pub refinement_level: u8,
+    pub cards: [u32; 4],
+    pub option_data: [ItemOptions; 5], // fix count
+    pub location: u32,
+    pub view_sprite: u16,
+    pub grade: u8,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0800)]
+#[variable_length]
+pub struct VendingItemListPacket {
+    pub account_id: AccountId,
+    pub vending_id: u32,
+    #[repeating_remaining]
+    pub items: Vec<VendingItemInformation>,
+}
+
+#[derive(Debug, Clone, FixedByteSize, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct VendingPurchaseInformation {
+    pub amount: u16,
+    pub index: InventoryIndex,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0801)]
+#[variable_length]
+pub struct PurchaseVendingItemsPacket {
+    pub account_id: AccountId,
+    pub vending_id: u32,
+    #[repeating_remaining]
+    pub items: Vec<VendingPurchaseInformation>,
+}
+
+#[derive(Copy, Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
+#[numeric_type(u8)]
+#[fallback(Unknown)]
+pub enum VendingPurchaseResult {
+    Success,
+    NotEnoughZeny,
+    WeightLimitExceeded,
+    #[numeric_value(4)]
+    OutOfStock,
+    /// The vendor is currently trading with another player.
+    VendorTrading,
+    /// The items of the shop changed while buying.
+    ShopChanged,
+    NoSalesInformation,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for VendingPurchaseResult {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+/// Sent by the map server when buying an item from a vending shop failed.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0135)]
+pub struct VendingPurchaseResultPacket {
+    pub index: InventoryIndex,
+    pub amount: u16,
+    pub result: VendingPurchaseResult,
+}
+
+/// The mailbox a mail is in.
+#[derive(Copy, Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
+#[numeric_type(u8)]
+#[fallback(Unknown)]
+pub enum MailboxType {
+    /// Mails sent to the character.
+    Character,
+    /// Mails sent to any character of the account.
+    Account,
+    /// Mails that were returned to the sender.
+    Returned,
+    /// Used by the client to request mails from all mailboxes.
+    Unset,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for MailboxType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+/// Sent by the client to open the mailbox. The server responds with a
+/// [`MailListPacket`].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0AC0)]
+pub struct OpenMailboxPacket {
+    pub mailbox_type: MailboxType,
+    /// The id of the newest mail that is already known to the client, or 0
+    /// to request all mails.
+    pub mail_id: MailId,
+    #[new_value([0; 15])]
+    pub unknown: [u8; 15],
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x09E9)]
+pub struct CloseMailboxPacket {}
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct MailInformation {
+    pub mailbox_type: MailboxType,
+    pub mail_id: MailId,
+    pub is_read: u8,
+    pub flags: MailFlags,
+    #[length(24)]
+    pub sender_name: String,
+    pub received_time: u32,
+    pub expiration_time: u32,
+    pub title_length: u16,
+    #[length(title_length)]
+    pub title: String,
+}
+
+/// Sent by the map server with a page of the mails inside the mailbox.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0AC2)]
+#[variable_length]
+pub struct MailListPacket {
+    /// Set on the last page of the mail list.
+    pub is_end: u8,
+    #[length_remaining]
+    pub mails: Vec<MailInformation>,
+}
+
+/// Sent by the client to read a mail. The server responds with a
+/// [`MailContentPacket`].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x09EA)]
+pub struct ReadMailPacket {
+    pub mailbox_type: MailboxType,
+    pub mail_id: MailId,
+}
+
+/// An item attached to a mail.
+#[derive(Debug, Clone, FixedByteSize, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct MailItemInformation {
+    pub amount: u16,
+    pub item_id: ItemId,
+    pub is_identified: u8,
+    pub is_broken: u8,
+    pub refinement_level: u8,
+    pub cards: [u32; 4],
+    pub location: u32,
+    pub item_type: ItemType,
+    pub view_sprite: u16,
+    pub bind_on_equip_type: u16,
+    pub option_data: [ItemOptions; 5], // fix count
+    pub grade: u8,
+}
+
+/// Sent by the map server with the text and the attachments of a mail.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x09EB)]
+#[variable_length]
+pub struct MailContentPacket {
+    pub mailbox_type: MailboxType,
+    pub mail_id: MailId,
+    pub text_length: u16,
+    pub zeny: u64,
+    pub item_count: u8,
+    #[length(text_length)]
+    pub text: String,
+    #[repeating(item_count)]
+    pub items: Vec<MailItemInformation>,
+}
+
+/// Sent by the client to send a mail. Items that should be attached need to
+/// be added to the mail beforehand. The server responds with a
+/// [`SendMailResultPacket`].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x09EC)]
+#[variable_length]
+pub struct SendMailPacket {
+    #[length(24)]
+    pub receiver_name: String,
+    #[length(24)]
+    pub sender_name: String,
+    pub zeny: u64,
+    // The lengths include the NUL terminator.
+    #[new_value((title.len() + 1) as u16)]
+    pub title_length: u16,
+    #[new_value((text.len() + 1) as u16)]
+    pub text_length: u16,
+    pub receiver_id: CharacterId,
+    #[length(title_length)]
+    pub title: String,
+    #[length(text_length)]
+    pub text: String,
+}
+
+#[derive(Copy, Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
+#[numeric_type(u8)]
+#[fallback(Unknown)]
+pub enum SendMailResult {
+    Success,
+    Failed,
+    /// Too many mails were sent today.
+    TooManyMails,
+    /// The attached items couldn't be sent.
+    InvalidItems,
+    InvalidReceiver,
+    Unknown(u8),
+}
+
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for SendMailResult {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+/// Sent by the map server as a response to [`SendMailPacket`].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x09ED)]
+pub struct SendMailResultPacket {
+    pub result: SendMailResult,
+}
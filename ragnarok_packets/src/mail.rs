@@ -0,0 +1,173 @@
+use ragnarok_bytes::{ByteConvertable, ByteReader, ConversionResult, FixedByteSize, FromBytes, ToBytes};
+
+#[derive(Clone, Copy, Debug, ByteConvertable, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct MailId(pub u64);
+
+bitflags::bitflags! {
+    /// What a mail contains, so the mail list can show it without reading the
+    /// mail.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    pub struct MailFlags: u8 {
+        const TEXT = 0b0001;
+        const ZENY = 0b0010;
+        const ITEM = 0b0100;
+        /// The mail was sent by an NPC.
+        const NPC = 0b1000;
+    }
+}
+
+impl FixedByteSize for MailFlags {
+    fn size_in_bytes() -> usize {
+        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
+    }
+}
+
+impl FromBytes for MailFlags {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        <Self as bitflags::Flags>::Bits::from_bytes(byte_reader).map(Self::from_bits_retain)
+    }
+}
+
+impl ToBytes for MailFlags {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.bits().to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::fixture::{padded, with_header, EMPTY_CARDS, EMPTY_OPTION_DATA};
+    use crate::{
+        CharacterId, ItemId, ItemType, MailContentPacket, MailFlags, MailId, MailListPacket, MailboxType, OpenMailboxPacket, PacketExt,
+        ReadMailPacket, SendMailPacket, SendMailResult, SendMailResultPacket,
+    };
+
+    fn mail_information(bytes: &mut Vec<u8>, mail_id: u64, flags: u8, title: &[u8]) {
+        bytes.push(0x00); // mailbox type
+        bytes.extend_from_slice(&mail_id.to_le_bytes());
+        bytes.push(0x00); // is read
+        bytes.push(flags);
+        bytes.extend_from_slice(&padded::<24>(b"Alice")); // sender name
+        bytes.extend_from_slice(&60u32.to_le_bytes()); // received time
+        bytes.extend_from_slice(&86400u32.to_le_bytes()); // expiration time
+        bytes.extend_from_slice(&(title.len() as u16 + 1).to_le_bytes());
+        bytes.extend_from_slice(title);
+        bytes.push(0x00);
+    }
+
+    #[test]
+    fn open_mailbox() {
+        let packet = OpenMailboxPacket::new(MailboxType::Unset, MailId(0));
+
+        let mut expected = vec![0xC0, 0x0A, 0x03];
+        expected.extend_from_slice(&[0; 23]);
+        assert_eq!(packet.packet_to_bytes().unwrap(), expected);
+    }
+
+    #[test]
+    fn mail_list() {
+        let mut payload = vec![0x01]; // is end
+        mail_information(&mut payload, 7, 0b0101, b"Hello");
+        mail_information(&mut payload, 8, 0b1011, b"Your reward");
+
+        let bytes = with_header(0x0AC2, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = MailListPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.is_end, 1);
+        assert_eq!(packet.mails.len(), 2);
+        assert_eq!(packet.mails[0].mail_id, MailId(7));
+        assert_eq!(packet.mails[0].sender_name, "Alice");
+        assert_eq!(packet.mails[0].title, "Hello");
+        assert_eq!(packet.mails[0].flags, MailFlags::TEXT | MailFlags::ITEM);
+        assert_eq!(packet.mails[1].mail_id, MailId(8));
+        assert_eq!(packet.mails[1].title, "Your reward");
+        assert_eq!(packet.mails[1].flags, MailFlags::TEXT | MailFlags::ZENY | MailFlags::NPC);
+    }
+
+    #[test]
+    fn read_mail() {
+        let packet = ReadMailPacket::new(MailboxType::Character, MailId(7));
+
+        assert_eq!(packet.packet_to_bytes().unwrap(), [
+            0xEA, 0x09, 0x00, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]);
+    }
+
+    #[test]
+    fn mail_content() {
+        let text = b"Have a potion";
+
+        let mut payload = vec![0x00]; // mailbox type
+        payload.extend_from_slice(&7u64.to_le_bytes()); // mail id
+        payload.extend_from_slice(&(text.len() as u16 + 1).to_le_bytes());
+        payload.extend_from_slice(&1000u64.to_le_bytes()); // zeny
+        payload.push(0x01); // item count
+        payload.extend_from_slice(text);
+        payload.push(0x00);
+        payload.extend_from_slice(&3u16.to_le_bytes()); // amount
+        payload.extend_from_slice(&501u32.to_le_bytes()); // item id
+        payload.extend_from_slice(&[0x01, 0x00, 0x00]); // identified, broken and refinement level
+        payload.extend_from_slice(&EMPTY_CARDS);
+        payload.extend_from_slice(&[0; 4]); // location
+        payload.push(0x00); // item type
+        payload.extend_from_slice(&[0; 4]); // view sprite and bind on equip type
+        payload.extend_from_slice(&EMPTY_OPTION_DATA);
+        payload.push(0x00); // grade
+
+        let bytes = with_header(0x09EB, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = MailContentPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.mail_id, MailId(7));
+        assert_eq!(packet.text, "Have a potion");
+        assert_eq!(packet.zeny, 1000);
+        assert_eq!(packet.items.len(), 1);
+        assert_eq!(packet.items[0].amount, 3);
+        assert_eq!(packet.items[0].item_id, ItemId(501));
+        assert_eq!(packet.items[0].item_type, ItemType::Healing);
+    }
+
+    #[test]
+    fn send_mail() {
+        let packet = SendMailPacket::new(
+            "Bob".to_string(),
+            "Alice".to_string(),
+            500,
+            CharacterId(150001),
+            "Hi".to_string(),
+            "Hello Bob".to_string(),
+        );
+        let bytes = packet.packet_to_bytes().unwrap();
+
+        assert_eq!(packet.title_length, 3);
+        assert_eq!(packet.text_length, 10);
+        assert_eq!(bytes.len(), 81);
+        assert_eq!(bytes[2..4], [81, 0]);
+        assert_eq!(bytes[68..], *b"Hi\0Hello Bob\0");
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let parsed = SendMailPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(parsed.receiver_name, "Bob");
+        assert_eq!(parsed.receiver_id, CharacterId(150001));
+        assert_eq!(parsed.title, "Hi");
+        assert_eq!(parsed.text, "Hello Bob");
+    }
+
+    #[test]
+    fn send_mail_result() {
+        let bytes = [0xED, 0x09, 0x04];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = SendMailResultPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!(packet.result, SendMailResult::InvalidReceiver);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::PartyMemberInformation;
+
+impl PartyMemberInformation {
+    /// Returns `true` if the member is the leader of the party.
+    pub fn is_leader(&self) -> bool {
+        self.role == 0
+    }
+
+    /// Returns `true` if the member is currently logged in.
+    pub fn is_online(&self) -> bool {
+        self.offline == 0
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::fixture::{padded, with_header};
+    use crate::{
+        AccountId, CharacterId, PacketExt, PartyInformationPacket, PartyMemberHealthPacket, PartyMemberLeftPacket,
+        PartyMemberPositionPacket, TilePosition,
+    };
+
+    fn member(bytes: &mut Vec<u8>, account_id: u32, name: &[u8], role: u8, offline: u8) {
+        bytes.extend_from_slice(&account_id.to_le_bytes());
+        bytes.extend_from_slice(&(account_id + 100000).to_le_bytes());
+        bytes.extend_from_slice(&padded::<24>(name));
+        bytes.extend_from_slice(&padded::<16>(b"prontera.gat"));
+        bytes.push(role);
+        bytes.push(offline);
+        bytes.extend_from_slice(&4001u16.to_le_bytes());
+        bytes.extend_from_slice(&99u16.to_le_bytes());
+    }
+
+    #[test]
+    fn party_information() {
+        let mut payload = padded::<24>(b"Adventurers").to_vec();
+        member(&mut payload, 2000000, b"Alice", 0, 0);
+        member(&mut payload, 2000001, b"Bob", 1, 1);
+
+        let bytes = with_header(0x0AE5, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = PartyInformationPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.party_name, "Adventurers");
+        assert_eq!(packet.members.len(), 2);
+
+        assert_eq!(packet.members[0].account_id, AccountId(2000000));
+        assert_eq!(packet.members[0].character_id, CharacterId(2100000));
+        assert_eq!(packet.members[0].name, "Alice");
+        assert_eq!(packet.members[0].map_name, "prontera.gat");
+        assert_eq!(packet.members[0].job, 4001);
+        assert_eq!(packet.members[0].base_level, 99);
+        assert!(packet.members[0].is_leader());
+        assert!(packet.members[0].is_online());
+
+        assert_eq!(packet.members[1].name, "Bob");
+        assert!(!packet.members[1].is_leader());
+        assert!(!packet.members[1].is_online());
+    }
+
+    #[test]
+    fn party_member_left() {
+        let mut bytes = vec![0x05, 0x01, 0x80, 0x84, 0x1E, 0x00];
+        bytes.extend_from_slice(&padded::<24>(b"Bob"));
+        bytes.push(1);
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = PartyMemberLeftPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.account_id, AccountId(2000000));
+        assert_eq!(packet.name, "Bob");
+        assert_eq!(packet.reason, 1);
+    }
+
+    #[test]
+    fn party_member_health() {
+        let bytes = [0x0E, 0x08, 0x80, 0x84, 0x1E, 0x00, 0x84, 0x03, 0x00, 0x00, 0xE8, 0x03, 0x00, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = PartyMemberHealthPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.account_id, AccountId(2000000));
+        assert_eq!(packet.health_points, 900);
+        assert_eq!(packet.maximum_health_points, 1000);
+    }
+
+    #[test]
+    fn party_member_position() {
+        let bytes = [0x07, 0x01, 0x80, 0x84, 0x1E, 0x00, 0x9C, 0x00, 0x2A, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = PartyMemberPositionPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.account_id, AccountId(2000000));
+        assert_eq!(packet.position, TilePosition { x: 156, y: 42 });
+    }
+}
@@ -0,0 +1,74 @@
+/// Shuffles the digits 0-9 the same way the server does, based on the
+/// pincode seed of the [`LoginPincodePacket`](crate::LoginPincodePacket).
+fn pincode_table(mut pincode_seed: u32) -> [u8; 10] {
+    let mut table = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    for index in 1..table.len() {
+        pincode_seed = pincode_seed.wrapping_mul(0x3498).wrapping_add(0x881234);
+        let position = pincode_seed as usize % (index + 1);
+        table.swap(index, position);
+    }
+
+    table
+}
+
+/// Encrypts a pincode entered by the user, so that it can be sent to the
+/// character server with a
+/// [`PincodeSubmitPacket`](crate::PincodeSubmitPacket).
+///
+/// The server maps every received digit through a table that is shuffled with
+/// the pincode seed, so every digit is replaced by the position of the digit
+/// in that table. Characters that are not digits are left unchanged and will
+/// be rejected by the server.
+pub fn encrypt_pincode(pincode_seed: u32, pincode: &str) -> String {
+    let table = pincode_table(pincode_seed);
+
+    pincode
+        .chars()
+        .map(|character| match character.to_digit(10) {
+            Some(digit) => {
+                let position = table.iter().position(|entry| *entry as u32 == digit).unwrap();
+                char::from(b'0' + position as u8)
+            }
+            None => character,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod encryption {
+    use super::{encrypt_pincode, pincode_table};
+
+    /// The decryption as done by rAthena.
+    fn decrypt_pincode(pincode_seed: u32, pincode: &str) -> String {
+        let table = pincode_table(pincode_seed);
+
+        pincode
+            .chars()
+            .map(|character| char::from(b'0' + table[character.to_digit(10).unwrap() as usize]))
+            .collect()
+    }
+
+    #[test]
+    fn table_is_permutation() {
+        let mut table = pincode_table(0xDEADBEEF);
+        table.sort_unstable();
+
+        assert_eq!(table, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn encrypted_pincode_decrypts() {
+        for pincode_seed in [0, 1, 12345, 0x881234, u32::MAX] {
+            for pincode in ["0000", "1234", "9876", "5050"] {
+                let encrypted = encrypt_pincode(pincode_seed, pincode);
+                assert_eq!(decrypt_pincode(pincode_seed, &encrypted), pincode);
+            }
+        }
+    }
+
+    #[test]
+    fn non_digits_are_unchanged() {
+        assert_eq!(encrypt_pincode(42, "a-b"), "a-b");
+    }
+}
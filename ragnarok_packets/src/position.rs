@@ -1,4 +1,6 @@
-use ragnarok_bytes::{ByteConvertable, ByteReader, ConversionResult, FromBytes, ToBytes};
+use ragnarok_bytes::{ByteConvertable, ByteReader, ConversionResult, FixedByteSize, FromBytes, ToBytes};
+
+use crate::TilePosition;
 
 #[derive(Debug, Copy, Clone, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
@@ -53,6 +55,18 @@ impl From<[isize; 2]> for Direction {
     }
 }
 
+/// A tile position and a direction, packed into 3 bytes.
+///
+/// Both coordinates are 10 bits wide and the direction takes up the lowest 4
+/// bits, most significant bits first:
+///
+/// ```text
+/// byte:  |    0     |    1     |    2     |
+/// bits:  |xxxxxxxx  |xxyyyyyy  |yyyydddd  |
+/// ```
+///
+/// All supported client versions use this layout, so it is not version
+/// dependent.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct WorldPosition {
@@ -62,6 +76,9 @@ pub struct WorldPosition {
 }
 
 impl WorldPosition {
+    /// Size of the encoded position in bytes.
+    pub const SIZE_IN_BYTES: usize = 3;
+
     pub fn new(x: usize, y: usize, direction: Direction) -> Self {
         Self { x, y, direction }
     }
@@ -73,14 +90,29 @@ impl WorldPosition {
             direction: Direction::N,
         }
     }
+
+    /// Creates a position on the given tile facing north, which is what the
+    /// client sends when requesting to move to a tile.
+    pub fn from_tile(tile_position: TilePosition) -> Self {
+        Self {
+            x: tile_position.x as usize,
+            y: tile_position.y as usize,
+            direction: Direction::N,
+        }
+    }
 }
 
 impl FromBytes for WorldPosition {
     fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
-        let coordinates: Vec<usize> = byte_reader.slice::<Self>(3)?.iter().map(|byte| *byte as usize).collect();
+        let coordinates: Vec<usize> = byte_reader
+            .slice::<Self>(Self::SIZE_IN_BYTES)?
+            .iter()
+            .map(|byte| *byte as usize)
+            .collect();
 
         let x = (coordinates[1] >> 6) | (coordinates[0] << 2);
         let y = (coordinates[2] >> 4) | ((coordinates[1] & 0b111111) << 4);
+        // The server and the client count directions in opposite directions.
         let mut direction = coordinates[2] & 0b1111;
         direction = (8 - direction + 4) & 7;
 
@@ -94,7 +126,7 @@ impl FromBytes for WorldPosition {
 
 impl ToBytes for WorldPosition {
     fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut coordinates = vec![0, 0, 0];
+        let mut coordinates = vec![0; Self::SIZE_IN_BYTES];
         let direction = (8 - usize::from(self.direction) + 4) & 7;
 
         coordinates[0] = (self.x >> 2) as u8;
@@ -105,6 +137,26 @@ impl ToBytes for WorldPosition {
     }
 }
 
+impl FixedByteSize for WorldPosition {
+    fn size_in_bytes() -> usize {
+        Self::SIZE_IN_BYTES
+    }
+}
+
+/// The origin and destination tile of a movement, packed into 6 bytes.
+///
+/// All four coordinates are 10 bits wide, most significant bits first. The
+/// last byte holds the position inside of the origin tile, with the x offset
+/// in the upper and the y offset in the lower 4 bits:
+///
+/// ```text
+/// byte:  |    0     |    1     |    2     |    3     |    4     |    5     |
+/// bits:  |aaaaaaaa  |aabbbbbb  |bbbbcccc  |ccccccdd  |dddddddd  |sssstttt  |
+/// ```
+///
+/// Where `a` is `x1`, `b` is `y1`, `c` is `x2`, `d` is `y2`, `s` is `sub_x`
+/// and `t` is `sub_y`. All supported client versions use this layout, so it
+/// is not version dependent.
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct WorldPosition2 {
@@ -112,17 +164,27 @@ pub struct WorldPosition2 {
     pub y1: usize,
     pub x2: usize,
     pub y2: usize,
-    pub unknown: usize,
+    /// Offset inside of the origin tile, from 0 to 15. rAthena always sends
+    /// 8 (see `clif_move2`), which is the center of the tile.
+    pub sub_x: usize,
+    /// Offset inside of the origin tile, from 0 to 15. rAthena always sends
+    /// 8 (see `clif_move2`), which is the center of the tile.
+    pub sub_y: usize,
 }
 
 impl WorldPosition2 {
+    /// Size of the encoded positions in bytes.
+    pub const SIZE_IN_BYTES: usize = 6;
+
+    /// Creates a movement that starts in the center of the origin tile.
     pub fn new(x1: usize, y1: usize, x2: usize, y2: usize) -> Self {
         Self {
             x1,
             y1,
             x2,
             y2,
-            unknown: 0,
+            sub_x: 8,
+            sub_y: 8,
         }
     }
 
@@ -144,38 +206,74 @@ impl WorldPosition2 {
 
 impl FromBytes for WorldPosition2 {
     fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
-        let coordinates: Vec<usize> = byte_reader.slice::<Self>(6)?.iter().map(|byte| *byte as usize).collect();
+        let coordinates: Vec<usize> = byte_reader
+            .slice::<Self>(Self::SIZE_IN_BYTES)?
+            .iter()
+            .map(|byte| *byte as usize)
+            .collect();
 
         let x1 = (coordinates[1] >> 6) | (coordinates[0] << 2);
         let y1 = (coordinates[2] >> 4) | ((coordinates[1] & 0b111111) << 4);
         let x2 = (coordinates[3] >> 2) | ((coordinates[2] & 0b1111) << 6);
         let y2 = coordinates[4] | ((coordinates[3] & 0b11) << 8);
-        let unknown = coordinates[5];
+        let sub_x = coordinates[5] >> 4;
+        let sub_y = coordinates[5] & 0b1111;
 
-        Ok(Self { x1, y1, x2, y2, unknown })
+        Ok(Self {
+            x1,
+            y1,
+            x2,
+            y2,
+            sub_x,
+            sub_y,
+        })
     }
 }
 
 impl ToBytes for WorldPosition2 {
     fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = vec![0; 6];
+        let mut bytes = vec![0; Self::SIZE_IN_BYTES];
 
         bytes[0] = (self.x1 >> 2) as u8;
-        bytes[1] = ((self.x1 << 6) as u8) | ((self.y1 >> 4) as u8);
-        bytes[2] = ((self.y1 << 4) as u8) | ((self.x2 >> 6) as u8);
-        bytes[3] = ((self.x2 << 2) as u8) | ((self.y2 >> 8) as u8);
+        bytes[1] = ((self.x1 << 6) as u8) | (((self.y1 >> 4) & 0b111111) as u8);
+        bytes[2] = ((self.y1 << 4) as u8) | (((self.x2 >> 6) & 0b1111) as u8);
+        bytes[3] = ((self.x2 << 2) as u8) | (((self.y2 >> 8) & 0b11) as u8);
         bytes[4] = self.y2 as u8;
-        bytes[5] = self.unknown as u8;
+        bytes[5] = ((self.sub_x << 4) as u8) | ((self.sub_y & 0b1111) as u8);
 
         Ok(bytes)
     }
 }
 
+impl FixedByteSize for WorldPosition2 {
+    fn size_in_bytes() -> usize {
+        Self::SIZE_IN_BYTES
+    }
+}
+
 #[cfg(test)]
 mod conversion {
-    use ragnarok_bytes::{FromBytes, ToBytes};
+    use ragnarok_bytes::{FixedByteSize, FromBytes, ToBytes};
 
-    use crate::{WorldPosition, WorldPosition2};
+    use crate::{Direction, TilePosition, WorldPosition, WorldPosition2};
+
+    #[test]
+    fn sizes() {
+        assert_eq!(WorldPosition::size_in_bytes(), 3);
+        assert_eq!(WorldPosition2::size_in_bytes(), 6);
+        assert_eq!(WorldPosition::origin().to_bytes().unwrap().len(), WorldPosition::SIZE_IN_BYTES);
+        assert_eq!(
+            WorldPosition2::new(0, 0, 0, 0).to_bytes().unwrap().len(),
+            WorldPosition2::SIZE_IN_BYTES
+        );
+    }
+
+    #[test]
+    fn world_position_too_short() {
+        let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&[0, 0]);
+
+        assert!(WorldPosition::from_bytes(&mut byte_reader).is_err());
+    }
 
     #[test]
     fn world_position() {
@@ -193,6 +291,42 @@ mod conversion {
         }
     }
 
+    /// Port of the `RBUFPOS` macro that rAthena uses to decode the positions
+    /// sent by the client, so the layout isn't only checked against itself.
+    fn rbufpos(bytes: &[u8]) -> (usize, usize, usize) {
+        let x = ((bytes[0] as usize) << 2) | (bytes[1] as usize >> 6);
+        let y = ((bytes[1] as usize & 0x3F) << 4) | (bytes[2] as usize >> 4);
+        let direction = bytes[2] as usize & 0x0F;
+        (x, y, direction)
+    }
+
+    #[test]
+    fn world_position_from_tile() {
+        let tile_positions = [
+            TilePosition { x: 0, y: 0 },
+            TilePosition { x: 150, y: 100 },
+            TilePosition { x: 1, y: 1022 },
+            TilePosition { x: 1023, y: 1023 },
+        ];
+
+        for tile_position in tile_positions {
+            let bytes = WorldPosition::from_tile(tile_position).to_bytes().unwrap();
+
+            // clif_parse_WalkToXY passes no direction to RBUFPOS, so only the
+            // coordinates are read by the server.
+            let (x, y, _) = rbufpos(&bytes);
+
+            assert_eq!((x, y), (tile_position.x as usize, tile_position.y as usize));
+
+            let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&bytes);
+            let position = WorldPosition::from_bytes(&mut byte_reader).unwrap();
+
+            assert_eq!(position.x, tile_position.x as usize);
+            assert_eq!(position.y, tile_position.y as usize);
+            assert!(matches!(position.direction, Direction::N));
+        }
+    }
+
     #[test]
     fn world_position_2() {
         let cases = [
@@ -213,4 +347,68 @@ mod conversion {
             assert_eq!(case.as_slice(), output.as_slice());
         }
     }
+
+    /// Port of the `WBUFPOS2` macro that rAthena uses to encode movements, so
+    /// the layout isn't only checked against itself.
+    fn wbufpos2(x0: usize, y0: usize, x1: usize, y1: usize, sx0: usize, sy0: usize) -> [u8; 6] {
+        [
+            (x0 >> 2) as u8,
+            ((x0 << 6) | ((y0 >> 4) & 0x3F)) as u8,
+            ((y0 << 4) | ((x1 >> 6) & 0x0F)) as u8,
+            ((x1 << 2) | ((y1 >> 8) & 0x03)) as u8,
+            y1 as u8,
+            ((sx0 << 4) | (sy0 & 0x0F)) as u8,
+        ]
+    }
+
+    #[test]
+    fn world_position_2_coordinates() {
+        // clif_move2 always passes 8 as the sub-cell offsets, the center of the
+        // tile.
+        let bytes = wbufpos2(150, 100, 152, 97, 8, 8);
+        let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&bytes);
+
+        let position = WorldPosition2::from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!((position.x1, position.y1), (150, 100));
+        assert_eq!((position.x2, position.y2), (152, 97));
+        assert_eq!((position.sub_x, position.sub_y), (8, 8));
+        assert_eq!(WorldPosition2::new(150, 100, 152, 97).to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn world_position_2_sub_cell() {
+        let bytes = wbufpos2(0, 0, 0, 0, 3, 12);
+        let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&bytes);
+
+        let position = WorldPosition2::from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!((position.sub_x, position.sub_y), (3, 12));
+    }
+
+    #[test]
+    fn world_position_2_maximum_coordinates() {
+        let position = WorldPosition2::new(1023, 1023, 1023, 1023);
+        let bytes = position.to_bytes().unwrap();
+
+        assert_eq!(bytes, [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x88]);
+
+        let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&bytes);
+        let parsed = WorldPosition2::from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!((parsed.x1, parsed.y1, parsed.x2, parsed.y2), (1023, 1023, 1023, 1023));
+    }
+
+    #[test]
+    fn world_position_2_out_of_range() {
+        // Coordinates that don't fit into 10 bits must not overwrite their
+        // neighbours.
+        let position = WorldPosition2::new(0, 1024 | 5, 0, 0);
+        let bytes = position.to_bytes().unwrap();
+
+        let mut byte_reader = ragnarok_bytes::ByteReader::without_metadata(&bytes);
+        let parsed = WorldPosition2::from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!((parsed.x1, parsed.y1, parsed.x2, parsed.y2), (0, 5, 0, 0));
+    }
 }
@@ -0,0 +1,268 @@
+use ragnarok_bytes::{ByteConvertable, ByteReader, ConversionResult, ConversionResultExt, FromBytes, FromBytesExt, ToBytes, ToBytesExt};
+
+/// A single objective of a [`Quest`]. Fields that are not sent by older
+/// clients are set to 0.
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct QuestDetails {
+    pub hunt_identification: u32,
+    pub objective_type: u32,
+    pub mob_id: u32,
+    pub minimum_level: u16,
+    pub maximum_level: u16,
+    pub kill_count: u16,
+    pub total_count: u16,
+    #[length(24)]
+    pub mob_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct Quest {
+    pub quest_id: u32,
+    pub active: u8,
+    pub remaining_time: u32, // TODO: double check these
+    pub expire_time: u32,    // TODO: double check these
+    pub objective_details: Vec<QuestDetails>,
+}
+
+/// The layout of the quest objectives inside a
+/// [`QuestListPacket`](crate::QuestListPacket). The layout changed over
+/// different client versions, so each layout has its own packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum QuestObjectiveLayout {
+    /// Mob id, kill count, total count and mob name. See
+    /// [`LegacyQuestListPacket`](crate::LegacyQuestListPacket).
+    Legacy,
+    /// Same as [`Legacy`](Self::Legacy) but prefixed with a hunt
+    /// identification. See
+    /// [`HuntingQuestListPacket`](crate::HuntingQuestListPacket).
+    Hunting,
+    /// Full [`QuestDetails`], including the objective type and the level
+    /// range. See [`QuestListPacket`](crate::QuestListPacket).
+    Extended,
+}
+
+impl QuestObjectiveLayout {
+    fn read_objective<Meta>(self, byte_reader: &mut ByteReader<Meta>) -> ConversionResult<QuestDetails> {
+        if self == Self::Extended {
+            return QuestDetails::from_bytes(byte_reader);
+        }
+
+        let hunt_identification = match self {
+            Self::Hunting => u32::from_bytes(byte_reader)?,
+            _ => 0,
+        };
+
+        Ok(QuestDetails {
+            hunt_identification,
+            objective_type: 0,
+            mob_id: u32::from_bytes(byte_reader)?,
+            minimum_level: 0,
+            maximum_level: 0,
+            kill_count: u16::from_bytes(byte_reader)?,
+            total_count: u16::from_bytes(byte_reader)?,
+            mob_name: String::from_n_bytes(byte_reader, 24)?,
+        })
+    }
+
+    fn write_objective(self, objective: &QuestDetails) -> ConversionResult<Vec<u8>> {
+        if self == Self::Extended {
+            return objective.to_bytes();
+        }
+
+        let mut bytes = Vec::new();
+
+        if self == Self::Hunting {
+            bytes.extend(objective.hunt_identification.to_bytes()?);
+        }
+
+        bytes.extend(objective.mob_id.to_bytes()?);
+        bytes.extend(objective.kill_count.to_bytes()?);
+        bytes.extend(objective.total_count.to_bytes()?);
+        bytes.extend(objective.mob_name.to_n_bytes(24)?);
+
+        Ok(bytes)
+    }
+
+    fn read_quests<Meta>(self, byte_reader: &mut ByteReader<Meta>, quest_count: u32) -> ConversionResult<Vec<Quest>> {
+        // TODO: Add check to make sure this allocation is not too big.
+        let mut quests = Vec::with_capacity(quest_count as usize);
+
+        for _ in 0..quest_count {
+            let quest_id = u32::from_bytes(byte_reader)?;
+            let active = u8::from_bytes(byte_reader)?;
+            let remaining_time = u32::from_bytes(byte_reader)?;
+            let expire_time = u32::from_bytes(byte_reader)?;
+            let objective_count = u16::from_bytes(byte_reader)?;
+            let objective_details = (0..objective_count)
+                .map(|_| self.read_objective(byte_reader))
+                .collect::<ConversionResult<_>>()?;
+
+            quests.push(Quest {
+                quest_id,
+                active,
+                remaining_time,
+                expire_time,
+                objective_details,
+            });
+        }
+
+        Ok(quests)
+    }
+}
+
+/// The quests of a [`QuestListPacket`](crate::QuestListPacket), normalized to
+/// a single representation independent of the client version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct QuestList {
+    pub objective_layout: QuestObjectiveLayout,
+    pub quests: Vec<Quest>,
+}
+
+impl QuestList {
+    fn from_bytes_with_layout<Meta>(byte_reader: &mut ByteReader<Meta>, objective_layout: QuestObjectiveLayout) -> ConversionResult<Self> {
+        let quest_count = u32::from_bytes(byte_reader).trace::<Self>()?;
+        let quests = objective_layout.read_quests(byte_reader, quest_count).trace::<Self>()?;
+
+        Ok(Self { objective_layout, quests })
+    }
+}
+
+impl FromBytes for QuestList {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        Self::from_bytes_with_layout(byte_reader, QuestObjectiveLayout::Extended)
+    }
+}
+
+/// A [`QuestList`] with [`QuestObjectiveLayout::Hunting`] objectives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct HuntingQuestList(pub QuestList);
+
+impl FromBytes for HuntingQuestList {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        QuestList::from_bytes_with_layout(byte_reader, QuestObjectiveLayout::Hunting).map(Self)
+    }
+}
+
+impl ToBytes for HuntingQuestList {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.0.to_bytes()
+    }
+}
+
+/// A [`QuestList`] with [`QuestObjectiveLayout::Legacy`] objectives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct LegacyQuestList(pub QuestList);
+
+impl FromBytes for LegacyQuestList {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        QuestList::from_bytes_with_layout(byte_reader, QuestObjectiveLayout::Legacy).map(Self)
+    }
+}
+
+impl ToBytes for LegacyQuestList {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        self.0.to_bytes()
+    }
+}
+
+impl ToBytes for QuestList {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        let mut bytes = (self.quests.len() as u32).to_bytes()?;
+
+        for quest in &self.quests {
+            bytes.extend(quest.quest_id.to_bytes()?);
+            bytes.extend(quest.active.to_bytes()?);
+            bytes.extend(quest.remaining_time.to_bytes()?);
+            bytes.extend(quest.expire_time.to_bytes()?);
+            bytes.extend((quest.objective_details.len() as u16).to_bytes()?);
+
+            for objective in &quest.objective_details {
+                bytes.extend(self.objective_layout.write_objective(objective)?);
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteReader, FromBytes, ToBytes};
+
+    use crate::{HuntingQuestList, LegacyQuestList, Quest, QuestDetails, QuestList, QuestObjectiveLayout};
+
+    fn quest_list(objective_layout: QuestObjectiveLayout) -> QuestList {
+        let hunt_identification = match objective_layout {
+            QuestObjectiveLayout::Legacy => 0,
+            _ => 7,
+        };
+        let (objective_type, minimum_level, maximum_level) = match objective_layout {
+            QuestObjectiveLayout::Extended => (1, 10, 20),
+            _ => (0, 0, 0),
+        };
+
+        QuestList {
+            objective_layout,
+            quests: vec![
+                Quest {
+                    quest_id: 1000,
+                    active: 1,
+                    remaining_time: 60,
+                    expire_time: 120,
+                    objective_details: vec![QuestDetails {
+                        hunt_identification,
+                        objective_type,
+                        mob_id: 1002,
+                        minimum_level,
+                        maximum_level,
+                        kill_count: 3,
+                        total_count: 10,
+                        mob_name: "Poring".to_owned(),
+                    }],
+                },
+                Quest {
+                    quest_id: 1001,
+                    active: 0,
+                    remaining_time: 0,
+                    expire_time: 0,
+                    objective_details: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    fn assert_round_trip<T>(value: T)
+    where
+        T: FromBytes + ToBytes + PartialEq + std::fmt::Debug,
+    {
+        let bytes = value.to_bytes().unwrap();
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let parsed = T::from_bytes(&mut byte_reader).unwrap();
+
+        assert_eq!(parsed, value);
+        assert!(byte_reader.is_empty());
+    }
+
+    #[test]
+    fn quest_list_layouts() {
+        assert_round_trip(quest_list(QuestObjectiveLayout::Extended));
+        assert_round_trip(HuntingQuestList(quest_list(QuestObjectiveLayout::Hunting)));
+        assert_round_trip(LegacyQuestList(quest_list(QuestObjectiveLayout::Legacy)));
+    }
+
+    #[test]
+    fn quest_list_layout_is_not_guessed() {
+        let bytes = quest_list(QuestObjectiveLayout::Legacy).to_bytes().unwrap();
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+
+        assert!(QuestList::from_bytes(&mut byte_reader).is_err());
+    }
+}
@@ -0,0 +1,378 @@
+use ragnarok_bytes::{ByteReader, ConversionError, ConversionResult, ConversionResultExt, FromBytes, ToBytes};
+
+/// Every value that can be set from the server through
+/// [UpdateStatusPacket](crate::UpdateStatusPacket),
+/// [UpdateStatusPacket1](crate::UpdateStatusPacket1),
+/// [UpdateStatusPacket2](crate::UpdateStatusPacket2), and
+/// [UpdateStatusPacket3](crate::UpdateStatusPacket3).
+/// All UpdateStatusPackets do the same, they just have different sizes
+/// correlating to the space the updated value requires.
+///
+/// Every status starts with its id as a `u16`, followed by the value. Most
+/// values are a single number, but the base stats (e.g.
+/// [`Strength`](Self::Strength)) and the trait stats (e.g.
+/// [`Power`](Self::Power)) are sent as a pair of numbers, which is decoded by
+/// [`StatusType::stat_pair_from_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusType {
+    Weight(u32),
+    MaximumWeight(u32),
+    MovementSpeed(u32),
+    BaseLevel(u32),
+    JobLevel(u32),
+    Karma(u32),
+    Manner(u32),
+    StatusPoint(u32),
+    SkillPoint(u32),
+    Hit(u32),
+    Flee1(u32),
+    Flee2(u32),
+    MaximumHealthPoints(u32),
+    MaximumSpellPoints(u32),
+    HealthPoints(u32),
+    SpellPoints(u32),
+    AttackSpeed(u32),
+    Attack1(u32),
+    Defense1(u32),
+    MagicDefense1(u32),
+    Attack2(u32),
+    Defense2(u32),
+    MagicDefense2(u32),
+    Critical(u32),
+    MagicAttack1(u32),
+    MagicAttack2(u32),
+    Zeny(u32),
+    BaseExperience(u64),
+    JobExperience(u64),
+    NextBaseExperience(u64),
+    NextJobExperience(u64),
+    SpUstr(u8),
+    SpUagi(u8),
+    SpUvit(u8),
+    SpUint(u8),
+    SpUdex(u8),
+    SpUluk(u8),
+    Strength(u32, u32),
+    Agility(u32, u32),
+    Vitality(u32, u32),
+    Intelligence(u32, u32),
+    Dexterity(u32, u32),
+    Luck(u32, u32),
+    CartInfo(u16, u32, u32),
+    ActivityPoints(u32),
+    TraitPoint(u32),
+    MaximumActivityPoints(u32),
+    Power(u32, u32),
+    Stamina(u32, u32),
+    Wisdom(u32, u32),
+    Spell(u32, u32),
+    Concentration(u32, u32),
+    Creativity(u32, u32),
+    SpUpow(u8),
+    SpUsta(u8),
+    SpUwis(u8),
+    SpUspl(u8),
+    SpUcon(u8),
+    SpUcrt(u8),
+    PhysicalAttack(u32),
+    SpellMagicAttack(u32),
+    Resistance(u32),
+    MagicResistance(u32),
+    HealingPlus(u32),
+    CriticalDamageRate(u32),
+}
+
+impl StatusType {
+    /// Decode the value of a base or trait stat, which follows the `u16` id of
+    /// the status. The layout is a `u32` base value followed by a `u32` bonus
+    /// value, both little endian, so the whole status takes 10 bytes.
+    pub fn stat_pair_from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<(u32, u32)> {
+        let base = u32::from_bytes(byte_reader).trace::<Self>()?;
+        let bonus = u32::from_bytes(byte_reader).trace::<Self>()?;
+
+        Ok((base, bonus))
+    }
+
+    /// Encode the value of a base or trait stat. This is the inverse of
+    /// [`StatusType::stat_pair_from_bytes`].
+    pub fn stat_pair_to_bytes(base: u32, bonus: u32) -> ConversionResult<Vec<u8>> {
+        let mut bytes = base.to_bytes().trace::<Self>()?;
+        bytes.extend(bonus.to_bytes().trace::<Self>()?);
+        Ok(bytes)
+    }
+}
+
+impl FromBytes for StatusType {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        let status = match u16::from_bytes(byte_reader).trace::<Self>()? {
+            0 => u32::from_bytes(byte_reader).map(Self::MovementSpeed),
+            1 => u64::from_bytes(byte_reader).map(Self::BaseExperience),
+            2 => u64::from_bytes(byte_reader).map(Self::JobExperience),
+            3 => u32::from_bytes(byte_reader).map(Self::Karma),
+            4 => u32::from_bytes(byte_reader).map(Self::Manner),
+            5 => u32::from_bytes(byte_reader).map(Self::HealthPoints),
+            6 => u32::from_bytes(byte_reader).map(Self::MaximumHealthPoints),
+            7 => u32::from_bytes(byte_reader).map(Self::SpellPoints),
+            8 => u32::from_bytes(byte_reader).map(Self::MaximumSpellPoints),
+            9 => u32::from_bytes(byte_reader).map(Self::StatusPoint),
+            11 => u32::from_bytes(byte_reader).map(Self::BaseLevel),
+            12 => u32::from_bytes(byte_reader).map(Self::SkillPoint),
+            13 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Strength(base, bonus)),
+            14 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Agility(base, bonus)),
+            15 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Vitality(base, bonus)),
+            16 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Intelligence(base, bonus)),
+            17 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Dexterity(base, bonus)),
+            18 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Luck(base, bonus)),
+            20 => u32::from_bytes(byte_reader).map(Self::Zeny),
+            22 => u64::from_bytes(byte_reader).map(Self::NextBaseExperience),
+            23 => u64::from_bytes(byte_reader).map(Self::NextJobExperience),
+            24 => u32::from_bytes(byte_reader).map(Self::Weight),
+            25 => u32::from_bytes(byte_reader).map(Self::MaximumWeight),
+            32 => u8::from_bytes(byte_reader).map(Self::SpUstr),
+            33 => u8::from_bytes(byte_reader).map(Self::SpUagi),
+            34 => u8::from_bytes(byte_reader).map(Self::SpUvit),
+            35 => u8::from_bytes(byte_reader).map(Self::SpUint),
+            36 => u8::from_bytes(byte_reader).map(Self::SpUdex),
+            37 => u8::from_bytes(byte_reader).map(Self::SpUluk),
+            41 => u32::from_bytes(byte_reader).map(Self::Attack1),
+            42 => u32::from_bytes(byte_reader).map(Self::Attack2),
+            43 => u32::from_bytes(byte_reader).map(Self::MagicAttack1),
+            44 => u32::from_bytes(byte_reader).map(Self::MagicAttack2),
+            45 => u32::from_bytes(byte_reader).map(Self::Defense1),
+            46 => u32::from_bytes(byte_reader).map(Self::Defense2),
+            47 => u32::from_bytes(byte_reader).map(Self::MagicDefense1),
+            48 => u32::from_bytes(byte_reader).map(Self::MagicDefense2),
+            49 => u32::from_bytes(byte_reader).map(Self::Hit),
+            50 => u32::from_bytes(byte_reader).map(Self::Flee1),
+            51 => u32::from_bytes(byte_reader).map(Self::Flee2),
+            52 => u32::from_bytes(byte_reader).map(Self::Critical),
+            53 => u32::from_bytes(byte_reader).map(Self::AttackSpeed),
+            55 => u32::from_bytes(byte_reader).map(Self::JobLevel),
+            99 => u16::from_bytes(byte_reader)
+                .and_then(|a| Ok(Self::CartInfo(a, u32::from_bytes(byte_reader)?, u32::from_bytes(byte_reader)?))),
+            219 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Power(base, bonus)),
+            220 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Stamina(base, bonus)),
+            221 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Wisdom(base, bonus)),
+            222 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Spell(base, bonus)),
+            223 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Concentration(base, bonus)),
+            224 => Self::stat_pair_from_bytes(byte_reader).map(|(base, bonus)| Self::Creativity(base, bonus)),
+            225 => u32::from_bytes(byte_reader).map(Self::PhysicalAttack),
+            226 => u32::from_bytes(byte_reader).map(Self::SpellMagicAttack),
+            227 => u32::from_bytes(byte_reader).map(Self::Resistance),
+            228 => u32::from_bytes(byte_reader).map(Self::MagicResistance),
+            229 => u32::from_bytes(byte_reader).map(Self::HealingPlus),
+            230 => u32::from_bytes(byte_reader).map(Self::CriticalDamageRate),
+            231 => u32::from_bytes(byte_reader).map(Self::TraitPoint),
+            232 => u32::from_bytes(byte_reader).map(Self::ActivityPoints),
+            233 => u32::from_bytes(byte_reader).map(Self::MaximumActivityPoints),
+            247 => u8::from_bytes(byte_reader).map(Self::SpUpow),
+            248 => u8::from_bytes(byte_reader).map(Self::SpUsta),
+            249 => u8::from_bytes(byte_reader).map(Self::SpUwis),
+            250 => u8::from_bytes(byte_reader).map(Self::SpUspl),
+            251 => u8::from_bytes(byte_reader).map(Self::SpUcon),
+            252 => u8::from_bytes(byte_reader).map(Self::SpUcrt),
+            invalid => Err(ConversionError::from_message(format!("invalid status code {invalid}"))),
+        };
+
+        status.trace::<Self>()
+    }
+}
+
+impl ToBytes for StatusType {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        let (status_id, value): (u16, Vec<u8>) = match self {
+            Self::MovementSpeed(value) => (0, value.to_bytes()?),
+            Self::BaseExperience(value) => (1, value.to_bytes()?),
+            Self::JobExperience(value) => (2, value.to_bytes()?),
+            Self::Karma(value) => (3, value.to_bytes()?),
+            Self::Manner(value) => (4, value.to_bytes()?),
+            Self::HealthPoints(value) => (5, value.to_bytes()?),
+            Self::MaximumHealthPoints(value) => (6, value.to_bytes()?),
+            Self::SpellPoints(value) => (7, value.to_bytes()?),
+            Self::MaximumSpellPoints(value) => (8, value.to_bytes()?),
+            Self::StatusPoint(value) => (9, value.to_bytes()?),
+            Self::BaseLevel(value) => (11, value.to_bytes()?),
+            Self::SkillPoint(value) => (12, value.to_bytes()?),
+            Self::Strength(base, bonus) => (13, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Agility(base, bonus) => (14, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Vitality(base, bonus) => (15, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Intelligence(base, bonus) => (16, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Dexterity(base, bonus) => (17, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Luck(base, bonus) => (18, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Zeny(value) => (20, value.to_bytes()?),
+            Self::NextBaseExperience(value) => (22, value.to_bytes()?),
+            Self::NextJobExperience(value) => (23, value.to_bytes()?),
+            Self::Weight(value) => (24, value.to_bytes()?),
+            Self::MaximumWeight(value) => (25, value.to_bytes()?),
+            Self::SpUstr(value) => (32, value.to_bytes()?),
+            Self::SpUagi(value) => (33, value.to_bytes()?),
+            Self::SpUvit(value) => (34, value.to_bytes()?),
+            Self::SpUint(value) => (35, value.to_bytes()?),
+            Self::SpUdex(value) => (36, value.to_bytes()?),
+            Self::SpUluk(value) => (37, value.to_bytes()?),
+            Self::Attack1(value) => (41, value.to_bytes()?),
+            Self::Attack2(value) => (42, value.to_bytes()?),
+            Self::MagicAttack1(value) => (43, value.to_bytes()?),
+            Self::MagicAttack2(value) => (44, value.to_bytes()?),
+            Self::Defense1(value) => (45, value.to_bytes()?),
+            Self::Defense2(value) => (46, value.to_bytes()?),
+            Self::MagicDefense1(value) => (47, value.to_bytes()?),
+            Self::MagicDefense2(value) => (48, value.to_bytes()?),
+            Self::Hit(value) => (49, value.to_bytes()?),
+            Self::Flee1(value) => (50, value.to_bytes()?),
+            Self::Flee2(value) => (51, value.to_bytes()?),
+            Self::Critical(value) => (52, value.to_bytes()?),
+            Self::AttackSpeed(value) => (53, value.to_bytes()?),
+            Self::JobLevel(value) => (55, value.to_bytes()?),
+            Self::CartInfo(a, b, c) => (99, [a.to_bytes()?, b.to_bytes()?, c.to_bytes()?].concat()),
+            Self::Power(base, bonus) => (219, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Stamina(base, bonus) => (220, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Wisdom(base, bonus) => (221, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Spell(base, bonus) => (222, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Concentration(base, bonus) => (223, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::Creativity(base, bonus) => (224, Self::stat_pair_to_bytes(*base, *bonus)?),
+            Self::PhysicalAttack(value) => (225, value.to_bytes()?),
+            Self::SpellMagicAttack(value) => (226, value.to_bytes()?),
+            Self::Resistance(value) => (227, value.to_bytes()?),
+            Self::MagicResistance(value) => (228, value.to_bytes()?),
+            Self::HealingPlus(value) => (229, value.to_bytes()?),
+            Self::CriticalDamageRate(value) => (230, value.to_bytes()?),
+            Self::TraitPoint(value) => (231, value.to_bytes()?),
+            Self::ActivityPoints(value) => (232, value.to_bytes()?),
+            Self::MaximumActivityPoints(value) => (233, value.to_bytes()?),
+            Self::SpUpow(value) => (247, value.to_bytes()?),
+            Self::SpUsta(value) => (248, value.to_bytes()?),
+            Self::SpUwis(value) => (249, value.to_bytes()?),
+            Self::SpUspl(value) => (250, value.to_bytes()?),
+            Self::SpUcon(value) => (251, value.to_bytes()?),
+            Self::SpUcrt(value) => (252, value.to_bytes()?),
+        };
+
+        let mut bytes = status_id.to_bytes().trace::<Self>()?;
+        bytes.extend(value);
+        Ok(bytes)
+    }
+}
+
+// TODO: make StatusType derivable
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for StatusType {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        format!("{self:?}").to_element(display)
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteReader, FromBytes, ToBytes};
+
+    use crate::{InitialStatusPacket, PacketExt, StatusType};
+
+    fn parse(bytes: &[u8]) -> StatusType {
+        let mut byte_reader = ByteReader::without_metadata(bytes);
+        let status_type = StatusType::from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        status_type
+    }
+
+    #[test]
+    fn initial_status() {
+        // The offsets are the ones written by `clif_initialstatus` in rAthena,
+        // independent of the field order of the packet definition.
+        let mut bytes = [0; 44];
+        let mut write = |offset: usize, value: u16| bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+
+        write(0, 0x00BD);
+        write(2, 48); // status points
+        write(16, 14); // pc_leftside_atk
+        write(20, 1); // pc_rightside_matk
+        write(22, 2); // pc_leftside_matk
+        write(24, 3); // pc_leftside_def
+        write(26, 4); // pc_rightside_def
+        write(28, 5); // pc_leftside_mdef
+        write(30, 6); // pc_rightside_mdef
+        write(40, 150); // attack speed
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = InitialStatusPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.status_points, 48);
+        assert_eq!(packet.left_attack, 14);
+        assert_eq!(packet.right_magic_attack, 1);
+        assert_eq!(packet.left_magic_attack, 2);
+        assert_eq!(packet.left_defense, 3);
+        assert_eq!(packet.right_defense, 4);
+        assert_eq!(packet.left_magic_defense, 5);
+        assert_eq!(packet.right_magic_defense, 6);
+        assert_eq!(packet.attack_speed, 150);
+        assert_eq!(packet.packet_to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn stat_pair_layout() {
+        let bytes = [0x2A, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+
+        assert_eq!(StatusType::stat_pair_from_bytes(&mut byte_reader).unwrap(), (42, 7));
+        assert!(byte_reader.is_empty());
+    }
+
+    #[test]
+    fn strength() {
+        let bytes = [0x0D, 0x00, 0x63, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00];
+        assert_eq!(parse(&bytes), StatusType::Strength(99, 12));
+    }
+
+    #[test]
+    fn agility() {
+        let bytes = [0x0E, 0x00, 0x2C, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(parse(&bytes), StatusType::Agility(300, 0));
+    }
+
+    #[test]
+    fn luck() {
+        let bytes = [0x12, 0x00, 0x01, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00];
+        assert_eq!(parse(&bytes), StatusType::Luck(1, 5));
+    }
+
+    #[test]
+    fn power() {
+        let bytes = [0xDB, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+        assert_eq!(parse(&bytes), StatusType::Power(10, 2));
+    }
+
+    #[test]
+    fn stat_pair_to_bytes() {
+        let bytes = [0x0D, 0x00, 0x63, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00];
+        assert_eq!(StatusType::Strength(99, 12).to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn round_trip() {
+        let status_types = [
+            StatusType::MovementSpeed(150),
+            StatusType::BaseExperience(123456789),
+            StatusType::Zeny(5000),
+            StatusType::SpUagi(3),
+            StatusType::Agility(40, 7),
+            StatusType::CartInfo(12, 300, 8000),
+            StatusType::Creativity(5, 1),
+            StatusType::CriticalDamageRate(20),
+        ];
+
+        for status_type in status_types {
+            let bytes = status_type.to_bytes().unwrap();
+            assert_eq!(parse(&bytes), status_type);
+        }
+    }
+
+    #[test]
+    fn truncated_stat_pair() {
+        let bytes = [0x0D, 0x00, 0x63, 0x00, 0x00, 0x00, 0x0C, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+
+        assert!(StatusType::from_bytes(&mut byte_reader).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use ragnarok_bytes::{ByteReader, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes};
+
+use crate::ItemListIndex;
+
+/// Storage index is always actual index + 1.
+#[derive(Clone, Copy, Debug, FixedByteSize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct StorageIndex(pub u16);
+
+impl FromBytes for StorageIndex {
+    fn from_bytes<Meta>(byte_reader: &mut ByteReader<Meta>) -> ConversionResult<Self> {
+        let raw = u16::from_bytes(byte_reader)?;
+        ItemListIndex(raw)
+            .storage_index()
+            .ok_or_else(|| ConversionError::from_message(format!("invalid storage index {raw}")))
+    }
+}
+
+impl ToBytes for StorageIndex {
+    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+        let raw = self
+            .0
+            .checked_add(1)
+            .ok_or_else(|| ConversionError::from_message(format!("storage index {} is out of range", self.0)))?;
+        u16::to_bytes(&raw)
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteReader, FromBytes, ToBytes};
+
+    use crate::fixture::{with_header, EMPTY_CARDS, EMPTY_OPTION_DATA};
+    use crate::{
+        InventoryIndex, ItemId, ItemType, MoveItemFromStoragePacket, MoveItemToStoragePacket, PacketExt, RegularItemListPacket,
+        StorageIndex, StorageItemAddedPacket, StorageItemRemovedPacket,
+    };
+
+    #[test]
+    fn storage_item_list_index() {
+        let mut payload = vec![0x02]; // inventory type
+        payload.extend_from_slice(&[0x01, 0x00]); // storage index 0
+        payload.extend_from_slice(&501u32.to_le_bytes()); // item id
+        payload.push(0x00); // item type
+        payload.extend_from_slice(&5u16.to_le_bytes()); // amount
+        payload.extend_from_slice(&[0; 4]); // equipped position
+        payload.extend_from_slice(&EMPTY_CARDS);
+        payload.extend_from_slice(&[0; 4]); // hire expiration date
+        payload.push(0x01); // flags
+
+        let bytes = with_header(0x0B09, &payload);
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = RegularItemListPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.inventory_type, 2);
+        assert_eq!(packet.item_information[0].index.storage_index(), Some(StorageIndex(0)));
+        assert_eq!(packet.item_information[0].index.inventory_index(), None);
+    }
+
+    #[test]
+    fn invalid_inventory_index() {
+        let mut byte_reader = ByteReader::without_metadata(&[0x01, 0x00]);
+
+        assert!(InventoryIndex::from_bytes(&mut byte_reader).is_err());
+        assert!(InventoryIndex(u16::MAX).to_bytes().is_err());
+    }
+
+    #[test]
+    fn storage_item_added() {
+        let mut bytes = vec![0x0A, 0x0A, 0x04, 0x00];
+        bytes.extend_from_slice(&10u32.to_le_bytes()); // amount
+        bytes.extend_from_slice(&1201u32.to_le_bytes()); // item id
+        bytes.push(0x05); // item type
+        bytes.extend_from_slice(&[0x01, 0x00, 0x07]); // identified, broken and refinement level
+        bytes.extend_from_slice(&EMPTY_CARDS);
+        bytes.extend_from_slice(&EMPTY_OPTION_DATA);
+        bytes.push(0x00); // grade
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = StorageItemAddedPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.index, StorageIndex(3));
+        assert_eq!(packet.amount, 10);
+        assert_eq!(packet.item_id, ItemId(1201));
+        assert_eq!(packet.item_type, ItemType::Weapon);
+        assert_eq!(packet.refinement_level, 7);
+    }
+
+    #[test]
+    fn storage_item_removed() {
+        let bytes = [0xF6, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = StorageItemRemovedPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.index, StorageIndex(0));
+        assert_eq!(packet.amount, 2);
+    }
+
+    #[test]
+    fn move_items() {
+        let to_storage = MoveItemToStoragePacket::new(InventoryIndex(0), 3);
+        let from_storage = MoveItemFromStoragePacket::new(StorageIndex(0), 3);
+
+        assert_eq!(to_storage.packet_to_bytes().unwrap(), [
+            0x64, 0x03, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00
+        ]);
+        assert_eq!(from_storage.packet_to_bytes().unwrap(), [
+            0x65, 0x03, 0x01, 0x00, 0x03, 0x00, 0x00, 0x00
+        ]);
+    }
+}
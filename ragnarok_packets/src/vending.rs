@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::ByteReader;
+
+    use crate::fixture::{padded, with_header, EMPTY_CARDS, EMPTY_OPTION_DATA};
+    use crate::{
+        AccountId, InventoryIndex, ItemId, ItemType, PacketExt, Price, PurchaseVendingItemsPacket, VendingItemListPacket,
+        VendingPurchaseInformation, VendingPurchaseResult, VendingPurchaseResultPacket, VendingShopOpenedPacket,
+    };
+
+    #[test]
+    fn vending_shop_opened() {
+        let mut bytes = vec![0x31, 0x01, 0x80, 0x84, 0x1E, 0x00];
+        bytes.extend_from_slice(&padded::<80>(b"Cheap pots!"));
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = VendingShopOpenedPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.account_id, AccountId(2000000));
+        assert_eq!(packet.title, "Cheap pots!");
+    }
+
+    #[test]
+    fn vending_item_list() {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&2000000u32.to_le_bytes()); // account id
+        payload.extend_from_slice(&7u32.to_le_bytes()); // vending id
+        payload.extend_from_slice(&1500u32.to_le_bytes()); // price
+        payload.extend_from_slice(&20u16.to_le_bytes()); // amount
+        payload.extend_from_slice(&2u16.to_le_bytes()); // cart index
+        payload.push(0x00); // item type
+        payload.extend_from_slice(&501u32.to_le_bytes()); // item id
+        payload.extend_from_slice(&[0x01, 0x00, 0x00]); // identified, broken and refinement level
+        payload.extend_from_slice(&EMPTY_CARDS);
+        payload.extend_from_slice(&EMPTY_OPTION_DATA);
+        payload.extend_from_slice(&[0; 4]); // location
+        payload.extend_from_slice(&[0; 2]); // view sprite
+        payload.push(0x00); // grade
+
+        let bytes = with_header(0x0800, &payload);
+
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = VendingItemListPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.account_id, AccountId(2000000));
+        assert_eq!(packet.vending_id, 7);
+        assert_eq!(packet.items.len(), 1);
+        assert_eq!(packet.items[0].price, Price(1500));
+        assert_eq!(packet.items[0].amount, 20);
+        assert_eq!(packet.items[0].index, InventoryIndex(0));
+        assert_eq!(packet.items[0].item_type, ItemType::Healing);
+        assert_eq!(packet.items[0].item_id, ItemId(501));
+    }
+
+    #[test]
+    fn purchase_vending_items() {
+        let packet = PurchaseVendingItemsPacket::new(AccountId(2000000), 7, vec![VendingPurchaseInformation {
+            amount: 5,
+            index: InventoryIndex(0),
+        }]);
+
+        assert_eq!(packet.packet_to_bytes().unwrap(), [
+            0x01, 0x08, 0x10, 0x00, 0x80, 0x84, 0x1E, 0x00, 0x07, 0x00, 0x00, 0x00, 0x05, 0x00, 0x02, 0x00
+        ]);
+    }
+
+    #[test]
+    fn vending_purchase_result() {
+        let bytes = [0x35, 0x01, 0x02, 0x00, 0x05, 0x00, 0x04];
+        let mut byte_reader = ByteReader::without_metadata(&bytes);
+        let packet = VendingPurchaseResultPacket::packet_from_bytes(&mut byte_reader).unwrap();
+
+        assert!(byte_reader.is_empty());
+        assert_eq!(packet.index, InventoryIndex(0));
+        assert_eq!(packet.amount, 5);
+        assert_eq!(packet.result, VendingPurchaseResult::OutOfStock);
+    }
+}
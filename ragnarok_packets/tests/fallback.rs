@@ -0,0 +1,38 @@
+use ragnarok_bytes::{ByteReader, FromBytes, ToBytes};
+use ragnarok_packets::{DamageType, ServerType, SkillFailureCause};
+
+fn decode<T: FromBytes>(bytes: &[u8]) -> T {
+    T::from_bytes(&mut ByteReader::without_metadata(bytes)).unwrap()
+}
+
+#[test]
+fn known_value() {
+    let cause: SkillFailureCause = decode(&[1]);
+
+    assert_eq!(cause, SkillFailureCause::InsufficientSpellPoints);
+    assert_eq!(cause.to_bytes().unwrap(), [1]);
+}
+
+#[test]
+fn unknown_value() {
+    let cause: SkillFailureCause = decode(&[200]);
+
+    assert_eq!(cause, SkillFailureCause::Unknown(200));
+    assert_eq!(cause.to_bytes().unwrap(), [200]);
+}
+
+#[test]
+fn unknown_value_keeps_numeric_type() {
+    let bytes = 300u16.to_le_bytes();
+    let server_type: ServerType = decode(&bytes);
+
+    assert_eq!(server_type, ServerType::Unknown(300));
+    assert_eq!(server_type.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn unknown_damage_type() {
+    let damage_type: DamageType = decode(&[99]);
+
+    assert!(matches!(damage_type, DamageType::Unknown(99)));
+}
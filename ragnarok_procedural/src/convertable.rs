@@ -77,11 +77,24 @@ fn derive_for_enum(
         .map(|attribute| attribute.parse_args().unwrap())
         .unwrap_or_else(|| Ident::new("u8", Span::call_site()));
 
+    let fallback: Option<Ident> = get_unique_attribute(&mut attributes, "fallback")
+        .map(|attribute| attribute.parse_args().expect("fallback requires the name of a variant"));
+
     let mut current_index = 0usize;
     let mut indices = Vec::new();
     let mut values = Vec::new();
+    let mut has_fallback_variant = false;
 
     for mut variant in data_enum.variants.into_iter() {
+        if fallback.as_ref().is_some_and(|fallback| *fallback == variant.ident) {
+            if variant.fields.len() != 1 {
+                panic!("the fallback variant must have exactly one field");
+            }
+
+            has_fallback_variant = true;
+            continue;
+        }
+
         if let Some(attribute) = get_unique_attribute(&mut variant.attrs, "numeric_value") {
             current_index = attribute
                 .parse_args::<syn::LitInt>()
@@ -95,13 +108,28 @@ fn derive_for_enum(
         current_index += 1;
     }
 
+    if let Some(fallback) = fallback.as_ref().filter(|_| !has_fallback_variant) {
+        panic!("fallback variant {fallback} doesn't exist");
+    }
+
+    let invalid_variant = match &fallback {
+        Some(fallback) => quote!(Ok(Self::#fallback(invalid as #numeric_type))),
+        None => quote!(Err(ragnarok_bytes::ConversionError::from_message(format!(
+            "invalid enum variant {}",
+            invalid
+        )))),
+    };
+
+    let fallback_to_bytes =
+        fallback.map(|fallback| quote!(#name::#fallback(value) => ragnarok_bytes::ConversionResultExt::trace::<Self>(value.to_bytes()),));
+
     let from = add_from.then(|| {
         quote! {
             impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
                 fn from_bytes<Meta>(byte_reader: &mut ragnarok_bytes::ByteReader<Meta>) -> ragnarok_bytes::ConversionResult<Self> {
                     match ragnarok_bytes::ConversionResultExt::trace::<Self>(#numeric_type::from_bytes(byte_reader))? as usize {
                         #( #indices => Ok(Self::#values), )*
-                        invalid => Err(ragnarok_bytes::ConversionError::from_message(format!("invalid enum variant {}", invalid))),
+                        invalid => #invalid_variant,
                     }
                 }
             }
@@ -116,6 +144,7 @@ fn derive_for_enum(
                 fn to_bytes(&self) -> ragnarok_bytes::ConversionResult<Vec<u8>> {
                     match self {
                         #( #name::#values => ragnarok_bytes::ConversionResultExt::trace::<Self>((#indices as #numeric_type).to_bytes()), )*
+                        #fallback_to_bytes
                     }
                 }
             }
@@ -35,6 +35,7 @@ pub fn derive_fixed_byte_size(token_stream: InterfaceTokenStream) -> InterfaceTo
 #[proc_macro_derive(
     ByteConvertable,
     attributes(
+        fallback,
         length,
         new_default,
         new_derive,
@@ -68,6 +69,7 @@ pub fn derive_byte_convertable(token_stream: InterfaceTokenStream) -> InterfaceT
 #[proc_macro_derive(
     FromBytes,
     attributes(
+        fallback,
         length,
         numeric_type,
         numeric_value,
@@ -98,6 +100,7 @@ pub fn derive_from_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenSt
 #[proc_macro_derive(
     ToBytes,
     attributes(
+        fallback,
         length,
         new_default,
         new_derive,